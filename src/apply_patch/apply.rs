@@ -0,0 +1,189 @@
+//! Applying hunks to file contents in memory
+//!
+//! Each hunk is looked for at the line its header names, shifted by the
+//! lines earlier hunks added or removed, and then ever further above and
+//! below it, the way `patch` does. If it matches nowhere, up to `fuzz`
+//! context lines are ignored at each end of it and the search repeats.
+//! Lines are compared without their line endings, so LF patches apply to
+//! CRLF files; added lines take the file's line ending.
+
+use super::parse::{FilePatch, Hunk, HunkLine, PatchAction};
+
+/// Result of applying a file's hunks
+#[derive(Debug)]
+pub struct Applied {
+    pub content: String,
+    /// Hunks that applied at a different line or with fuzz
+    pub notes: Vec<String>,
+}
+
+/// Apply `hunks` in order to `content`
+///
+/// # Errors
+/// Returns the (0-based) indices of every hunk that matched nowhere; none
+/// of the hunks are applied then
+pub fn apply_hunks(content: &str, hunks: &[Hunk], fuzz: usize) -> Result<Applied, Vec<usize>> {
+    let ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut trailing_newline = content.is_empty() || content.ends_with('\n');
+
+    let mut notes = Vec::new();
+    let mut failed = Vec::new();
+    // Lines before this index belong to hunks already applied
+    let mut floor = 0;
+    // Lines added minus lines removed by the hunks applied so far
+    let mut delta: isize = 0;
+    // How far the last hunk was from where its header said
+    let mut last_offset: isize = 0;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let Some(found) = locate(&lines, hunk, fuzz, floor, delta + last_offset) else {
+            failed.push(index);
+            continue;
+        };
+
+        let new = hunk.new_lines();
+        let replacement = &new[found.lead..new.len() - found.trail];
+        let end = found.at + found.len;
+        let at_eof = end == lines.len() && found.trail == 0;
+        lines.splice(
+            found.at..end,
+            replacement.iter().map(|line| (*line).to_string()),
+        );
+        if at_eof {
+            if hunk.new_missing_newline {
+                trailing_newline = false;
+            } else if hunk.old_missing_newline {
+                trailing_newline = true;
+            }
+        }
+
+        let offset = found.at as isize - (expected_index(hunk) as isize + delta + found.lead as isize);
+        if offset != 0 || found.fuzz > 0 {
+            let mut note = format!("hunk {} applied at line {}", index + 1, found.at.saturating_sub(found.lead) + 1);
+            let mut details = Vec::new();
+            if offset != 0 {
+                details.push(format!("offset {offset:+}"));
+            }
+            if found.fuzz > 0 {
+                details.push(format!("fuzz {}", found.fuzz));
+            }
+            note.push_str(&format!(" ({})", details.join(", ")));
+            notes.push(note);
+        }
+
+        floor = found.at + replacement.len();
+        delta += replacement.len() as isize - found.len as isize;
+        last_offset = offset;
+    }
+
+    if !failed.is_empty() {
+        return Err(failed);
+    }
+
+    let mut content = lines.join(ending);
+    if trailing_newline && !lines.is_empty() {
+        content.push_str(ending);
+    }
+    Ok(Applied { content, notes })
+}
+
+/// Check that a deletion leaves the file empty once its hunks are applied
+///
+/// Like `git apply`, a patch only deletes a file whose whole content it
+/// removes, so a deletion without hunks only removes an empty file.
+///
+/// # Errors
+/// Returns the failure message when content would be left behind
+pub fn check_deletion(patch: &FilePatch, applied: &Applied) -> Result<(), String> {
+    if patch.action() != PatchAction::Delete || applied.content.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "{}: the patch deletes the file, but {} line(s) of it are not removed by its hunks",
+        patch.path(),
+        applied.content.lines().count()
+    ))
+}
+
+/// Where a hunk matched
+struct Found {
+    /// Index of the first matched line
+    at: usize,
+    /// Number of matched lines
+    len: usize,
+    /// Context lines ignored at the start and end of the hunk
+    lead: usize,
+    trail: usize,
+    fuzz: usize,
+}
+
+/// Index the hunk's old lines start at according to its header
+fn expected_index(hunk: &Hunk) -> usize {
+    if hunk.old_lines().is_empty() {
+        hunk.old_start
+    } else {
+        hunk.old_start.saturating_sub(1)
+    }
+}
+
+/// Find the hunk's old lines at or after `floor`, nearest to where its header
+/// says they are, with as little fuzz as possible
+fn locate(lines: &[String], hunk: &Hunk, fuzz: usize, floor: usize, shift: isize) -> Option<Found> {
+    let old = hunk.old_lines();
+    let leading = hunk
+        .lines
+        .iter()
+        .take_while(|line| matches!(line, HunkLine::Context(_)))
+        .count();
+    let trailing = hunk
+        .lines
+        .iter()
+        .rev()
+        .take_while(|line| matches!(line, HunkLine::Context(_)))
+        .count();
+
+    for level in 0..=fuzz.min(leading.max(trailing)) {
+        let lead = level.min(leading);
+        let trail = level.min(trailing);
+        // Ignoring every line would match anywhere
+        if !old.is_empty() && lead + trail >= old.len() {
+            break;
+        }
+        let pattern = &old[lead..old.len() - trail];
+
+        let expected = (expected_index(hunk) as isize + shift + lead as isize).max(0) as usize;
+        if pattern.is_empty() {
+            let at = expected.clamp(floor, lines.len());
+            return Some(Found { at, len: 0, lead, trail, fuzz: level });
+        }
+
+        let Some(last) = lines.len().checked_sub(pattern.len()).filter(|&last| last >= floor)
+        else {
+            continue;
+        };
+        let expected = expected.clamp(floor, last);
+        let matches_at = |at: usize| {
+            lines[at..at + pattern.len()]
+                .iter()
+                .zip(pattern)
+                .all(|(line, expected)| line == expected)
+        };
+        for distance in 0..=(last - floor) {
+            let below = expected + distance;
+            if below <= last && matches_at(below) {
+                return Some(Found { at: below, len: pattern.len(), lead, trail, fuzz: level });
+            }
+            if let Some(above) = expected.checked_sub(distance).filter(|&at| at >= floor)
+                && distance > 0
+                && matches_at(above)
+            {
+                return Some(Found { at: above, len: pattern.len(), lead, trail, fuzz: level });
+            }
+            if below > last && expected.checked_sub(distance).is_none_or(|at| at < floor) {
+                break;
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,426 @@
+//! `fs_apply_patch`: apply a multi-file unified diff, all files or none
+//!
+//! Every hunk of every file is applied in memory first; if any fails to
+//! match, nothing is written and each failing hunk is reported with the
+//! closest text in its file and the same character-level analysis
+//! `fs_edit_block` gives. Only then are the files written, and a failure
+//! part way through (a full disk, say) rolls back the files already
+//! written.
+
+pub mod apply;
+pub mod parse;
+
+use crate::atomic_write::write_atomic;
+use crate::edit_block::char_analysis::CharCodeData;
+use crate::edit_block::char_diff::CharDiff;
+use crate::edit_block::fuzzy_search::{fuzzy_index_of, get_similarity_ratio};
+use crate::encoding::{self, TextEncoding};
+use crate::journal::Recorder;
+use crate::schema::{DEFAULT_FUZZ, FsApplyPatchArgs, FsApplyPatchOutput, PatchedFile};
+use crate::validate_path;
+use apply::{apply_hunks, check_deletion};
+use kodegen_config::shorten_path_for_display;
+use kodegen_mcp_schema::filesystem::EditBlockPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use parse::{FilePatch, Hunk, HunkLine, PatchAction, parse_patch};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tokio::fs;
+
+#[derive(Clone)]
+pub struct ApplyPatchTool {
+    config_manager: kodegen_config_manager::ConfigManager,
+}
+
+impl ApplyPatchTool {
+    #[must_use]
+    pub fn new(config_manager: kodegen_config_manager::ConfigManager) -> Self {
+        Self { config_manager }
+    }
+}
+
+/// One file's change, checked and ready to write
+struct Planned {
+    patch: FilePatch,
+    /// Validated path before the patch, with its bytes
+    old: Option<(PathBuf, Vec<u8>)>,
+    /// Validated path after the patch, with the bytes to write there
+    new: Option<(PathBuf, Vec<u8>)>,
+    notes: Vec<String>,
+}
+
+/// A write already made, and how to take it back
+enum Done {
+    Wrote {
+        path: PathBuf,
+        original: Option<Vec<u8>>,
+    },
+    Removed {
+        path: PathBuf,
+        original: Vec<u8>,
+    },
+}
+
+impl Tool for ApplyPatchTool {
+    type Args = FsApplyPatchArgs;
+    type Prompts = EditBlockPrompts;
+
+    fn name() -> &'static str {
+        crate::schema::FS_APPLY_PATCH
+    }
+
+    fn description() -> &'static str {
+        "Apply a unified diff (as printed by git diff or diff -u) to one or more files. The \
+         patch may modify, create (--- /dev/null), delete (+++ /dev/null) and rename files \
+         (git's rename from/rename to headers). Every hunk is checked before anything is \
+         written: if one does not apply, no file changes and the error shows each failing hunk \
+         with the closest text in its file and a character-level diff. Hunks are found near \
+         their @@ line even if the file shifted, and up to fuzz context lines (default 2) may be \
+         ignored at each end of a hunk; wrong line counts in @@ headers are recounted. strip \
+         removes leading path components like patch -p (default: 1 for a/ b/ paths). Pass \
+         dry_run: true to check the patch without writing. The response carries an undo_id \
+         for fs_undo covering every file. Far fewer tokens than many fs_edit_block calls. \
+         Automatically validates paths."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true // Modifies, deletes and renames files
+    }
+
+    fn idempotent() -> bool {
+        false // A patch applied twice no longer matches
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let patches = parse_patch(&args.patch, args.strip)?;
+        if patches.is_empty() {
+            return Err(McpError::InvalidArguments(
+                "The patch contains no file changes. Expected a unified diff with \
+                 `--- a/path` / `+++ b/path` headers and @@ hunks."
+                    .to_string(),
+            ));
+        }
+        let fuzz = args.fuzz.unwrap_or(DEFAULT_FUZZ);
+
+        // Check every file and apply every hunk in memory
+        let mut seen = HashSet::new();
+        let mut planned = Vec::with_capacity(patches.len());
+        let mut failures = Vec::new();
+        for patch in patches {
+            let old = match &patch.old_path {
+                Some(path) => Some(self.validate(path, &ctx, &mut seen).await?),
+                None => None,
+            };
+            let new = match &patch.new_path {
+                Some(path) if patch.action() != PatchAction::Modify => {
+                    let valid = self.validate(path, &ctx, &mut seen).await?;
+                    if fs::symlink_metadata(&valid).await.is_ok() {
+                        return Err(McpError::InvalidArguments(format!(
+                            "{path} already exists; the patch {} it",
+                            if patch.action() == PatchAction::Create { "creates" } else { "renames a file to" }
+                        )));
+                    }
+                    Some(valid)
+                }
+                _ => old.clone(),
+            };
+
+            let (content, text_encoding, old) = match old {
+                Some(old) => {
+                    let bytes = fs::read(&old).await.map_err(|e| match e.kind() {
+                        std::io::ErrorKind::NotFound => McpError::ResourceNotFound(format!(
+                            "{} does not exist; the patch {} it",
+                            patch.old_path.as_deref().unwrap_or_default(),
+                            match patch.action() {
+                                PatchAction::Delete => "deletes",
+                                PatchAction::Rename => "renames",
+                                _ => "modifies",
+                            }
+                        )),
+                        _ => McpError::Io(e),
+                    })?;
+                    let text_encoding = encoding::detect(&bytes, None);
+                    let content = encoding::decode(&bytes, text_encoding)?;
+                    (content, text_encoding, Some((old, bytes)))
+                }
+                None => (String::new(), TextEncoding::UTF8, None),
+            };
+
+            let applied = match apply_hunks(&content, &patch.hunks, fuzz) {
+                Ok(applied) => applied,
+                Err(indices) => {
+                    for index in indices {
+                        failures.push(diagnose(&patch, index, &content));
+                    }
+                    continue;
+                }
+            };
+            if let Err(failure) = check_deletion(&patch, &applied) {
+                failures.push(failure);
+                continue;
+            }
+
+            let new = match new {
+                Some(path) => Some((path, encoding::encode(&applied.content, text_encoding)?)),
+                None => None,
+            };
+            planned.push(Planned {
+                patch,
+                old,
+                new,
+                notes: applied.notes,
+            });
+        }
+
+        if !failures.is_empty() {
+            return Err(McpError::InvalidArguments(format!(
+                "The patch does not apply; no files were changed.\n\n{}\n\n💡 Suggestions:\n\
+                 1. Re-read the file and regenerate the hunk from its current content\n\
+                 2. Include a few unchanged lines of context around each change\n\
+                 3. Raise fuzz to ignore more context lines at the ends of a hunk\n",
+                failures.join("\n\n")
+            )));
+        }
+
+        let files: Vec<PatchedFile> = planned.iter().map(report).collect();
+
+        if args.dry_run {
+            let summary = format!(
+                "\x1b[36mDry run: patch applies to {} file(s)\x1b[0m\n{}",
+                files.len(),
+                self.file_lines(&planned, &ctx)
+            );
+            return Ok(ToolResponse::new(summary, FsApplyPatchOutput {
+                success: true,
+                message: format!("Dry run: the patch applies cleanly to {} file(s)", files.len()),
+                files,
+                undo_id: None,
+                dry_run: true,
+            }));
+        }
+
+        // Write everything, rolling back if any write fails
+        let mut undo = Recorder::for_call(
+            &ctx,
+            Self::name(),
+            format!("apply patch to {} file(s)", planned.len()),
+        );
+        for plan in &planned {
+            let mut paths: Vec<&PathBuf> = plan.old.iter().chain(&plan.new).map(|(path, _)| path).collect();
+            paths.dedup();
+            for path in paths {
+                undo.snapshot(path).await;
+            }
+        }
+        let mut done = Vec::new();
+        if let Err(e) = commit(&planned, &mut done).await {
+            rollback(done).await;
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Writing the patch failed ({e}); the files already written were restored"
+            )));
+        }
+        let undo_id = undo.commit().await;
+
+        let summary = format!(
+            "\x1b[33mPatched {} file(s)\x1b[0m\n{}\nUndo: {}",
+            files.len(),
+            self.file_lines(&planned, &ctx),
+            undo_id.as_deref().unwrap_or("not journaled")
+        );
+        Ok(ToolResponse::new(summary, FsApplyPatchOutput {
+            success: true,
+            message: format!("Applied the patch to {} file(s)", files.len()),
+            files,
+            undo_id,
+            dry_run: false,
+        }))
+    }
+}
+
+impl ApplyPatchTool {
+    /// Validate a path from the patch, refusing one the patch names twice
+    async fn validate(
+        &self,
+        path: &str,
+        ctx: &ToolExecutionContext,
+        seen: &mut HashSet<PathBuf>,
+    ) -> Result<PathBuf, McpError> {
+        let valid = validate_path(path, &self.config_manager, ctx.pwd()).await?;
+        if !seen.insert(valid.clone()) {
+            return Err(McpError::InvalidArguments(format!(
+                "The patch changes {path} more than once; combine its hunks into one file section"
+            )));
+        }
+        Ok(valid)
+    }
+
+    /// One summary line per file, e.g. "  M src/lib.rs (+3 -1)"
+    fn file_lines(&self, planned: &[Planned], ctx: &ToolExecutionContext) -> String {
+        planned
+            .iter()
+            .map(|plan| {
+                let (added, removed) = line_counts(&plan.patch.hunks);
+                let display = |path: &PathBuf| shorten_path_for_display(path, ctx.git_root());
+                let (letter, path) = match (&plan.old, &plan.new) {
+                    (Some((old, _)), Some((new, _))) if old != new => {
+                        ('R', format!("{} → {}", display(old), display(new)))
+                    }
+                    (_, Some((new, _))) if plan.old.is_none() => ('A', display(new)),
+                    (Some((old, _)), None) => ('D', display(old)),
+                    (_, Some((new, _))) => ('M', display(new)),
+                    (None, None) => ('?', String::new()),
+                };
+                let mut line = format!("  {letter} {path} (+{added} -{removed})");
+                for note in &plan.notes {
+                    line.push_str(&format!("\n      {note}"));
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The output entry for a planned file
+fn report(plan: &Planned) -> PatchedFile {
+    let (added, removed) = line_counts(&plan.patch.hunks);
+    let display = |path: &PathBuf| path.to_string_lossy().to_string();
+    PatchedFile {
+        path: plan
+            .new
+            .as_ref()
+            .or(plan.old.as_ref())
+            .map(|(path, _)| display(path))
+            .unwrap_or_default(),
+        action: plan.patch.action().as_str().to_string(),
+        from: match plan.patch.action() {
+            PatchAction::Rename => plan.old.as_ref().map(|(path, _)| display(path)),
+            _ => None,
+        },
+        hunks: plan.patch.hunks.len() as u32,
+        lines_added: added as u32,
+        lines_removed: removed as u32,
+        notes: plan.notes.clone(),
+    }
+}
+
+/// Lines added and removed by a file's hunks
+fn line_counts(hunks: &[Hunk]) -> (usize, usize) {
+    hunks
+        .iter()
+        .flat_map(|hunk| &hunk.lines)
+        .fold((0, 0), |(added, removed), line| match line {
+            HunkLine::Add(_) => (added + 1, removed),
+            HunkLine::Remove(_) => (added, removed + 1),
+            HunkLine::Context(_) => (added, removed),
+        })
+}
+
+/// Describe a hunk that matched nowhere, with the closest text in the file
+fn diagnose(patch: &FilePatch, index: usize, content: &str) -> String {
+    let hunk = &patch.hunks[index];
+    let mut message = format!(
+        "{}: hunk {} of {} (patch line {}, {}) does not apply",
+        patch.path(),
+        index + 1,
+        patch.hunks.len(),
+        hunk.patch_line,
+        hunk.header()
+    );
+
+    let expected = hunk.old_lines().join("\n");
+    let haystack = content.replace("\r\n", "\n");
+    if haystack.is_empty() {
+        message.push_str("; the file is empty");
+        return message;
+    }
+
+    let found = fuzzy_index_of(&haystack, &expected, None);
+    let similarity = get_similarity_ratio(&found.value, &expected);
+    let line = haystack[..haystack.floor_char_boundary(found.start)]
+        .matches('\n')
+        .count()
+        + 1;
+    let diff = CharDiff::new(&expected, &found.value);
+    message.push_str(&format!(
+        "\nClosest match at line {line} ({:.0}% similar).\n\nCharacter-level differences:\n{}",
+        similarity * 100.0,
+        diff.format()
+    ));
+    if diff.is_whitespace_only() {
+        message.push_str("\n\nNote: Difference is whitespace only.");
+    }
+    message.push_str("\n\n");
+    message.push_str(&CharCodeData::analyze(&expected, &found.value).format_detailed_report());
+    message
+}
+
+/// Write new and renamed files, then remove deleted and renamed-away ones
+async fn commit(planned: &[Planned], done: &mut Vec<Done>) -> Result<(), McpError> {
+    for plan in planned {
+        let Some((path, bytes)) = &plan.new else {
+            continue;
+        };
+        let original = match &plan.old {
+            Some((old, bytes)) if old == path => Some(bytes.clone()),
+            _ => None,
+        };
+        if original.is_none()
+            && let Some(parent) = path.parent()
+        {
+            fs::create_dir_all(parent).await?;
+        }
+        write_atomic(path, bytes.clone()).await?;
+        done.push(Done::Wrote {
+            path: path.clone(),
+            original,
+        });
+
+        // A renamed file keeps its permissions
+        if let Some((old, _)) = &plan.old
+            && old != path
+        {
+            let permissions = fs::metadata(old).await?.permissions();
+            fs::set_permissions(path, permissions).await?;
+        }
+    }
+
+    for plan in planned {
+        let Some((old, bytes)) = &plan.old else {
+            continue;
+        };
+        if plan.new.as_ref().is_some_and(|(new, _)| new == old) {
+            continue;
+        }
+        fs::remove_file(old).await?;
+        done.push(Done::Removed {
+            path: old.clone(),
+            original: bytes.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Undo the writes `commit` made before it failed, newest first
+async fn rollback(done: Vec<Done>) {
+    for step in done.into_iter().rev() {
+        let result = match step {
+            Done::Wrote {
+                path,
+                original: Some(original),
+            }
+            | Done::Removed { path, original } => write_atomic(&path, original).await,
+            Done::Wrote {
+                path,
+                original: None,
+            } => fs::remove_file(&path).await.map_err(McpError::from),
+        };
+        if let Err(e) = result {
+            log::warn!("Rolling back a failed patch: {e}");
+        }
+    }
+}
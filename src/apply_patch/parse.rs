@@ -0,0 +1,529 @@
+//! Unified diff parsing, including git's extended headers
+//!
+//! Hunks are read by the line counts in their `@@` headers, as `patch` and
+//! `git apply` do. When the counts are wrong, which hand-written and
+//! model-written hunks often get, the hunk is recounted from its body
+//! instead, like `git apply --recount`.
+
+use kodegen_mcp_schema::McpError;
+
+/// One line of a hunk body, without its prefix or line ending
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+/// One `@@` hunk
+#[derive(Debug, Clone)]
+pub struct Hunk {
+    /// First line of the old side (1-based; for an empty old side, the line
+    /// after which the new lines go)
+    pub old_start: usize,
+    /// First line of the new side (1-based)
+    pub new_start: usize,
+    pub lines: Vec<HunkLine>,
+    /// The last old line has no trailing newline (`\ No newline at end of file`)
+    pub old_missing_newline: bool,
+    /// The last new line has no trailing newline
+    pub new_missing_newline: bool,
+    /// Line of the `@@` header within the patch (1-based), for diagnostics
+    pub patch_line: usize,
+}
+
+impl Hunk {
+    /// Lines the hunk expects to find: context and removed lines
+    #[must_use]
+    pub fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    /// Lines the hunk leaves behind: context and added lines
+    #[must_use]
+    pub fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Add(text) => Some(text.as_str()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect()
+    }
+
+    /// The hunk's `@@` header, with recounted line counts
+    #[must_use]
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start,
+            self.old_lines().len(),
+            self.new_start,
+            self.new_lines().len()
+        )
+    }
+}
+
+/// What a file patch does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchAction {
+    Modify,
+    Create,
+    Delete,
+    Rename,
+}
+
+impl PatchAction {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Modify => "modify",
+            Self::Create => "create",
+            Self::Delete => "delete",
+            Self::Rename => "rename",
+        }
+    }
+}
+
+/// The changes a patch makes to one file
+#[derive(Debug, Clone)]
+pub struct FilePatch {
+    /// Path before the patch; `None` for a created file
+    pub old_path: Option<String>,
+    /// Path after the patch; `None` for a deleted file
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    #[must_use]
+    pub fn action(&self) -> PatchAction {
+        match (&self.old_path, &self.new_path) {
+            (None, _) => PatchAction::Create,
+            (_, None) => PatchAction::Delete,
+            (Some(old), Some(new)) if old != new => PatchAction::Rename,
+            _ => PatchAction::Modify,
+        }
+    }
+
+    /// Path the patch is reported under: the new path, or the old one for a
+    /// deletion
+    #[must_use]
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// PARSER
+// ============================================================================
+
+const DEV_NULL: &str = "/dev/null";
+
+/// Headers of one file section, before paths are resolved
+#[derive(Debug, Default)]
+struct Section {
+    /// Line of the section's first header (1-based)
+    line: usize,
+    git_paths: Option<(String, String)>,
+    minus: Option<String>,
+    plus: Option<String>,
+    rename_from: Option<String>,
+    rename_to: Option<String>,
+    new_file: bool,
+    deleted_file: bool,
+    hunks: Vec<Hunk>,
+}
+
+/// Parse a unified diff into per-file patches
+///
+/// `strip` removes leading path components like `patch -p`; by default one
+/// for git-style `a/` and `b/` paths and none otherwise. Text outside file
+/// sections (a commit message, say) is ignored, as are mode-only changes.
+///
+/// # Errors
+/// Returns `InvalidArguments` naming the patch line for malformed hunks,
+/// binary patches, and paths with fewer components than `strip`
+pub fn parse_patch(text: &str, strip: Option<usize>) -> Result<Vec<FilePatch>, McpError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            sections.push(Section {
+                line: i + 1,
+                git_paths: split_git_paths(rest),
+                ..Section::default()
+            });
+            i += 1;
+            continue;
+        }
+
+        if is_file_header(&lines, i) {
+            // Starts a new section unless it belongs to the `diff --git` line above
+            let continues_git_section = sections
+                .last()
+                .is_some_and(|s| s.git_paths.is_some() && s.minus.is_none() && s.hunks.is_empty());
+            if !continues_git_section {
+                sections.push(Section {
+                    line: i + 1,
+                    ..Section::default()
+                });
+            }
+            let section = sections.last_mut().expect("section was just ensured");
+            section.minus = Some(header_path(&line[4..]));
+            section.plus = Some(header_path(&lines[i + 1][4..]));
+            i += 2;
+            continue;
+        }
+
+        if line.starts_with("@@ ") {
+            let Some(section) = sections.last_mut() else {
+                return Err(McpError::InvalidArguments(format!(
+                    "Patch line {}: hunk comes before any `---`/`+++` file header",
+                    i + 1
+                )));
+            };
+            let (hunk, next) = parse_hunk(&lines, i)?;
+            section.hunks.push(hunk);
+            i = next;
+            continue;
+        }
+
+        if let Some(section) = sections
+            .last_mut()
+            .filter(|s| s.minus.is_none() && s.hunks.is_empty())
+        {
+            if let Some(path) = line.strip_prefix("rename from ") {
+                section.rename_from = Some(unquote(path));
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                section.rename_to = Some(unquote(path));
+            } else if line.starts_with("new file mode ") {
+                section.new_file = true;
+            } else if line.starts_with("deleted file mode ") {
+                section.deleted_file = true;
+            } else if line == "GIT binary patch" || line.starts_with("Binary files ") {
+                return Err(McpError::InvalidArguments(format!(
+                    "Patch line {}: binary patches are not supported",
+                    i + 1
+                )));
+            }
+        }
+        i += 1;
+    }
+
+    sections
+        .into_iter()
+        .filter_map(|section| resolve(section, strip).transpose())
+        .collect()
+}
+
+/// Turn a section's headers into paths; `None` for sections that change
+/// nothing this tool applies (mode changes)
+fn resolve(section: Section, strip: Option<usize>) -> Result<Option<FilePatch>, McpError> {
+    let git_style = section.git_paths.is_some()
+        || (section
+            .minus
+            .as_deref()
+            .is_some_and(|p| p.starts_with("a/"))
+            && section.plus.as_deref().is_some_and(|p| p.starts_with("b/")));
+    let strip = strip.unwrap_or(usize::from(git_style));
+    let stripped = |path: &str| {
+        strip_components(path, strip).ok_or_else(|| {
+            McpError::InvalidArguments(format!(
+                "Patch line {}: cannot strip {strip} leading component(s) from {path:?}",
+                section.line
+            ))
+        })
+    };
+    let (git_old, git_new) = section.git_paths.clone().unzip();
+
+    let old_path = if section.new_file || section.minus.as_deref() == Some(DEV_NULL) {
+        None
+    } else if let Some(path) = section.rename_from {
+        Some(path)
+    } else {
+        section
+            .minus
+            .as_deref()
+            .or(git_old.as_deref())
+            .map(stripped)
+            .transpose()?
+    };
+    let new_path = if section.deleted_file || section.plus.as_deref() == Some(DEV_NULL) {
+        None
+    } else if let Some(path) = section.rename_to {
+        Some(path)
+    } else {
+        section
+            .plus
+            .as_deref()
+            .or(git_new.as_deref())
+            .map(stripped)
+            .transpose()?
+    };
+
+    let patch = FilePatch {
+        old_path,
+        new_path,
+        hunks: section.hunks,
+    };
+    match patch.action() {
+        _ if patch.old_path.is_none() && patch.new_path.is_none() => {
+            Err(McpError::InvalidArguments(format!(
+                "Patch line {}: file section has no path",
+                section.line
+            )))
+        }
+        PatchAction::Modify if patch.hunks.is_empty() => Ok(None),
+        _ => Ok(Some(patch)),
+    }
+}
+
+/// A `--- ` line directly followed by a `+++ ` line
+fn is_file_header(lines: &[&str], i: usize) -> bool {
+    lines[i].starts_with("--- ") && lines.get(i + 1).is_some_and(|l| l.starts_with("+++ "))
+}
+
+/// Parse the hunk whose `@@` header is at `start`; returns it and the index
+/// of the line after it
+fn parse_hunk(lines: &[&str], start: usize) -> Result<(Hunk, usize), McpError> {
+    let header = lines[start];
+    let invalid = || {
+        McpError::InvalidArguments(format!(
+            "Patch line {}: invalid hunk header {header:?} (expected \"@@ -l,s +l,s @@\")",
+            start + 1
+        ))
+    };
+    let ranges = header[3..].split(" @@").next().ok_or_else(invalid)?;
+    let (old, new) = ranges.split_once(' ').ok_or_else(invalid)?;
+    let (old_start, old_len) =
+        parse_range(old.strip_prefix('-').ok_or_else(invalid)?).ok_or_else(invalid)?;
+    let (new_start, new_len) =
+        parse_range(new.strip_prefix('+').ok_or_else(invalid)?).ok_or_else(invalid)?;
+
+    let mut hunk = Hunk {
+        old_start,
+        new_start,
+        lines: Vec::new(),
+        old_missing_newline: false,
+        new_missing_newline: false,
+        patch_line: start + 1,
+    };
+
+    let next = match read_counted(lines, start + 1, old_len, new_len, &mut hunk) {
+        Some(next) if !is_body_line(lines, next) => next,
+        _ => {
+            hunk.lines.clear();
+            hunk.old_missing_newline = false;
+            hunk.new_missing_newline = false;
+            read_recounted(lines, start + 1, &mut hunk)
+        }
+    };
+
+    if hunk.lines.is_empty() {
+        return Err(McpError::InvalidArguments(format!(
+            "Patch line {}: hunk {header:?} has no lines",
+            start + 1
+        )));
+    }
+    Ok((hunk, next))
+}
+
+/// `l,s` or `l` (a length of 1)
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Read a hunk body by its header's line counts; `None` if the body ends
+/// before the counts are used up
+fn read_counted(
+    lines: &[&str],
+    mut i: usize,
+    mut old_left: usize,
+    mut new_left: usize,
+    hunk: &mut Hunk,
+) -> Option<usize> {
+    while old_left > 0 || new_left > 0 {
+        let line = *lines.get(i)?;
+        match line.as_bytes().first() {
+            Some(b' ') | None if old_left > 0 && new_left > 0 => {
+                hunk.lines.push(HunkLine::Context(
+                    line.get(1..).unwrap_or_default().to_string(),
+                ));
+                old_left -= 1;
+                new_left -= 1;
+            }
+            Some(b'-') if old_left > 0 => {
+                hunk.lines.push(HunkLine::Remove(line[1..].to_string()));
+                old_left -= 1;
+            }
+            Some(b'+') if new_left > 0 => {
+                hunk.lines.push(HunkLine::Add(line[1..].to_string()));
+                new_left -= 1;
+            }
+            Some(b'\\') => mark_missing_newline(hunk),
+            _ => return None,
+        }
+        i += 1;
+    }
+    // A marker for the hunk's last line follows the counted lines
+    if lines.get(i).is_some_and(|line| line.starts_with('\\')) {
+        mark_missing_newline(hunk);
+        i += 1;
+    }
+    Some(i)
+}
+
+/// Read a hunk body up to the next header or non-hunk line, ignoring the
+/// header's counts
+fn read_recounted(lines: &[&str], mut i: usize, hunk: &mut Hunk) -> usize {
+    while is_body_line(lines, i) {
+        let line = lines[i];
+        match line.as_bytes().first() {
+            Some(b'-') => hunk.lines.push(HunkLine::Remove(line[1..].to_string())),
+            Some(b'+') => hunk.lines.push(HunkLine::Add(line[1..].to_string())),
+            Some(b'\\') => mark_missing_newline(hunk),
+            _ => hunk.lines.push(HunkLine::Context(
+                line.get(1..).unwrap_or_default().to_string(),
+            )),
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Whether line `i` continues a hunk body: a prefixed line that does not
+/// start the next file or hunk, or blank lines (context whose space was
+/// trimmed) followed by one
+fn is_body_line(lines: &[&str], i: usize) -> bool {
+    let Some(line) = lines.get(i) else {
+        return false;
+    };
+    if line.starts_with("@@ ") || line.starts_with("diff --git ") || is_file_header(lines, i) {
+        return false;
+    }
+    // `git format-patch` ends with a "-- " line and the git version
+    if *line == "-- "
+        && !lines
+            .get(i + 1)
+            .is_some_and(|next| next.starts_with([' ', '-', '+']))
+    {
+        return false;
+    }
+    match line.as_bytes().first() {
+        Some(b' ' | b'-' | b'+' | b'\\') => true,
+        None => lines[i..]
+            .iter()
+            .position(|l| !l.is_empty())
+            .is_some_and(|offset| {
+                let next = i + offset;
+                lines[next].starts_with([' ', '-', '+', '\\']) && is_body_line(lines, next)
+            }),
+        _ => false,
+    }
+}
+
+/// Apply a `\ No newline at end of file` marker to the line before it
+fn mark_missing_newline(hunk: &mut Hunk) {
+    match hunk.lines.last() {
+        Some(HunkLine::Remove(_)) => hunk.old_missing_newline = true,
+        Some(HunkLine::Add(_)) => hunk.new_missing_newline = true,
+        Some(HunkLine::Context(_)) => {
+            hunk.old_missing_newline = true;
+            hunk.new_missing_newline = true;
+        }
+        None => {}
+    }
+}
+
+/// Path of a `---`/`+++` header, without the timestamp `diff -u` appends
+fn header_path(value: &str) -> String {
+    let value = value.trim_end();
+    if value.starts_with('"') {
+        return unquote(value);
+    }
+    value.split('\t').next().unwrap_or(value).to_string()
+}
+
+/// The two paths of a `diff --git a/x b/y` line
+fn split_git_paths(rest: &str) -> Option<(String, String)> {
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let end = quoted.find("\" ")? + 1;
+        return Some((unquote(&rest[..=end]), unquote(rest[end + 2..].trim())));
+    }
+    if rest.ends_with('"') {
+        let start = rest.rfind(" \"")?;
+        return Some((rest[..start].to_string(), unquote(&rest[start + 1..])));
+    }
+    // Both sides name the same file unless it was renamed: prefer the split
+    // that gives equal paths, so paths containing " b/" still work
+    let mid = rest.len() / 2;
+    if rest.len() % 2 == 1 && rest.as_bytes()[mid] == b' ' {
+        let (old, new) = (&rest[..mid], &rest[mid + 1..]);
+        if old.get(2..) == new.get(2..) {
+            return Some((old.to_string(), new.to_string()));
+        }
+    }
+    let split = rest.find(" b/").or_else(|| rest.find(' '))?;
+    Some((rest[..split].to_string(), rest[split + 1..].to_string()))
+}
+
+/// Undo git's C-style quoting of paths with unusual characters
+fn unquote(value: &str) -> String {
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.bytes().peekable();
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match chars.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(digit @ b'0'..=b'7') => {
+                let mut value = u32::from(digit - b'0');
+                for _ in 0..2 {
+                    if let Some(&next @ b'0'..=b'7') = chars.peek() {
+                        value = value * 8 + u32::from(next - b'0');
+                        chars.next();
+                    }
+                }
+                bytes.push(u8::try_from(value).unwrap_or(b'?'));
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// `path` without its first `n` components
+fn strip_components(path: &str, n: usize) -> Option<String> {
+    if n == 0 {
+        return Some(path.to_string());
+    }
+    let mut rest = path;
+    for _ in 0..n {
+        rest = rest.split_once('/')?.1;
+    }
+    (!rest.is_empty()).then(|| rest.to_string())
+}
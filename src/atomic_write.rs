@@ -0,0 +1,230 @@
+//! Atomic file replacement for `fs_write_file` and `fs_edit_block`
+//!
+//! New contents never go into the file being replaced. They are written to a
+//! hidden sibling temp file, which is fsynced and given the original file's
+//! permissions, ownership and extended attributes, then renamed over the
+//! target and the directory entry is fsynced. A crash, an OOM kill or a
+//! concurrent reader (a build watcher, say) sees either the old file or the
+//! new one, never a truncated one.
+//!
+//! A symlinked target is resolved first, so the link keeps pointing at the
+//! replaced file. Hard links are not preserved: the rename gives the path a
+//! new inode, as it does for editors that save the same way.
+//!
+//! [`create_new`] and [`rename_noreplace`] are the no-clobber counterparts
+//! used by `fs_write_file`'s `create_new` mode and `fs_move_file`.
+
+use kodegen_mcp_schema::McpError;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Replace `path` with `bytes` atomically, creating it if it does not exist
+///
+/// # Errors
+/// Returns `McpError::Io` if the temp file cannot be written or renamed into
+/// place; the target is left untouched in that case.
+pub async fn write_atomic(path: &Path, bytes: Vec<u8>) -> Result<(), McpError> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || replace(&path, &bytes))
+        .await
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Atomic write task failed: {e}")))?
+        .map_err(McpError::from)
+}
+
+/// Blocking implementation of [`write_atomic`]
+///
+/// # Errors
+/// Returns the I/O error of the step that failed; the temp file is removed.
+pub fn replace(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let target = resolve_symlink(path)?;
+    let dir = parent_dir(&target);
+    let original = fs::metadata(&target).ok();
+    let tmp = temp_path(&target, &dir);
+
+    let result = (|| {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(&tmp)?;
+        file.write_all(bytes)?;
+        if let Some(original) = &original {
+            copy_metadata(&target, &file, original)?;
+        }
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp, &target)?;
+        sync_dir(&dir)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Create `path` with `bytes`, failing with `AlreadyExists` if anything is
+/// already there
+///
+/// The contents are fsynced in a temp file that is then hard-linked into
+/// place: the link fails if the name is taken, like `O_EXCL`, and the file
+/// appears complete or not at all. On filesystems without hard links the
+/// target itself is opened with `O_EXCL` instead.
+///
+/// # Errors
+/// Returns `AlreadyExists` when `path` exists (even as a dangling symlink),
+/// or the I/O error of the step that failed.
+pub fn create_new(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let dir = parent_dir(path);
+    let tmp = temp_path(path, &dir);
+
+    let result = (|| {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        drop(file);
+
+        match fs::hard_link(&tmp, path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(e),
+            Err(_) => {
+                let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+                file.write_all(bytes)?;
+                file.sync_all()?;
+            }
+        }
+        sync_dir(&dir)
+    })();
+
+    let _ = fs::remove_file(&tmp);
+    result
+}
+
+/// Rename `from` to `to`, failing with `AlreadyExists` if `to` exists
+///
+/// Uses `renameat2(RENAME_NOREPLACE)` on Linux and `renamex_np(RENAME_EXCL)`
+/// on macOS, which check and rename in one step. Elsewhere, and on
+/// filesystems that reject the flag, the destination is checked first, which
+/// leaves a small window for a racing writer.
+///
+/// # Errors
+/// Returns `AlreadyExists` when `to` exists, or the rename's I/O error.
+pub fn rename_noreplace(from: &Path, to: &Path) -> io::Result<()> {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios"
+    ))]
+    {
+        use rustix::fs::{CWD, RenameFlags, renameat_with};
+        use rustix::io::Errno;
+
+        match renameat_with(CWD, from, CWD, to, RenameFlags::NOREPLACE) {
+            Ok(()) => return Ok(()),
+            // ENOTSUP and EOPNOTSUPP are the same value on Linux but not on macOS
+            Err(e)
+                if [Errno::INVAL, Errno::NOSYS, Errno::NOTSUP, Errno::OPNOTSUPP].contains(&e) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    if fs::symlink_metadata(to).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    fs::rename(from, to)
+}
+
+/// Directory holding `path` (`.` for a bare file name)
+fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// The file a write to `path` should replace: `path` itself, or the file a
+/// symlink at `path` points to
+fn resolve_symlink(path: &Path) -> io::Result<PathBuf> {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => match fs::canonicalize(path) {
+            Ok(resolved) => Ok(resolved),
+            // Dangling link: create the file it names
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let link = fs::read_link(path)?;
+                Ok(match path.parent() {
+                    Some(parent) if link.is_relative() => parent.join(link),
+                    _ => link,
+                })
+            }
+            Err(e) => Err(e),
+        },
+        _ => Ok(path.to_path_buf()),
+    }
+}
+
+/// Hidden, unique sibling of `target` in `dir`
+fn temp_path(target: &Path, dir: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map_or_else(|| "file".into(), |n| n.to_string_lossy());
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    dir.join(format!(".{name}.{}.tmp", &id[..12]))
+}
+
+/// Give the temp file the original's ownership, mode and extended attributes
+#[cfg(unix)]
+fn copy_metadata(target: &Path, file: &File, original: &fs::Metadata) -> io::Result<()> {
+    use std::os::unix::fs::{MetadataExt, fchown};
+
+    // Ownership first: chown clears setuid/setgid, which the mode restores.
+    // Only root can give a file to another user, so keep at least the group.
+    if let Err(e) = fchown(file, Some(original.uid()), Some(original.gid())) {
+        if e.kind() != io::ErrorKind::PermissionDenied {
+            return Err(e);
+        }
+        let _ = fchown(file, None, Some(original.gid()));
+    }
+    file.set_permissions(original.permissions())?;
+    copy_xattrs(target, file);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_metadata(_target: &Path, file: &File, original: &fs::Metadata) -> io::Result<()> {
+    file.set_permissions(original.permissions())
+}
+
+/// Copy extended attributes (SELinux labels, macOS quarantine/Finder info,
+/// `user.*` tags) where the filesystem and our privileges allow it
+#[cfg(unix)]
+fn copy_xattrs(target: &Path, file: &File) {
+    use xattr::FileExt;
+
+    let Ok(names) = xattr::list(target) else {
+        return;
+    };
+    for name in names {
+        if let Ok(Some(value)) = xattr::get(target, &name)
+            && let Err(e) = file.set_xattr(&name, &value)
+        {
+            log::debug!(
+                "Could not copy xattr {} to {}: {e}",
+                name.to_string_lossy(),
+                target.display()
+            );
+        }
+    }
+}
+
+/// Flush the directory entry so the rename survives a crash
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
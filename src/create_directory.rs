@@ -1,7 +1,9 @@
+use crate::schema::{FsCreateDirectoryArgs, FsCreateDirectoryOutput};
 use crate::validate_path;
 use kodegen_config::shorten_path_for_display;
-use kodegen_mcp_schema::filesystem::{FsCreateDirectoryArgs, FsCreateDirectoryOutput, CreateDirectoryPrompts};
+use kodegen_mcp_schema::filesystem::CreateDirectoryPrompts;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use std::path::Path;
 use tokio::fs;
 
 // ============================================================================
@@ -34,7 +36,8 @@ impl Tool for CreateDirectoryTool {
 
     fn description() -> &'static str {
         "Create a new directory or ensure a directory exists. Can create multiple nested \
-         directories in one operation. Automatically validates paths."
+         directories in one operation. Pass dry_run: true to list the directories that would be \
+         created without creating them. Automatically validates paths."
     }
 
     fn read_only() -> bool {
@@ -52,6 +55,10 @@ impl Tool for CreateDirectoryTool {
     async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
         let valid_path = validate_path(&args.path, &self.config_manager, ctx.pwd()).await?;
 
+        if args.dry_run {
+            return dry_run(&valid_path, &ctx).await;
+        }
+
         fs::create_dir_all(&valid_path).await?;
 
         // Human summary
@@ -67,6 +74,53 @@ impl Tool for CreateDirectoryTool {
             path: valid_path.to_string_lossy().to_string(),
             created: true,
             message: "Directory created successfully".to_string(),
+            dry_run: false,
+            would_create: Vec::new(),
         }))
     }
 }
+
+/// Report the missing directories `create_dir_all` would create, failing
+/// where it would: on a path component that exists but is not a directory
+async fn dry_run(
+    valid_path: &Path,
+    ctx: &ToolExecutionContext,
+) -> Result<ToolResponse<FsCreateDirectoryOutput>, McpError> {
+    let mut missing = Vec::new();
+    for dir in valid_path.ancestors() {
+        match fs::metadata(dir).await {
+            Ok(metadata) if metadata.is_dir() => break,
+            Ok(_) => {
+                return Err(McpError::InvalidArguments(format!(
+                    "{} exists and is not a directory",
+                    dir.display()
+                )));
+            }
+            Err(_) => missing.push(dir.to_string_lossy().to_string()),
+        }
+    }
+    missing.reverse();
+
+    // Human summary
+    let display_path = shorten_path_for_display(valid_path, ctx.git_root());
+    let summary = if missing.is_empty() {
+        format!("\x1b[36mDry run: {display_path} already exists\x1b[0m\nNothing would change")
+    } else {
+        format!(
+            "\x1b[36mDry run: would create {} director{}\x1b[0m\n{}",
+            missing.len(),
+            if missing.len() == 1 { "y" } else { "ies" },
+            missing.join("\n")
+        )
+    };
+    let message = format!("Dry run: would create {} directories", missing.len());
+
+    Ok(ToolResponse::new(summary, FsCreateDirectoryOutput {
+        success: true,
+        path: valid_path.to_string_lossy().to_string(),
+        created: !missing.is_empty(),
+        message,
+        dry_run: true,
+        would_create: missing,
+    }))
+}
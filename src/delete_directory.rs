@@ -1,6 +1,10 @@
+use crate::dry_run::tree_size;
+use crate::journal::{DEFAULT_CONNECTION, Recorder};
+use crate::schema::{FsDeleteDirectoryArgs, FsDeleteDirectoryOutput};
+use crate::trash::Trash;
 use crate::validate_path;
 use kodegen_config::shorten_path_for_display;
-use kodegen_mcp_schema::filesystem::{FsDeleteDirectoryArgs, FsDeleteDirectoryOutput, DeleteDirectoryPrompts};
+use kodegen_mcp_schema::filesystem::DeleteDirectoryPrompts;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use tokio::fs;
 
@@ -25,8 +29,13 @@ impl Tool for DeleteDirectoryTool {
     }
 
     fn description() -> &'static str {
-        "Delete a directory and all its contents recursively. This operation is permanent and \
-         cannot be undone. Requires recursive=true to confirm deletion. Automatically validates paths."
+        "Delete a directory and all its contents recursively. The directory is moved to the \
+         trash (freedesktop.org layout, on the same filesystem), so fs_restore can bring it back \
+         (the response carries a trash_id) and fs_undo can revert the delete; fs_empty_trash \
+         removes it for good. Where no trash is available the tree is copied to the undo journal \
+         instead and deleted, permanently if it exceeds the journal's size limit. Requires \
+         recursive=true to confirm deletion. Pass dry_run: true to get the number and size of the \
+         files that would be removed without deleting anything. Automatically validates paths."
     }
 
     fn read_only() -> bool {
@@ -34,7 +43,7 @@ impl Tool for DeleteDirectoryTool {
     }
 
     fn destructive() -> bool {
-        true // Permanently deletes data recursively
+        true // Deletes recursively (permanently when no trash is available)
     }
 
     fn idempotent() -> bool {
@@ -60,21 +69,83 @@ impl Tool for DeleteDirectoryTool {
             ));
         }
 
-        fs::remove_dir_all(&valid_path).await?;
+        if args.dry_run {
+            let path = valid_path.clone();
+            let size = tokio::task::spawn_blocking(move || tree_size(&path))
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Dry run task failed: {e}")))??;
+            let trashed = Trash::open_default().is_some();
+            let display_path = shorten_path_for_display(&valid_path, ctx.git_root());
+            let summary = format!(
+                "\x1b[36mDry run: would delete directory (recursive) {}\x1b[0m\n\
+                 Contents: {} file(s), {} director(ies), {} bytes · {}",
+                display_path,
+                size.files,
+                size.directories,
+                size.bytes,
+                if trashed { "to the trash" } else { "permanently" }
+            );
+            return Ok(ToolResponse::new(summary, FsDeleteDirectoryOutput {
+                success: true,
+                path: valid_path.to_string_lossy().to_string(),
+                message: format!(
+                    "Dry run: would {} {} file(s) totalling {} bytes",
+                    if trashed { "move to the trash" } else { "delete" },
+                    size.files,
+                    size.bytes
+                ),
+                undo_id: None,
+                trash_id: None,
+                dry_run: true,
+                would_remove: Some(size),
+            }));
+        }
+
+        // Trashing is a rename, so the journal only notes the move; without a
+        // trash the tree is snapshotted before it is removed
+        let mut undo = Recorder::for_call(&ctx, Self::name(), format!("delete {}", args.path));
+        let trashed = match Trash::open_default() {
+            Some(trash) => {
+                let connection = ctx.connection_id().unwrap_or(DEFAULT_CONNECTION);
+                trash.trash(&valid_path, connection).await?
+            }
+            None => None,
+        };
+        match &trashed {
+            Some(item) => undo.moved(&valid_path, &item.location()),
+            None => {
+                undo.snapshot(&valid_path).await;
+                fs::remove_dir_all(&valid_path).await?;
+            }
+        }
+        let undo_id = undo.commit().await;
+        let trash_id = trashed.map(|item| item.id);
 
         // Human summary
         let display_path = shorten_path_for_display(&valid_path, ctx.git_root());
         let summary = format!(
             "\x1b[31mDeleted directory (recursive)\x1b[0m\n\
              Removed: {}\n\
-             Permanent: All contents deleted",
-            display_path
+             Trash: {}\n\
+             Undo: {}",
+            display_path,
+            trash_id.as_deref().unwrap_or("not trashed"),
+            undo_id.as_deref().unwrap_or("not journaled (permanent)")
         );
+        let message = if trash_id.is_some() {
+            "Directory moved to the trash"
+        } else {
+            "Directory and all contents deleted successfully"
+        };
 
         Ok(ToolResponse::new(summary, FsDeleteDirectoryOutput {
             success: true,
             path: valid_path.to_string_lossy().to_string(),
-            message: "Directory and all contents deleted successfully".to_string(),
+            message: message.to_string(),
+            undo_id,
+            trash_id,
+            dry_run: false,
+            would_remove: None,
         }))
     }
 }
@@ -1,6 +1,10 @@
+use crate::journal::{DEFAULT_CONNECTION, Recorder};
+use crate::precondition::check_if_match;
+use crate::schema::{FsDeleteFileArgs, FsDeleteFileOutput};
+use crate::trash::Trash;
 use crate::validate_path;
 use kodegen_config::shorten_path_for_display;
-use kodegen_mcp_schema::filesystem::{FsDeleteFileArgs, FsDeleteFileOutput, DeleteFilePrompts};
+use kodegen_mcp_schema::filesystem::DeleteFilePrompts;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use tokio::fs;
 
@@ -25,8 +29,14 @@ impl Tool for DeleteFileTool {
     }
 
     fn description() -> &'static str {
-        "Delete a file from the filesystem. This operation is permanent and cannot be undone. \
-         Only deletes files, not directories. Automatically validates paths."
+        "Delete a file from the filesystem. The file is moved to the trash (freedesktop.org \
+         layout, on the same filesystem), so fs_restore can bring it back (the response carries \
+         a trash_id) and fs_undo can revert the delete; fs_empty_trash removes it for good. \
+         Where no trash is available the file is copied to the undo journal instead. Only \
+         deletes files, not directories. Pass dry_run: true to validate and report what would \
+         happen without deleting. Pass if_match (content_hash or modified from \
+         fs_read_file) to refuse the delete if the file changed since it was read. Automatically \
+         validates paths."
     }
 
     fn read_only() -> bool {
@@ -34,7 +44,7 @@ impl Tool for DeleteFileTool {
     }
 
     fn destructive() -> bool {
-        true // Permanently deletes data
+        true // Deletes data (permanently when no trash is available)
     }
 
     fn idempotent() -> bool {
@@ -53,20 +63,75 @@ impl Tool for DeleteFileTool {
             ));
         }
 
-        fs::remove_file(&valid_path).await?;
+        if args.dry_run {
+            check_if_match(&valid_path, args.if_match.as_deref()).await?;
+            let trashed = Trash::open_default().is_some();
+            let display_path = shorten_path_for_display(&valid_path, ctx.git_root());
+            let summary = format!(
+                "\x1b[36mDry run: would delete file {}\x1b[0m\n\
+                 Size: {} bytes · {}",
+                display_path,
+                metadata.len(),
+                if trashed { "to the trash" } else { "permanently" }
+            );
+            return Ok(ToolResponse::new(summary, FsDeleteFileOutput {
+                success: true,
+                path: valid_path.to_string_lossy().to_string(),
+                message: format!(
+                    "Dry run: would {} the file ({} bytes)",
+                    if trashed { "move to the trash" } else { "delete" },
+                    metadata.len()
+                ),
+                undo_id: None,
+                trash_id: None,
+                dry_run: true,
+            }));
+        }
+
+        let mut undo = Recorder::for_call(&ctx, Self::name(), format!("delete {}", args.path));
+        // Checked right before the file goes, so a change has as little time
+        // as possible to slip in between
+        check_if_match(&valid_path, args.if_match.as_deref()).await?;
+        let trashed = match Trash::open_default() {
+            Some(trash) => {
+                let connection = ctx.connection_id().unwrap_or(DEFAULT_CONNECTION);
+                trash.trash(&valid_path, connection).await?
+            }
+            None => None,
+        };
+        match &trashed {
+            Some(item) => undo.moved(&valid_path, &item.location()),
+            None => {
+                undo.snapshot(&valid_path).await;
+                fs::remove_file(&valid_path).await?;
+            }
+        }
+        let undo_id = undo.commit().await;
+        let trash_id = trashed.map(|item| item.id);
 
         // Human summary
         let display_path = shorten_path_for_display(&valid_path, ctx.git_root());
         let summary = format!(
             "\x1b[31mDeleted file: {}\x1b[0m\n\
-             Permanent: File removed from filesystem",
-            display_path
+             Trash: {}\n\
+             Undo: {}",
+            display_path,
+            trash_id.as_deref().unwrap_or("not trashed"),
+            undo_id.as_deref().unwrap_or("not journaled (permanent)")
         );
+        let message = if trash_id.is_some() {
+            "File moved to the trash"
+        } else {
+            "File deleted successfully"
+        };
 
         Ok(ToolResponse::new(summary, FsDeleteFileOutput {
             success: true,
             path: valid_path.to_string_lossy().to_string(),
-            message: "File deleted successfully".to_string(),
+            message: message.to_string(),
+            undo_id,
+            trash_id,
+            dry_run: false,
         }))
     }
 }
@@ -0,0 +1,29 @@
+//! Unified diffs of writes and edits
+//!
+//! `fs_edit_block` and `fs_write_file` return the change they made (or, in a
+//! dry run, would make) as a unified diff, so the caller can check it
+//! without reading the file again.
+
+use similar::TextDiff;
+use std::time::Duration;
+
+/// Default lines of context around each change
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Give up on a minimal diff after this long and return a coarser one
+const DIFF_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Unified diff turning `old` into `new`, with `path` in the headers and
+/// `context` unchanged lines around each hunk
+///
+/// Returns an empty string when nothing changes.
+#[must_use]
+pub fn unified_diff(path: &str, old: &str, new: &str, context: usize) -> String {
+    TextDiff::configure()
+        .timeout(DIFF_TIMEOUT)
+        .diff_lines(old, new)
+        .unified_diff()
+        .context_radius(context)
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string()
+}
@@ -0,0 +1,37 @@
+//! Previews for `dry_run: true` on the mutating tools
+//!
+//! A dry run goes through the same validation as the real call (path rules,
+//! `if_match`, existence and match checks) and then reports what it would do
+//! instead of doing it. Writes and edits report the diff they would make
+//! (see [`crate::diff`]); a recursive delete reports the size of the tree it
+//! would remove.
+
+use crate::schema::TreeSize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Count what is under the directory `path` without following symlinks
+///
+/// # Errors
+/// Returns the first I/O error from reading the tree.
+pub fn tree_size(path: &Path) -> io::Result<TreeSize> {
+    let mut size = TreeSize::default();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        size.directories += 1;
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else {
+                size.files += 1;
+                if file_type.is_file() {
+                    size.bytes += entry.metadata()?.len();
+                }
+            }
+        }
+    }
+    Ok(size)
+}
@@ -35,6 +35,8 @@ pub struct EditBlockLogEntry {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EditBlockResult {
     ExactMatch,
+    RegexMatch,
+    WhitespaceNormalizedMatch,
     FuzzyMatchAccepted,
     FuzzyMatchRejected,
     NoMatchFound,
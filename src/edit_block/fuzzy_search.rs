@@ -1,9 +1,20 @@
 //! Fuzzy string matching utilities using Levenshtein distance
 //!
-//! Provides algorithms for finding approximate string matches within text,
-//! useful for error correction, search features, and text comparison.
+//! Locates the substring of a text closest to a query in three steps:
+//!
+//! 1. Candidate windows of whole lines, a few lines longer than the query,
+//!    are ranked by the character trigrams they share with it. By the q-gram
+//!    lemma every edit destroys at most three trigrams, so the shared count
+//!    gives a lower bound on the edit distance of any match in the window.
+//! 2. Windows are searched in order of that bound with Myers' bit-parallel
+//!    algorithm, which finds the best matching substring in O(n·⌈m/64⌉).
+//! 3. The search stops as soon as no remaining window can beat the best match
+//!    (or the caller's maximum distance), or after [`MAX_CANDIDATES`].
+//!
+//! Memory use is linear in the query and text lengths.
 
 use std::cmp;
+use std::collections::HashMap;
 
 // ============================================================================
 // PUBLIC TYPES
@@ -13,10 +24,10 @@ use std::cmp;
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct FuzzySearchResult {
-    /// Start index of the match in the text
+    /// Start byte index of the match in the text
     pub start: usize,
 
-    /// End index of the match in the text
+    /// End byte index of the match in the text (exclusive)
     pub end: usize,
 
     /// The matched substring
@@ -26,251 +37,343 @@ pub struct FuzzySearchResult {
     pub distance: f64,
 }
 
+/// Most candidate windows searched for one query
+pub const MAX_CANDIDATES: usize = 8;
+
+/// Lines a candidate window has beyond the query's own
+const WINDOW_SLACK_LINES: usize = 2;
+
 // ============================================================================
-// INTERNAL HELPERS
+// BIT-PARALLEL EDIT DISTANCE
 // ============================================================================
 
-/// Safe substring that respects UTF-8 character boundaries
-/// - Swaps start and end if start > end
-/// - Clamps indices to valid bounds
-/// - Adjusts to nearest character boundaries (start rounds up, end rounds down)
-/// - Returns empty string for invalid ranges
-fn safe_substring(text: &str, start: usize, end: usize) -> &str {
-    let len = text.len();
-    
-    // Swap if reversed
-    let (mut start, mut end) = if start > end {
-        (end, start)
-    } else {
-        (start, end)
-    };
-    
-    // Clamp to string length
-    start = cmp::min(start, len);
-    end = cmp::min(end, len);
-    
-    // Return empty if invalid range
-    if start >= end {
-        return "";
+/// Query preprocessed for Myers' algorithm, in blocks of 64 characters
+struct Pattern {
+    len: usize,
+    blocks: usize,
+    /// Per character, the rows of the query holding it
+    peq: HashMap<char, Vec<u64>>,
+    /// Per block, the bit of its last row
+    high_bits: Vec<u64>,
+}
+
+/// How the first row of the dynamic programming matrix is set
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TopRow {
+    /// All zero: the query may start anywhere in the text
+    Free,
+    /// Column index: the query is aligned with the start of the text
+    Anchored,
+}
+
+impl Pattern {
+    fn new(query: impl Iterator<Item = char>) -> Self {
+        let chars: Vec<char> = query.collect();
+        let len = chars.len();
+        let blocks = len.div_ceil(64).max(1);
+        let mut peq: HashMap<char, Vec<u64>> = HashMap::new();
+        for (row, &c) in chars.iter().enumerate() {
+            peq.entry(c).or_insert_with(|| vec![0; blocks])[row / 64] |= 1 << (row % 64);
+        }
+        let high_bits = (0..blocks)
+            .map(|block| {
+                let rows = cmp::min(64, len.saturating_sub(block * 64)).max(1);
+                1 << (rows - 1)
+            })
+            .collect();
+        Self {
+            len,
+            blocks,
+            peq,
+            high_bits,
+        }
     }
-    
-    // Adjust start to next character boundary (round up)
-    while start < len && !text.is_char_boundary(start) {
-        start += 1;
+
+    /// Edit distance of the whole query to every prefix of `text` (when
+    /// `Anchored`) or to the best substring ending at each position (when
+    /// `Free`); `visit` gets each end position, in characters, with its
+    /// distance
+    fn scan(
+        &self,
+        text: impl Iterator<Item = char>,
+        top: TopRow,
+        mut visit: impl FnMut(usize, usize),
+    ) {
+        let mut pv = vec![u64::MAX; self.blocks];
+        let mut mv = vec![0u64; self.blocks];
+        let zeros = vec![0u64; self.blocks];
+        let mut score = self.len;
+        for (column, c) in text.enumerate() {
+            let eqs = self.peq.get(&c).unwrap_or(&zeros);
+            let mut carry: i8 = if top == TopRow::Anchored { 1 } else { 0 };
+            for block in 0..self.blocks {
+                carry = advance_block(
+                    &mut pv[block],
+                    &mut mv[block],
+                    eqs[block],
+                    carry,
+                    self.high_bits[block],
+                );
+            }
+            score = score.saturating_add_signed(isize::from(carry));
+            visit(column + 1, score);
+        }
     }
-    
-    // Adjust end to previous character boundary (round down)
-    while end > 0 && !text.is_char_boundary(end) {
-        end -= 1;
+}
+
+/// One column step of Hyyrö's block-based Myers algorithm
+///
+/// `hin` is the horizontal delta entering the block's first row; the
+/// return value is the delta leaving its last row (marked by `high`).
+fn advance_block(pv: &mut u64, mv: &mut u64, eq: u64, hin: i8, high: u64) -> i8 {
+    let mut eq = eq;
+    let xv = eq | *mv;
+    if hin < 0 {
+        eq |= 1;
     }
-    
-    // Final safety check
-    if start >= end {
-        return "";
+    let xh = ((eq & *pv).wrapping_add(*pv) ^ *pv) | eq;
+    let mut ph = *mv | !(xh | *pv);
+    let mut mh = *pv & xh;
+
+    let hout = if ph & high != 0 {
+        1
+    } else if mh & high != 0 {
+        -1
+    } else {
+        0
+    };
+
+    ph <<= 1;
+    mh <<= 1;
+    if hin < 0 {
+        mh |= 1;
+    } else if hin > 0 {
+        ph |= 1;
     }
-    
-    &text[start..end]
+    *pv = mh | !(xv | ph);
+    *mv = ph & xv;
+    hout
 }
 
-// ============================================================================
-// CORE ALGORITHMS
-// ============================================================================
-
 /// Calculate Levenshtein distance between two strings
 ///
 /// Returns the minimum number of single-character edits (insertions,
-/// deletions, or substitutions) needed to transform string `a` into string `b`.
-///
-/// # Examples
-///
-/// ```
-/// use kodegen_utils::fuzzy_search::levenshtein_distance;
-///
-/// assert!((levenshtein_distance("hello", "hello") - 0.0).abs() < f64::EPSILON);
-/// assert!((levenshtein_distance("hello", "hallo") - 1.0).abs() < f64::EPSILON);
-/// assert!((levenshtein_distance("", "hello") - 5.0).abs() < f64::EPSILON);
-/// ```
+/// deletions, or substitutions) needed to transform string `a` into string
+/// `b`. Runs in O(|a|·⌈|b|/64⌉) time and O(|b|) memory.
 #[must_use]
 pub fn levenshtein_distance(a: &str, b: &str) -> f64 {
-    let a_chars: Vec<char> = a.chars().collect();
-    let b_chars: Vec<char> = b.chars().collect();
-    let a_len = a_chars.len();
-    let b_len = b_chars.len();
-
-    // Return early for empty strings
-    // Cast string lengths to f64: exact for lengths < 2^52, acceptable loss for impossible sizes
-    if a_len == 0 {
-        return b_len as f64;
+    // Cast to f64: exact for all realistic string lengths < 2^52 chars
+    if b.is_empty() {
+        return a.chars().count() as f64;
     }
-    if b_len == 0 {
-        return a_len as f64;
-    }
-
-    // Dynamic programming matrix using f64 to avoid precision loss warnings
-    // f64 can exactly represent integers up to 2^52 (sufficient for realistic string lengths)
-    let mut matrix = vec![vec![0.0; b_len + 1]; a_len + 1];
+    let pattern = Pattern::new(b.chars());
+    let mut distance = pattern.len;
+    pattern.scan(a.chars(), TopRow::Anchored, |_, score| distance = score);
+    distance as f64
+}
 
-    // Initialize first row and column with distances
-    // Cast indices to f64: exact for all realistic string lengths < 2^52 chars
-    for (i, row) in matrix.iter_mut().enumerate().take(a_len + 1) {
-        row[0] = i as f64;
-    }
-    for (j, cell) in matrix[0].iter_mut().enumerate().take(b_len + 1) {
-        *cell = j as f64;
-    }
+// ============================================================================
+// CANDIDATE WINDOWS
+// ============================================================================
 
-    // Fill matrix
-    for i in 1..=a_len {
-        for j in 1..=b_len {
-            let cost = if a_chars[i - 1] == b_chars[j - 1] {
-                0.0
-            } else {
-                1.0
-            };
-            matrix[i][j] = (matrix[i - 1][j] + 1.0) // deletion
-                .min(matrix[i][j - 1] + 1.0) // insertion
-                .min(matrix[i - 1][j - 1] + cost); // substitution
-        }
+/// Byte offsets of the line starts of `text`, plus its length
+fn line_starts(text: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+    if starts.last() != Some(&text.len()) {
+        starts.push(text.len());
     }
+    starts
+}
 
-    matrix[a_len][b_len]
+/// Trigrams of `line` that contain no line break except as their last
+/// character, so that they never straddle two lines
+fn line_trigrams(line: &str) -> impl Iterator<Item = [char; 3]> + '_ {
+    let chars: Vec<char> = line.chars().collect();
+    (0..chars.len().saturating_sub(2))
+        .map(move |i| [chars[i], chars[i + 1], chars[i + 2]])
+        .filter(|gram| gram[0] != '\n' && gram[1] != '\n')
 }
 
-/// Iteratively refines the best match by reducing the search area
-fn iterative_reduction(
-    text: &str,
-    query: &str,
-    start: usize,
-    end: usize,
-    parent_distance: f64,
-) -> FuzzySearchResult {
-    let mut best_distance = parent_distance;
-    let mut best_start = start;
-    let mut best_end = end;
-
-    // Improve start position
-    let next_text = safe_substring(text, best_start + 1, best_end);
-    let mut next_distance = levenshtein_distance(next_text, query);
-
-    while next_distance < best_distance {
-        best_distance = next_distance;
-        best_start += 1;
-
-        let smaller_text = safe_substring(text, best_start + 1, best_end);
-        next_distance = levenshtein_distance(smaller_text, query);
+/// Windows of `query_lines + WINDOW_SLACK_LINES` lines, as byte ranges,
+/// each with a lower bound on the edit distance of any match inside it,
+/// sorted by that bound
+fn candidate_windows(text: &str, query: &str) -> Vec<(usize, std::ops::Range<usize>)> {
+    let starts = line_starts(text);
+    let line_count = starts.len() - 1;
+    let window_lines = query.lines().count().max(1) + WINDOW_SLACK_LINES;
+    if line_count <= window_lines {
+        return vec![(0, 0..text.len())];
     }
 
-    // Improve end position
-    let next_text = safe_substring(text, best_start, best_end.saturating_sub(1));
-    let mut next_distance = levenshtein_distance(next_text, query);
-
-    while next_distance < best_distance {
-        best_distance = next_distance;
-        best_end = best_end.saturating_sub(1);
-
-        let smaller_text = safe_substring(text, best_start, best_end.saturating_sub(1));
-        next_distance = levenshtein_distance(smaller_text, query);
+    // Query trigrams, each numbered, with how often they occur
+    let mut gram_ids: HashMap<[char; 3], usize> = HashMap::new();
+    let mut wanted: Vec<usize> = Vec::new();
+    for line in query.split_inclusive('\n') {
+        for gram in line_trigrams(line) {
+            let next = gram_ids.len();
+            let id = *gram_ids.entry(gram).or_insert(next);
+            if id == wanted.len() {
+                wanted.push(0);
+            }
+            wanted[id] += 1;
+        }
     }
-
-    FuzzySearchResult {
-        start: best_start,
-        end: best_end,
-        value: safe_substring(text, best_start, best_end).to_string(),
-        distance: best_distance,
+    let total: usize = wanted.iter().sum();
+
+    // The query trigrams on each line of the text
+    let line_grams: Vec<Vec<usize>> = (0..line_count)
+        .map(|i| {
+            line_trigrams(&text[starts[i]..starts[i + 1]])
+                .filter_map(|gram| gram_ids.get(&gram).copied())
+                .collect()
+        })
+        .collect();
+
+    // Slide the window one line at a time, keeping the multiset overlap
+    let mut have = vec![0usize; wanted.len()];
+    let mut shared = 0;
+    let mut windows = Vec::with_capacity(line_count - window_lines + 1);
+    for last in 0..line_count {
+        for &id in &line_grams[last] {
+            if have[id] < wanted[id] {
+                shared += 1;
+            }
+            have[id] += 1;
+        }
+        if last + 1 > window_lines {
+            for &id in &line_grams[last - window_lines] {
+                have[id] -= 1;
+                if have[id] < wanted[id] {
+                    shared -= 1;
+                }
+            }
+        }
+        if last + 1 >= window_lines {
+            let first = last + 1 - window_lines;
+            let bound = (total - shared).div_ceil(3);
+            windows.push((bound, starts[first]..starts[last + 1]));
+        }
     }
+    windows.sort_by_key(|(bound, range)| (*bound, range.start));
+    windows
 }
 
-/// Recursively finds the closest match to a query string within text using fuzzy matching
-///
-/// Uses a binary-search-like approach to efficiently find the best matching substring.
-///
-/// # Arguments
-///
-/// * `text` - The text to search within
-/// * `query` - The pattern to search for
-/// * `start` - Starting index in text
-/// * `end` - Optional ending index (None = end of text)
-/// * `parent_distance` - Best distance found so far
-///
-/// # Examples
-///
-/// ```
-/// use kodegen_utils::fuzzy_search::recursive_fuzzy_index_of;
+// ============================================================================
+// FUZZY LOCATOR
+// ============================================================================
+
+/// Best match for `pattern` inside `window` of `text`: its byte range and
+/// distance, preferring the earliest and then the shortest
+fn best_in_window(
+    text: &str,
+    window: std::ops::Range<usize>,
+    pattern: &Pattern,
+    reversed: &Pattern,
+) -> (std::ops::Range<usize>, usize) {
+    let slice = &text[window.clone()];
+    let mut best_end = 0;
+    let mut best = pattern.len;
+    pattern.scan(slice.chars(), TopRow::Free, |end, score| {
+        if score < best {
+            best = score;
+            best_end = end;
+        }
+    });
+    let end_byte = slice
+        .char_indices()
+        .nth(best_end)
+        .map_or(slice.len(), |(i, _)| i);
+
+    // The start: the shortest prefix of the reversed text before the end
+    // that the reversed query matches with the same distance
+    let mut length = 0;
+    let mut found = false;
+    let limit = pattern.len + best;
+    reversed.scan(
+        slice[..end_byte].chars().rev().take(limit),
+        TopRow::Anchored,
+        |chars, score| {
+            if !found && score == best {
+                found = true;
+                length = chars;
+            }
+        },
+    );
+    let start_byte = slice[..end_byte]
+        .char_indices()
+        .rev()
+        .nth(length.wrapping_sub(1))
+        .map_or(end_byte, |(i, _)| i);
+
+    (window.start + start_byte..window.start + end_byte, best)
+}
+
+/// Find the substring of `text` closest to `query` by edit distance
 ///
-/// let text = "The quick brown fox";
-/// let result = recursive_fuzzy_index_of(text, "qwick", 0, None, f64::INFINITY);
-/// assert!(result.distance > 0.0); // Not an exact match
-/// ```
+/// Candidate windows whose distance bound exceeds `max_distance` are not
+/// searched unless nothing has been searched yet, so a poor match is still
+/// reported as the closest one found.
 #[must_use]
-pub fn recursive_fuzzy_index_of(
-    text: &str,
-    query: &str,
-    start: usize,
-    end: Option<usize>,
-    parent_distance: f64,
-) -> FuzzySearchResult {
-    let end = end.unwrap_or(text.len());
-
-    // For small text segments, use iterative approach
-    if end.saturating_sub(start) <= 2 * query.len() {
-        return iterative_reduction(text, query, start, end, parent_distance);
+pub fn fuzzy_index_of(text: &str, query: &str, max_distance: Option<usize>) -> FuzzySearchResult {
+    if query.is_empty() || text.is_empty() {
+        return FuzzySearchResult {
+            start: 0,
+            end: 0,
+            value: String::new(),
+            distance: query.chars().count() as f64,
+        };
     }
 
-    let mid_point = start + (end - start) / 2;
-    let left_end = cmp::min(end, mid_point + query.len()); // Include query length to cover overlaps
-    let right_start = cmp::max(start, mid_point.saturating_sub(query.len())); // Include query length to cover overlaps
-
-    // Calculate distance for current segments
-    let left_text = safe_substring(text, start, left_end);
-    let right_text = safe_substring(text, right_start, end);
-
-    let left_distance = levenshtein_distance(left_text, query);
-    let right_distance = levenshtein_distance(right_text, query);
-    let best_distance = left_distance.min(parent_distance.min(right_distance));
-
-    // If parent distance is already the best, use iterative approach
-    // Use epsilon comparison for f64 to avoid precision issues
-    if (parent_distance - best_distance).abs() < f64::EPSILON {
-        return iterative_reduction(text, query, start, end, parent_distance);
+    let pattern = Pattern::new(query.chars());
+    let reversed = Pattern::new(query.chars().rev());
+    let mut best: Option<(std::ops::Range<usize>, usize)> = None;
+    for (bound, window) in candidate_windows(text, query)
+        .into_iter()
+        .take(MAX_CANDIDATES)
+    {
+        if let Some((_, distance)) = &best {
+            let beyond_cutoff = max_distance.is_some_and(|max| bound > max);
+            if bound >= *distance || beyond_cutoff {
+                break;
+            }
+        }
+        let (range, distance) = best_in_window(text, window, &pattern, &reversed);
+        let better = best.as_ref().is_none_or(|(best_range, best_distance)| {
+            distance < *best_distance
+                || (distance == *best_distance && range.start < best_range.start)
+        });
+        if better {
+            best = Some((range, distance));
+        }
     }
 
-    // Recursively search the better half
-    if left_distance < right_distance {
-        recursive_fuzzy_index_of(text, query, start, Some(left_end), best_distance)
-    } else {
-        recursive_fuzzy_index_of(text, query, right_start, Some(end), best_distance)
+    let (range, distance) = best.unwrap_or((0..0, pattern.len));
+    FuzzySearchResult {
+        start: range.start,
+        end: range.end,
+        value: text[range].to_string(),
+        // Cast to f64: exact for all realistic string lengths < 2^52 chars
+        distance: distance as f64,
     }
 }
 
-/// Public interface with sensible defaults
-///
-/// # Examples
-///
-/// ```
-/// use kodegen_utils::fuzzy_search::recursive_fuzzy_index_of_with_defaults;
-///
-/// let text = "The quick brown fox jumps over the lazy dog";
-/// let result = recursive_fuzzy_index_of_with_defaults(text, "quick");
-/// assert_eq!(result.value, "quick");
-/// assert!((result.distance - 0.0).abs() < f64::EPSILON);
-/// ```
+/// Largest edit distance at which a match of `query_len` bytes can still
+/// reach `threshold` with [`get_similarity_ratio`]
 #[must_use]
-pub fn recursive_fuzzy_index_of_with_defaults(text: &str, query: &str) -> FuzzySearchResult {
-    recursive_fuzzy_index_of(text, query, 0, None, f64::INFINITY)
+pub fn max_distance_for_similarity(query_len: usize, threshold: f64) -> usize {
+    if threshold <= 0.0 {
+        return usize::MAX;
+    }
+    // The match may be longer than the query by up to the distance itself,
+    // so d <= (1 - t)(m + d), i.e. d <= m(1 - t)/t
+    // Cast to f64 and back: exact for realistic lengths, rounded up
+    (query_len as f64 * (1.0 - threshold) / threshold).ceil() as usize
 }
 
 /// Calculate similarity ratio between two strings
 ///
 /// Returns a value between 0.0 (completely different) and 1.0 (identical).
-///
-/// # Examples
-///
-/// ```
-/// use kodegen_utils::fuzzy_search::get_similarity_ratio;
-///
-/// assert_eq!(get_similarity_ratio("hello", "hello"), 1.0);
-/// assert!(get_similarity_ratio("hello", "hallo") >= 0.8);
-/// ```
 #[must_use]
 pub fn get_similarity_ratio(a: &str, b: &str) -> f64 {
     let max_length = cmp::max(a.len(), b.len());
@@ -284,3 +387,369 @@ pub fn get_similarity_ratio(a: &str, b: &str) -> f64 {
     let max_length_f64 = max_length as f64;
     1.0 - (distance / max_length_f64)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_max_distance_for_similarity() {
+        assert_eq!(max_distance_for_similarity(100, 1.0), 0);
+        assert_eq!(max_distance_for_similarity(100, 0.8), 25);
+        assert_eq!(max_distance_for_similarity(100, 0.0), usize::MAX);
+    }
+
+    #[test]
+    fn test_levenshtein_distance() {
+        assert_eq!(levenshtein_distance("hello", "hello"), 0.0);
+        assert_eq!(levenshtein_distance("hello", "hallo"), 1.0);
+        assert_eq!(levenshtein_distance("", "hello"), 5.0);
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3.0);
+    }
+
+    /// Full-matrix Levenshtein distance, the reference implementation
+    fn matrix_distance(a: &str, b: &str) -> usize {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let mut matrix = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            row[0] = i;
+        }
+        for (j, cell) in matrix[0].iter_mut().enumerate() {
+            *cell = j;
+        }
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                let cost = usize::from(a[i - 1] != b[j - 1]);
+                matrix[i][j] = (matrix[i - 1][j] + 1)
+                    .min(matrix[i][j - 1] + 1)
+                    .min(matrix[i - 1][j - 1] + cost);
+            }
+        }
+        matrix[a.len()][b.len()]
+    }
+
+    /// Smallest distance of `query` to any substring of `text`
+    fn best_substring_distance(text: &str, query: &str) -> usize {
+        let text: Vec<char> = text.chars().collect();
+        let query: Vec<char> = query.chars().collect();
+        let mut previous: Vec<usize> = vec![0; text.len() + 1];
+        for (i, &q) in query.iter().enumerate() {
+            let mut row = vec![i + 1; text.len() + 1];
+            for j in 1..=text.len() {
+                let cost = usize::from(q != text[j - 1]);
+                row[j] = (previous[j] + 1)
+                    .min(row[j - 1] + 1)
+                    .min(previous[j - 1] + cost);
+            }
+            previous = row;
+        }
+        previous.into_iter().min().unwrap()
+    }
+
+    /// Deterministic xorshift generator
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 as usize
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            self.next() % n
+        }
+    }
+
+    /// Deterministic pseudo-random strings over `alphabet`
+    fn random_strings(alphabet: &str, count: usize, max_len: usize) -> Vec<String> {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        (0..count)
+            .map(|_| {
+                let len = rng.below(max_len + 1);
+                (0..len)
+                    .map(|_| alphabet[rng.below(alphabet.len())])
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// A source file of `lines` distinct lines
+    fn source_file(lines: usize) -> String {
+        (0..lines)
+            .map(|i| {
+                format!(
+                    "    let value_{i} = compute({i}, \"item {}\");\n",
+                    i * 7 % 13
+                )
+            })
+            .collect()
+    }
+
+    /// The match must be the text it claims, at the distance it claims
+    fn assert_consistent(text: &str, query: &str, result: &FuzzySearchResult) {
+        assert_eq!(&text[result.start..result.end], result.value);
+        assert_eq!(
+            matrix_distance(&result.value, query) as f64,
+            result.distance,
+            "{query:?} matched {:?}",
+            result.value
+        );
+    }
+
+    #[test]
+    fn test_distance_and_similarity_match_full_matrix() {
+        let strings = random_strings("ab c\né", 40, 150);
+        for a in &strings {
+            for b in strings.iter().take(12) {
+                let longest = a.len().max(b.len());
+                let expected = if longest == 0 {
+                    1.0
+                } else {
+                    1.0 - matrix_distance(a, b) as f64 / longest as f64
+                };
+                assert_eq!(
+                    levenshtein_distance(a, b) as usize,
+                    matrix_distance(a, b),
+                    "{a:?} vs {b:?}"
+                );
+                assert_eq!(get_similarity_ratio(a, b), expected, "{a:?} vs {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_locator_finds_best_substring() {
+        let result = fuzzy_index_of("The quick brown fox", "qwick", None);
+        assert_eq!(result.value, "quick");
+        assert_eq!(result.distance, 1.0);
+
+        let result = fuzzy_index_of("The quick brown fox jumps over the lazy dog", "quick", None);
+        assert_eq!(result.value, "quick");
+        assert_eq!(result.distance, 0.0);
+
+        // Texts of a few lines are searched whole, so the distance is the true minimum
+        let texts = random_strings("ab cé", 30, 80);
+        for text in &texts {
+            for query in random_strings("ab cé", 5, 20)
+                .iter()
+                .filter(|q| !q.is_empty())
+            {
+                let result = fuzzy_index_of(text, query, None);
+                assert_eq!(
+                    result.distance as usize,
+                    best_substring_distance(text, query),
+                    "{query:?} in {text:?}"
+                );
+                assert_consistent(text, query, &result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_locator_matches_brute_force_across_windows() {
+        let words: Vec<String> = random_strings("abcdefghijklmnopqrstuvwxyz_", 300, 9)
+            .into_iter()
+            .filter(|word| word.len() >= 3)
+            .collect();
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+        for case in 0..120 {
+            // Many more lines than one candidate window
+            let lines: Vec<String> = (0..20 + rng.below(40))
+                .map(|_| {
+                    let indent = " ".repeat(4 * rng.below(3));
+                    let line: Vec<&str> = (0..2 + rng.below(5))
+                        .map(|_| words[rng.below(words.len())].as_str())
+                        .collect();
+                    format!("{indent}{}", line.join(" "))
+                })
+                .collect();
+            let text = lines.join("\n") + "\n";
+
+            // A block of the text with a few random edits, which may add or
+            // remove line breaks
+            let first = rng.below(lines.len() - 4);
+            let mut query: Vec<char> = lines[first..first + 1 + rng.below(4)]
+                .join("\n")
+                .chars()
+                .collect();
+            for _ in 0..rng.below(4) {
+                let at = rng.below(query.len());
+                let c = "xyz \n".chars().nth(rng.below(5)).unwrap();
+                match rng.below(3) {
+                    0 => query[at] = c,
+                    1 => query.insert(at, c),
+                    _ if query.len() > 1 => {
+                        query.remove(at);
+                    }
+                    _ => {}
+                }
+            }
+            let query: String = query.into_iter().collect();
+
+            let result = fuzzy_index_of(&text, &query, None);
+            assert_eq!(
+                result.distance as usize,
+                best_substring_distance(&text, &query),
+                "case {case}: {query:?} in\n{text}"
+            );
+            assert_consistent(&text, &query, &result);
+        }
+    }
+
+    #[test]
+    fn test_locator_stops_after_max_candidates() {
+        let query = "alpha = compute(1);\nbeta = compute(2);\ngamma = compute(3);";
+        // The same lines in another order: every trigram of the query, so the
+        // windows around it rank first, but far from it by edit distance
+        let decoy = "beta = compute(2);\ngamma = compute(3);\nalpha = compute(1);\n";
+        let target = "alpha = compute(1);\nbeta = compote(2);\ngamma = compute(3);\n";
+        let file = |decoys: usize| {
+            let mut text = String::new();
+            for i in 0..decoys {
+                for j in 0..3 {
+                    text.push_str(&format!("let filler_{i}_{j} = {j};\n"));
+                }
+                text.push_str(decoy);
+            }
+            text.push_str("let before = 0;\nlet again = 0;\nlet once_more = 0;\n");
+            text.push_str(target);
+            text.push_str("let after = 0;\nlet done = 0;\n");
+            text
+        };
+
+        assert_eq!(MAX_CANDIDATES, 8);
+        // Each decoy fills three candidate windows (three filler lines keep a
+        // window from spanning two of them); two decoys leave room for the
+        // target within the first MAX_CANDIDATES
+        let text = file(2);
+        let result = fuzzy_index_of(&text, query, None);
+        assert_eq!(result.distance, 1.0);
+        assert_eq!(
+            result.distance as usize,
+            best_substring_distance(&text, query)
+        );
+        assert_eq!(result.start, text.find(target).unwrap());
+        assert_consistent(&text, query, &result);
+
+        // With three, the decoys take every candidate and the search gives up
+        // on the target: the work per query stays bounded
+        let text = file(3);
+        let result = fuzzy_index_of(&text, query, None);
+        assert!(result.distance > 1.0, "{result:?}");
+        assert!(result.start < text.find(target).unwrap());
+        assert_consistent(&text, query, &result);
+    }
+
+    #[test]
+    fn test_locator_finds_edited_block_in_large_file() {
+        let content = source_file(5_000);
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+        let original: String = lines[3_200..3_260].concat();
+        let query = original
+            .replace("value_3210", "value_321O")
+            .replace("compute(3240", "compute (3240");
+
+        let result = fuzzy_index_of(&content, &query, Some(query.len() * 3 / 7));
+
+        let line = content[..result.start].matches('\n').count() + 1;
+        assert_eq!(line, 3_201);
+        assert_eq!(result.distance, 2.0);
+        assert!(get_similarity_ratio(&result.value, &query) > 0.99);
+    }
+
+    // ============================================================================
+    // BENCHMARK (cargo test --release --lib fuzzy_search -- --ignored --nocapture)
+    // ============================================================================
+
+    /// The locator fuzzy_index_of replaced: bisect the text by full-matrix
+    /// distance, then trim the ends one character at a time
+    mod previous_locator {
+        use super::matrix_distance;
+
+        fn substring(text: &str, start: usize, end: usize) -> &str {
+            let (mut start, mut end) = (start.min(text.len()), end.min(text.len()));
+            while start < end && !text.is_char_boundary(start) {
+                start += 1;
+            }
+            while end > start && !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            &text[start..end.max(start)]
+        }
+
+        fn distance(text: &str, start: usize, end: usize, query: &str) -> usize {
+            matrix_distance(substring(text, start, end), query)
+        }
+
+        fn reduce(
+            text: &str,
+            query: &str,
+            mut start: usize,
+            mut end: usize,
+            mut best: usize,
+        ) -> usize {
+            while start < end && distance(text, start + 1, end, query) < best {
+                best = distance(text, start + 1, end, query);
+                start += 1;
+            }
+            while end > start && distance(text, start, end - 1, query) < best {
+                best = distance(text, start, end - 1, query);
+                end -= 1;
+            }
+            best
+        }
+
+        pub fn locate(text: &str, query: &str, start: usize, end: usize, parent: usize) -> usize {
+            if end.saturating_sub(start) <= 2 * query.len() {
+                return reduce(text, query, start, end, parent);
+            }
+            let mid = start + (end - start) / 2;
+            let left_end = end.min(mid + query.len());
+            let right_start = start.max(mid.saturating_sub(query.len()));
+            let left = distance(text, start, left_end, query);
+            let right = distance(text, right_start, end, query);
+            let best = left.min(right).min(parent);
+            if best == parent {
+                return reduce(text, query, start, end, parent);
+            }
+            if left < right {
+                locate(text, query, start, left_end, best)
+            } else {
+                locate(text, query, right_start, end, best)
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark; run with --ignored --nocapture"]
+    fn bench_locator_against_previous_locator() {
+        for (lines, block) in [(200, 5), (400, 10), (800, 20)] {
+            let content = source_file(lines);
+            let rows: Vec<&str> = content.split_inclusive('\n').collect();
+            let first = lines * 3 / 4;
+            let query = rows[first..first + block]
+                .concat()
+                .replacen("compute", "compote", 1);
+
+            let started = Instant::now();
+            let result = fuzzy_index_of(&content, &query, None);
+            let current = started.elapsed();
+
+            let started = Instant::now();
+            let previous = previous_locator::locate(&content, &query, 0, content.len(), usize::MAX);
+            let previous_time = started.elapsed();
+
+            println!(
+                "{lines} lines, {block}-line block: fuzzy_index_of {current:?} (distance {}), \
+                 previous locator {previous_time:?} (distance {previous})",
+                result.distance
+            );
+        }
+    }
+}
@@ -1,28 +1,42 @@
 // Local modules (moved from kodegen-utils)
-mod char_analysis;
-mod char_diff;
+pub(crate) mod char_analysis;
+pub(crate) mod char_diff;
 mod edit_log;
 mod fuzzy_logger;
-mod fuzzy_search;
+pub(crate) mod fuzzy_search;
 mod line_endings;
+pub mod pattern;
+pub mod scope;
 mod suggestions;
-
+pub mod whitespace;
+
+use crate::atomic_write::write_atomic;
+use crate::diff::{DEFAULT_CONTEXT_LINES, unified_diff};
+use crate::encoding::{self, resolve_label};
+use crate::journal::Recorder;
+use crate::precondition::{content_hash, read_if_match};
+use crate::schema::{
+    DEFAULT_FUZZY_ACCEPT_THRESHOLD, EditOperation, EditPatternMode, FsEditBlockArgs,
+    FsEditBlockOutput, FuzzyMatchApplied, ReplacedSpan,
+};
 use crate::validate_path;
 use chrono::Utc;
 use kodegen_config::shorten_path_for_display;
-use kodegen_mcp_schema::filesystem::{FsEditBlockArgs, FsEditBlockOutput, EditBlockPrompts};
+use kodegen_mcp_schema::filesystem::EditBlockPrompts;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 
 use char_analysis::CharCodeData;
 use char_diff::CharDiff;
 use edit_log::{EditBlockLogEntry, EditBlockResult, get_edit_logger};
 use fuzzy_logger::{FuzzySearchLogEntry, get_logger};
-use fuzzy_search::{get_similarity_ratio, recursive_fuzzy_index_of_with_defaults};
-use line_endings::{detect_line_ending, normalize_line_endings};
+use fuzzy_search::{fuzzy_index_of, get_similarity_ratio, max_distance_for_similarity};
+use line_endings::{LineEndingStyle, detect_line_ending, normalize_line_endings};
+use pattern::{RegexReplacement, apply_replacements, regex_replacements};
+use scope::{find_in_span, line_span, nth_occurrence, replace_at};
 use suggestions::{EditFailureReason, Suggestion, SuggestionContext};
+use whitespace::normalized_replacements;
 
 use std::time::Instant;
-use tokio::fs;
 
 // ============================================================================
 // HELPER FUNCTIONS
@@ -48,6 +62,46 @@ fn count_lines_before_index(content: &str, byte_index: usize) -> usize {
     content[..safe_index].matches('\n').count() + 1
 }
 
+/// Similarity a fuzzy match needs for `accept_fuzzy` to apply it
+///
+/// `requested` defaults to [`DEFAULT_FUZZY_ACCEPT_THRESHOLD`] and is raised
+/// to `suggest_threshold`, the threshold for merely suggesting a match.
+///
+/// # Errors
+/// Returns an error if `requested` is outside 0.0-1.0
+pub fn fuzzy_accept_threshold(
+    requested: Option<f64>,
+    suggest_threshold: f64,
+) -> Result<f64, McpError> {
+    let threshold = requested.unwrap_or(DEFAULT_FUZZY_ACCEPT_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(McpError::InvalidArguments(format!(
+            "accept_fuzzy_threshold must be between 0.0 and 1.0, got {threshold}"
+        )));
+    }
+    Ok(threshold.max(suggest_threshold))
+}
+
+/// One edit applied in memory
+struct AppliedEdit {
+    content: String,
+    replacements: usize,
+    /// Spans replaced by regex, whitespace-normalized and fuzzy edits
+    replaced: Vec<ReplacedSpan>,
+    whitespace_normalized: bool,
+    fuzzy_match: Option<FuzzyMatchApplied>,
+    /// How the replaced text was found, for the edit log
+    result: EditBlockResult,
+    /// Similarity of the replaced text to `old_string` (`None` for regex edits)
+    similarity: Option<f64>,
+    /// The replaced text, one line per span when several were replaced
+    found_text: String,
+    /// Bytes removed and inserted across every replacement, as written
+    /// (after capture expansion or re-indenting)
+    old_len: usize,
+    new_len: usize,
+}
+
 // ============================================================================
 // TOOL STRUCT
 // ============================================================================
@@ -62,113 +116,108 @@ impl EditBlockTool {
     pub fn new(config_manager: kodegen_config_manager::ConfigManager) -> Self {
         Self { config_manager }
     }
-}
-
-// ============================================================================
-// TOOL IMPLEMENTATION
-// ============================================================================
-
-impl Tool for EditBlockTool {
-    type Args = FsEditBlockArgs;
-    type Prompts = EditBlockPrompts;
-
-    fn name() -> &'static str {
-        kodegen_mcp_schema::filesystem::FS_EDIT_BLOCK
-    }
-
-    fn description() -> &'static str {
-        "Apply surgical text replacements to files. Takes old_string and new_string, and performs \
-         exact string replacement. By default replaces one occurrence. To replace multiple, set \
-         expected_replacements. Returns error if old_string not found, or warning if actual count \
-         doesn't match expected. Automatically validates paths."
-    }
-
-    fn read_only() -> bool {
-        false
-    }
-
-    fn destructive() -> bool {
-        true // Modifies file content
-    }
-
-    fn idempotent() -> bool {
-        false // Each replacement changes content
-    }
-
-    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
-        let start_time = Instant::now(); // START TIMER
 
+    /// Apply one edit to `content` in memory
+    ///
+    /// Nothing is written; a failed literal edit carries the same fuzzy-match
+    /// diagnostics and suggestions as a single-edit call. With
+    /// `accept_fuzzy` set to a threshold, a fuzzy match at least that
+    /// similar is replaced instead.
+    #[allow(clippy::too_many_arguments)]
+    async fn apply_edit(
+        &self,
+        content: &str,
+        edit: &EditOperation,
+        pattern_mode: EditPatternMode,
+        accept_fuzzy: Option<f64>,
+        file_line_ending: LineEndingStyle,
+        path: &str,
+        extension: &str,
+        start_time: Instant,
+    ) -> Result<AppliedEdit, McpError> {
         // Validate inputs
-        if args.old_string.is_empty() {
+        if edit.old_string.is_empty() {
             return Err(McpError::InvalidArguments(
                 "Empty search strings are not allowed. Please provide a non-empty string to search for.".to_string()
             ));
         }
 
-        if args.old_string == args.new_string {
+        if edit.old_string == edit.new_string {
             return Err(McpError::InvalidArguments(
                 "old_string and new_string are identical. No changes would be made.".to_string(),
             ));
         }
 
-        let valid_path = validate_path(&args.path, &self.config_manager, ctx.pwd()).await?;
-
-        // Get file extension for response
-        let extension = valid_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or_default()
-            .to_string();
-
-        // Read file
-        let content = fs::read_to_string(&valid_path).await?;
-
-        // Detect file's line ending style
-        let file_line_ending = detect_line_ending(&content);
+        let span = line_span(content, edit.start_line, edit.end_line)?;
+        if pattern_mode == EditPatternMode::Regex {
+            let mut applied = apply_regex_edit(content, span, edit, file_line_ending, path)?;
+            applied.result = EditBlockResult::RegexMatch;
+            applied.similarity = None;
+            return Ok(applied);
+        }
 
         // Normalize search string to match file's line endings
-        let normalized_old_string = normalize_line_endings(&args.old_string, file_line_ending);
-
-        // Check line limits and generate warning if exceeded
-        let line_limit = self.config_manager.get_file_write_line_limit();
-        let search_lines = normalized_old_string.lines().count().max(1);
-        let replace_lines = args.new_string.lines().count().max(1);
-        let max_lines = search_lines.max(replace_lines);
-
-        let warning = if max_lines > line_limit {
-            let problem_text = if search_lines > replace_lines {
-                "search text"
-            } else {
-                "replacement text"
-            };
-            format!(
-                "\n\nWARNING: The {problem_text} has {max_lines} lines (maximum: {line_limit}).\n\n\
-                 RECOMMENDATION: For large search/replace operations, consider breaking them \
-                 into smaller chunks with fewer lines."
-            )
-        } else {
-            String::new()
-        };
+        let normalized_old_string = normalize_line_endings(&edit.old_string, file_line_ending);
+
+        // Count occurrences using normalized search, within the line range if given
+        let matches = find_in_span(content, span.clone(), &normalized_old_string);
+        let occurrence_count = matches.len();
+
+        // Fall back to whole lines compared without indentation and trailing whitespace
+        if occurrence_count == 0 && pattern_mode == EditPatternMode::NormalizeWhitespace {
+            let found = normalized_replacements(
+                content,
+                span.clone(),
+                &edit.old_string,
+                &edit.new_string,
+                file_line_ending.as_str(),
+            );
+            if !found.is_empty() {
+                let mut applied = replace_found(content, found, edit, path)?;
+                applied.whitespace_normalized = true;
+                applied.result = EditBlockResult::WhitespaceNormalizedMatch;
+                applied.similarity =
+                    Some(get_similarity_ratio(&applied.found_text, &edit.old_string));
+                return Ok(applied);
+            }
+        }
 
-        // Count occurrences using normalized search
-        let occurrence_count = content.matches(&normalized_old_string).count();
+        if occurrence_count == 0 && (span.start > 0 || span.end < content.len()) {
+            let outside = content.matches(&normalized_old_string).count();
+            if outside > 0 {
+                return Err(McpError::InvalidArguments(format!(
+                    "old_string occurs {outside} time(s) in {}, but not within lines {}-{}. \
+                     Widen start_line/end_line or drop them to search the whole file.",
+                    path,
+                    edit.start_line.unwrap_or(1),
+                    edit.end_line.map_or_else(|| "end".to_string(), |end| end.to_string()),
+                )));
+            }
+        }
 
         // Handle no exact matches - try fuzzy search
         if occurrence_count == 0 {
             // Measure fuzzy search performance
             let start = std::time::Instant::now();
 
-            // Attempt fuzzy match
-            let fuzzy_result = recursive_fuzzy_index_of_with_defaults(&content, &args.old_string);
+            // Get configurable threshold from config
+            let threshold = self.config_manager.get_fuzzy_search_threshold();
+
+            // Attempt fuzzy match within the line range, giving up on
+            // candidates that cannot reach the threshold
+            let mut fuzzy_result = fuzzy_index_of(
+                &content[span.clone()],
+                &edit.old_string,
+                Some(max_distance_for_similarity(edit.old_string.len(), threshold)),
+            );
+            fuzzy_result.start += span.start;
+            fuzzy_result.end += span.start;
 
             // Calculate elapsed time in milliseconds
             let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
 
             // Calculate similarity using standard function
-            let similarity = get_similarity_ratio(&fuzzy_result.value, &args.old_string);
-
-            // Get configurable threshold from config
-            let threshold = self.config_manager.get_fuzzy_search_threshold();
+            let similarity = get_similarity_ratio(&fuzzy_result.value, &edit.old_string);
 
             // Get execution time for logging
             let execution_time = start_time.elapsed().as_secs_f64() * 1000.0;
@@ -176,18 +225,18 @@ impl Tool for EditBlockTool {
             // Log fuzzy search attempt (FIRE-AND-FORGET, NEVER BLOCKS!)
             let log_entry = EditBlockLogEntry {
                 timestamp: Utc::now(),
-                search_text: args.old_string.clone(),
+                search_text: edit.old_string.clone(),
                 found_text: Some(fuzzy_result.value.clone()),
                 similarity: Some(similarity),
                 execution_time_ms: execution_time,
                 exact_match_count: 0,
-                expected_replacements: args.expected_replacements,
+                expected_replacements: edit.expected_replacements,
                 fuzzy_threshold: threshold,
                 below_threshold: similarity < threshold,
                 diff: None,
-                search_length: args.old_string.len(),
+                search_length: edit.old_string.len(),
                 found_length: Some(fuzzy_result.value.len()),
-                file_extension: extension.clone(),
+                file_extension: extension.to_string(),
                 character_codes: None,
                 unique_character_count: None,
                 diff_length: None,
@@ -202,29 +251,55 @@ impl Tool for EditBlockTool {
 
             if similarity >= threshold {
                 // Found similar text - show character diff
-                let diff = CharDiff::new(&args.old_string, &fuzzy_result.value);
+                let diff = CharDiff::new(&edit.old_string, &fuzzy_result.value);
                 let diff_display = diff.format();
                 let is_whitespace_only = diff.is_whitespace_only();
 
                 // Calculate line number where match was found
-                let line_number = count_lines_before_index(&content, fuzzy_result.start);
+                let line_number = count_lines_before_index(content, fuzzy_result.start);
+
+                // Close enough to apply: replace the span the fuzzy search found.
+                // It is a single match, so only single-match edits qualify.
+                let single = edit.occurrence.is_some() || edit.expected_replacements == 1;
+                if let Some(accept_threshold) = accept_fuzzy
+                    && similarity >= accept_threshold
+                    && single
+                    && fuzzy_result.start < fuzzy_result.end
+                {
+                    let start = content.ceil_char_boundary(fuzzy_result.start);
+                    let end = content.floor_char_boundary(fuzzy_result.end);
+                    let found = vec![RegexReplacement {
+                        span: start..end,
+                        text: normalize_line_endings(&edit.new_string, file_line_ending),
+                    }];
+                    let mut applied = replace_found(content, found, edit, path)?;
+                    applied.result = EditBlockResult::FuzzyMatchAccepted;
+                    applied.similarity = Some(similarity);
+                    applied.fuzzy_match = Some(FuzzyMatchApplied {
+                        line: line_number,
+                        similarity,
+                        matched_text: content[start..end].to_string(),
+                        char_diff: diff_display,
+                    });
+                    return Ok(applied);
+                }
 
                 // Log the fuzzy match attempt
                 let logger = get_logger().await;
                 let fuzzy_log_entry = FuzzySearchLogEntry {
                     timestamp: Utc::now(),
-                    search_text: args.old_string.clone(),
+                    search_text: edit.old_string.clone(),
                     found_text: fuzzy_result.value.clone(),
                     similarity,
                     execution_time_ms: elapsed_ms,
                     exact_match_count: 0,
-                    expected_replacements: args.expected_replacements,
+                    expected_replacements: edit.expected_replacements,
                     fuzzy_threshold: threshold,
                     below_threshold: false,
                     diff: diff_display.clone(),
-                    search_length: args.old_string.len(),
+                    search_length: edit.old_string.len(),
                     found_length: fuzzy_result.value.len(),
-                    file_extension: extension.clone(),
+                    file_extension: extension.to_string(),
                 };
 
                 let _ = logger.log(&fuzzy_log_entry).await; // Ignore log errors
@@ -233,9 +308,10 @@ impl Tool for EditBlockTool {
 
                 // Build suggestion context
                 let context = SuggestionContext {
-                    file_path: args.path.clone(),
-                    search_string: args.old_string.clone(),
+                    file_path: path.to_string(),
+                    search_string: edit.old_string.clone(),
                     line_number: Some(line_number),
+                    accept_fuzzy,
                     log_path,
                     execution_time_ms: Some(elapsed_ms),
                 };
@@ -250,7 +326,7 @@ impl Tool for EditBlockTool {
                 );
 
                 // Perform comprehensive character analysis
-                let char_data = CharCodeData::analyze(&args.old_string, &fuzzy_result.value);
+                let char_data = CharCodeData::analyze(&edit.old_string, &fuzzy_result.value);
 
                 // Build complete error message
                 let mut error_msg = suggestion.message.clone();
@@ -271,27 +347,27 @@ impl Tool for EditBlockTool {
             }
 
             // Calculate line number where match was found
-            let line_number = count_lines_before_index(&content, fuzzy_result.start);
+            let line_number = count_lines_before_index(content, fuzzy_result.start);
 
             // Log the fuzzy match attempt (below threshold)
-            let diff = CharDiff::new(&args.old_string, &fuzzy_result.value);
+            let diff = CharDiff::new(&edit.old_string, &fuzzy_result.value);
             let diff_display = diff.format();
 
             let logger = get_logger().await;
             let fuzzy_log_entry = FuzzySearchLogEntry {
                 timestamp: Utc::now(),
-                search_text: args.old_string.clone(),
+                search_text: edit.old_string.clone(),
                 found_text: fuzzy_result.value.clone(),
                 similarity,
                 execution_time_ms: elapsed_ms,
                 exact_match_count: 0,
-                expected_replacements: args.expected_replacements,
+                expected_replacements: edit.expected_replacements,
                 fuzzy_threshold: threshold,
                 below_threshold: true,
                 diff: diff_display,
-                search_length: args.old_string.len(),
+                search_length: edit.old_string.len(),
                 found_length: fuzzy_result.value.len(),
-                file_extension: extension.clone(),
+                file_extension: extension.to_string(),
             };
 
             let _ = logger.log(&fuzzy_log_entry).await; // Ignore log errors
@@ -300,9 +376,10 @@ impl Tool for EditBlockTool {
 
             // No good fuzzy match found - below threshold
             let context = SuggestionContext {
-                file_path: args.path.clone(),
-                search_string: args.old_string.clone(),
+                file_path: path.to_string(),
+                search_string: edit.old_string.clone(),
                 line_number: Some(line_number),
+                accept_fuzzy,
                 log_path,
                 execution_time_ms: Some(elapsed_ms),
             };
@@ -324,108 +401,443 @@ impl Tool for EditBlockTool {
             return Err(McpError::InvalidArguments(error_msg));
         }
 
+        let selected = select_matches(matches, edit, content, path)?;
+        let replacements = selected.len();
+
         // Perform replacement using normalized strings
-        let normalized_new_string = normalize_line_endings(&args.new_string, file_line_ending);
-        let new_content = content.replace(&normalized_old_string, &normalized_new_string);
+        let normalized_new_string = normalize_line_endings(&edit.new_string, file_line_ending);
+        let new_content = replace_at(
+            content,
+            &selected,
+            normalized_old_string.len(),
+            &normalized_new_string,
+        );
 
-        fs::write(&valid_path, &new_content).await?;
+        Ok(AppliedEdit {
+            content: new_content,
+            replacements,
+            replaced: Vec::new(),
+            whitespace_normalized: false,
+            fuzzy_match: None,
+            result: EditBlockResult::ExactMatch,
+            similarity: Some(1.0),
+            found_text: normalized_old_string.clone(),
+            old_len: replacements * normalized_old_string.len(),
+            new_len: replacements * normalized_new_string.len(),
+        })
+    }
+}
 
-        // Build response based on match status
-        let execution_time = start_time.elapsed().as_secs_f64() * 1000.0;
+/// Pick the matches to replace: the selected occurrence, or all of them when
+/// there are exactly as many as expected
+fn select_matches(
+    matches: Vec<usize>,
+    edit: &EditOperation,
+    content: &str,
+    path: &str,
+) -> Result<Vec<usize>, McpError> {
+    if let Some(occurrence) = edit.occurrence {
+        return Ok(vec![nth_occurrence(&matches, occurrence)?]);
+    }
+    if matches.len() == edit.expected_replacements {
+        return Ok(matches);
+    }
 
-        // Log successful exact match (FIRE-AND-FORGET, NEVER BLOCKS!)
-        let log_entry = EditBlockLogEntry {
-            timestamp: Utc::now(),
-            search_text: args.old_string.clone(),
-            found_text: Some(args.old_string.clone()),
-            similarity: Some(1.0), // Exact match
-            execution_time_ms: execution_time,
-            exact_match_count: occurrence_count,
-            expected_replacements: args.expected_replacements,
-            fuzzy_threshold: self.config_manager.get_fuzzy_search_threshold(),
-            below_threshold: false,
-            diff: None,
-            search_length: args.old_string.len(),
-            found_length: Some(args.old_string.len()),
-            file_extension: extension.clone(),
-            character_codes: None,
-            unique_character_count: None,
-            diff_length: None,
-            result: EditBlockResult::ExactMatch,
+    let context = SuggestionContext {
+        file_path: path.to_string(),
+        search_string: edit.old_string.clone(),
+        line_number: Some(count_lines_before_index(content, matches[0])),
+        accept_fuzzy: None,
+        log_path: None,
+        execution_time_ms: None,
+    };
+    let suggestion = Suggestion::for_failure(
+        &EditFailureReason::UnexpectedCount {
+            expected: edit.expected_replacements,
+            found: matches.len(),
+        },
+        &context,
+    );
+    Err(McpError::InvalidArguments(format!(
+        "{} The file was not modified.{}",
+        suggestion.message,
+        suggestion.format()
+    )))
+}
+
+/// Apply a regex edit: `old_string` is the pattern and `new_string` the
+/// replacement, with capture references
+fn apply_regex_edit(
+    content: &str,
+    span: std::ops::Range<usize>,
+    edit: &EditOperation,
+    file_line_ending: LineEndingStyle,
+    path: &str,
+) -> Result<AppliedEdit, McpError> {
+    let replacement = normalize_line_endings(&edit.new_string, file_line_ending);
+    let scoped = span.start > 0 || span.end < content.len();
+    let found = regex_replacements(content, span, &edit.old_string, &replacement)?;
+    if found.is_empty() {
+        return Err(McpError::InvalidArguments(format!(
+            "Pattern {:?} matches nothing in {path}{}",
+            edit.old_string,
+            if scoped {
+                format!(
+                    " within lines {}-{}",
+                    edit.start_line.unwrap_or(1),
+                    edit.end_line.map_or_else(|| "end".to_string(), |end| end.to_string())
+                )
+            } else {
+                String::new()
+            }
+        )));
+    }
+
+    replace_found(content, found, edit, path)
+}
+
+/// Replace the selected ones of `found` (ascending, non-overlapping),
+/// listing each replaced span
+fn replace_found(
+    content: &str,
+    found: Vec<RegexReplacement>,
+    edit: &EditOperation,
+    path: &str,
+) -> Result<AppliedEdit, McpError> {
+    let starts = found.iter().map(|r| r.span.start).collect();
+    let selected = select_matches(starts, edit, content, path)?;
+    let found: Vec<_> = found
+        .into_iter()
+        .filter(|r| selected.binary_search(&r.span.start).is_ok())
+        .collect();
+
+    let (new_content, new_starts) = apply_replacements(content, &found);
+    let spans: Vec<ReplacedSpan> = found
+        .iter()
+        .zip(new_starts)
+        .map(|(r, new_start)| ReplacedSpan {
+            line_before: count_lines_before_index(content, r.span.start),
+            line_after: count_lines_before_index(&new_content, new_start),
+            old_text: content[r.span.clone()].to_string(),
+            new_text: r.text.clone(),
+        })
+        .collect();
+    let found_text = spans
+        .iter()
+        .map(|span| span.old_text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(AppliedEdit {
+        content: new_content,
+        replacements: found.len(),
+        old_len: spans.iter().map(|span| span.old_text.len()).sum(),
+        new_len: spans.iter().map(|span| span.new_text.len()).sum(),
+        replaced: spans,
+        whitespace_normalized: false,
+        fuzzy_match: None,
+        result: EditBlockResult::ExactMatch,
+        similarity: Some(1.0),
+        found_text,
+    })
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for EditBlockTool {
+    type Args = FsEditBlockArgs;
+    type Prompts = EditBlockPrompts;
+
+    fn name() -> &'static str {
+        kodegen_mcp_schema::filesystem::FS_EDIT_BLOCK
+    }
+
+    fn description() -> &'static str {
+        "Apply surgical text replacements to files. Takes old_string and new_string, and performs \
+         exact string replacement. By default replaces one occurrence. To replace multiple, set \
+         expected_replacements; the file is left untouched unless exactly that many matches are \
+         found. Set occurrence to replace only one match of several (1 = first, 2 = second, \
+         -1 = last), and start_line/end_line to only match within a line range. Returns error if \
+         old_string not found. Set pattern_mode: \"regex\" to treat old_string as a regex (Rust \
+         syntax, PCRE2 fallback for look-around and backreferences) and new_string as a \
+         replacement with $1 / ${name} capture references; the response lists each replaced span \
+         with its line before and after. Set pattern_mode: \"normalize_whitespace\" to match \
+         old_string's lines ignoring indentation and trailing whitespace when the exact text is \
+         not found; new_string is then re-indented to the file's indentation. Set accept_fuzzy: \
+         true to apply a literal edit to a near match at least accept_fuzzy_threshold similar \
+         (default 0.95) instead of failing; the response lists each fuzzy match with its \
+         character diff. For refactors, pass edits (a list of {old_string, new_string, \
+         expected_replacements, ...}) instead: they apply in order in memory and the file is \
+         written once, or not at all if any edit fails (the error names the failing edits[i]). \
+         Files in other encodings (detected from the BOM, or given with the encoding parameter, \
+         e.g. \"utf-16le\", \"shiftjis\") are edited in place and written back in the same \
+         encoding and BOM. Pass if_match (content_hash or modified from fs_read_file) to refuse \
+         the edit if the file changed since it was read, or dry_run: true to get a unified diff \
+         of the change without writing it. The file is replaced atomically (fsynced temp file \
+         renamed into place, keeping its permissions); the response carries a unified diff of the \
+         change (diff_context sets its context lines, default 3) and an undo_id for fs_undo. \
+         Automatically validates paths."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true // Modifies file content
+    }
+
+    fn idempotent() -> bool {
+        false // Each replacement changes content
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let start_time = Instant::now(); // START TIMER
+
+        // Edits to apply in order: the list, or the single top-level edit
+        let edits = if args.edits.is_empty() {
+            vec![EditOperation {
+                old_string: args.old_string.clone(),
+                new_string: args.new_string.clone(),
+                expected_replacements: args.expected_replacements,
+                occurrence: args.occurrence,
+                start_line: args.start_line,
+                end_line: args.end_line,
+            }]
+        } else if args.old_string.is_empty() && args.new_string.is_empty() {
+            args.edits.clone()
+        } else {
+            return Err(McpError::InvalidArguments(
+                "Pass either old_string/new_string or edits, not both.".to_string(),
+            ));
         };
+        let accept_fuzzy = if args.accept_fuzzy {
+            Some(fuzzy_accept_threshold(
+                args.accept_fuzzy_threshold,
+                self.config_manager.get_fuzzy_search_threshold(),
+            )?)
+        } else {
+            None
+        };
+
+        let valid_path = validate_path(&args.path, &self.config_manager, ctx.pwd()).await?;
+        let explicit_encoding = args.encoding.as_deref().map(resolve_label).transpose()?;
+
+        // Get file extension for response
+        let extension = valid_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        // Read file once: if_match is checked against the exact bytes edited
+        let bytes = read_if_match(&valid_path, args.if_match.as_deref())
+            .await?
+            .ok_or_else(|| {
+                McpError::ResourceNotFound(format!("{} does not exist", valid_path.display()))
+            })?;
+
+        // Decode it from its own encoding
+        let text_encoding = encoding::detect(&bytes, explicit_encoding);
+        let content = encoding::decode(&bytes, text_encoding)?;
 
-        get_edit_logger().log(log_entry);
+        // Detect file's line ending style
+        let file_line_ending = detect_line_ending(&content);
+
+        // Check line limits and generate warning if exceeded
+        let line_limit = self.config_manager.get_file_write_line_limit();
+        let search_lines = edits
+            .iter()
+            .map(|edit| edit.old_string.lines().count().max(1))
+            .max()
+            .unwrap_or(1);
+        let replace_lines = edits
+            .iter()
+            .map(|edit| edit.new_string.lines().count().max(1))
+            .max()
+            .unwrap_or(1);
+        let max_lines = search_lines.max(replace_lines);
 
-        if occurrence_count == args.expected_replacements {
-            // Exact match - success
-            let delta = args.new_string.len() as i64 - args.old_string.len() as i64;
-            let delta_str = if delta >= 0 {
-                format!("+{delta}")
+        let warning = if max_lines > line_limit {
+            let problem_text = if search_lines > replace_lines {
+                "search text"
             } else {
-                format!("{delta}")
+                "replacement text"
             };
-            let display_path = shorten_path_for_display(
-                std::path::Path::new(&args.path),
-                ctx.git_root()
-            );
+            format!(
+                "\n\nWARNING: The {problem_text} has {max_lines} lines (maximum: {line_limit}).\n\n\
+                 RECOMMENDATION: For large search/replace operations, consider breaking them \
+                 into smaller chunks with fewer lines."
+            )
+        } else {
+            String::new()
+        };
+
+        // Apply every edit in memory; the first failure aborts before anything is written
+        let mut new_content = content.clone();
+        let mut applied_edits = Vec::with_capacity(edits.len());
+        let mut replaced = Vec::new();
+        let mut whitespace_normalized = false;
+        let mut fuzzy_matches = Vec::new();
+        for (index, edit) in edits.iter().enumerate() {
+            let mut applied = self
+                .apply_edit(
+                    &new_content,
+                    edit,
+                    args.pattern_mode,
+                    accept_fuzzy,
+                    file_line_ending,
+                    &args.path,
+                    &extension,
+                    start_time,
+                )
+                .await
+                .map_err(|e| match e {
+                    McpError::InvalidArguments(msg) if !args.edits.is_empty() => {
+                        McpError::InvalidArguments(format!(
+                            "edits[{index}] failed ({} edit(s) in total); the file was not \
+                             modified.\n\n{msg}",
+                            edits.len()
+                        ))
+                    }
+                    e => e,
+                })?;
+            new_content = std::mem::take(&mut applied.content);
+            replaced.extend(std::mem::take(&mut applied.replaced));
+            whitespace_normalized |= applied.whitespace_normalized;
+            fuzzy_matches.extend(applied.fuzzy_match.take());
+            applied_edits.push(applied);
+        }
+        let replacements: usize = applied_edits.iter().map(|applied| applied.replacements).sum();
+        let replaced_list: String = replaced
+            .iter()
+            .map(|span| {
+                format!(
+                    "\n  line {} → {}: {:?} → {:?}",
+                    span.line_before, span.line_after, span.old_text, span.new_text
+                )
+            })
+            .collect();
+        let normalized_note = if whitespace_normalized {
+            "\nMatched with normalized indentation and trailing whitespace; new_string was \
+             re-indented to the file's indentation."
+        } else {
+            ""
+        };
+        let fuzzy_note: String = fuzzy_matches
+            .iter()
+            .map(|m| {
+                format!(
+                    "\n\x1b[33mFuzzy match applied at line {} ({:.1}% similar): {}\x1b[0m",
+                    m.line,
+                    m.similarity * 100.0,
+                    m.char_diff
+                )
+            })
+            .collect();
+
+        // Write back in the original encoding and BOM, atomically
+        let new_bytes = encoding::encode(&new_content, text_encoding)?;
+        let diff = unified_diff(
+            &args.path,
+            &content,
+            &new_content,
+            args.diff_context.unwrap_or(DEFAULT_CONTEXT_LINES),
+        );
+
+        if args.dry_run {
+            let display_path = shorten_path_for_display(&valid_path, ctx.git_root());
             let summary = format!(
-                "\x1b[33m{} replacement(s) in {display_path}\x1b[0m\n\
-                 Precision: {} → {} bytes (delta: {delta_str}){warning}",
-                occurrence_count,
-                args.old_string.len(),
-                args.new_string.len(),
+                "\x1b[36mDry run: would make {replacements} replacement(s) in \
+                 {display_path}\x1b[0m{normalized_note}{fuzzy_note}{replaced_list}{warning}\n{diff}"
             );
-
             return Ok(ToolResponse::new(summary, FsEditBlockOutput {
                 success: true,
                 path: valid_path.to_string_lossy().to_string(),
-                replacements_made: occurrence_count as u32,
-                message: format!("Successfully replaced {} occurrence(s)", occurrence_count),
+                replacements_made: replacements as u32,
+                message: format!("Dry run: would replace {replacements} occurrence(s)"),
+                encoding: text_encoding.label(),
+                content_hash: None,
+                undo_id: None,
+                dry_run: true,
+                diff: Some(diff),
+                replaced,
+                whitespace_normalized,
+                fuzzy_matches,
             }));
         }
 
-        // Mismatch - success with warning and suggestions
-        let context = SuggestionContext {
-            file_path: args.path.clone(),
-            search_string: args.old_string.clone(),
-            line_number: None,
-            log_path: None,
-            execution_time_ms: None,
-        };
+        let new_hash = content_hash(&new_bytes);
+        let mut undo = Recorder::for_call(&ctx, Self::name(), format!("edit {}", args.path));
+        undo.snapshot(&valid_path).await;
+        write_atomic(&valid_path, new_bytes).await?;
+        let undo_id = undo.commit().await;
 
-        let suggestion = Suggestion::for_failure(
-            &EditFailureReason::UnexpectedCount {
-                expected: args.expected_replacements,
-                found: occurrence_count,
-            },
-            &context,
-        );
+        // Build response based on match status
+        let execution_time = start_time.elapsed().as_secs_f64() * 1000.0;
 
-        // Human summary with warning
-        let delta = args.new_string.len() as i64 - args.old_string.len() as i64;
+        // Log each applied edit with how it matched (FIRE-AND-FORGET, NEVER BLOCKS!)
+        for (edit, applied) in edits.iter().zip(&applied_edits) {
+            let log_entry = EditBlockLogEntry {
+                timestamp: Utc::now(),
+                search_text: edit.old_string.clone(),
+                found_length: Some(applied.found_text.len()),
+                found_text: Some(applied.found_text.clone()),
+                similarity: applied.similarity,
+                execution_time_ms: execution_time,
+                exact_match_count: applied.replacements,
+                expected_replacements: edit.expected_replacements,
+                fuzzy_threshold: self.config_manager.get_fuzzy_search_threshold(),
+                below_threshold: false,
+                diff: Some(diff.clone()),
+                search_length: edit.old_string.len(),
+                file_extension: extension.clone(),
+                character_codes: None,
+                unique_character_count: None,
+                diff_length: Some(diff.len()),
+                result: applied.result.clone(),
+            };
+
+            get_edit_logger().log(log_entry);
+        }
+
+        // Bytes the replacements actually removed and inserted
+        let old_len: usize = applied_edits.iter().map(|applied| applied.old_len).sum();
+        let new_len: usize = applied_edits.iter().map(|applied| applied.new_len).sum();
+        let delta = new_len as i64 - old_len as i64;
         let delta_str = if delta >= 0 {
             format!("+{delta}")
         } else {
             format!("{delta}")
         };
+        let edit_count = if args.edits.is_empty() {
+            String::new()
+        } else {
+            format!(" from {} edits", edits.len())
+        };
         let display_path = shorten_path_for_display(
             std::path::Path::new(&args.path),
             ctx.git_root()
         );
         let summary = format!(
-            "\x1b[33m{} replacement(s) in {display_path}\x1b[0m\n\
-             Precision: {} → {} bytes (delta: {delta_str}) · Expected: {}{warning}",
-            occurrence_count,
-            args.old_string.len(),
-            args.new_string.len(),
-            args.expected_replacements,
+            "\x1b[33m{replacements} replacement(s){edit_count} in {display_path}\x1b[0m\n\
+             Precision: {old_len} → {new_len} bytes (delta: {delta_str}){normalized_note}{fuzzy_note}{replaced_list}{warning}\n{diff}"
         );
 
         Ok(ToolResponse::new(summary, FsEditBlockOutput {
             success: true,
             path: valid_path.to_string_lossy().to_string(),
-            replacements_made: occurrence_count as u32,
-            message: format!("Warning: {} - Expected {} replacements but made {}", suggestion.message, args.expected_replacements, occurrence_count),
+            replacements_made: replacements as u32,
+            message: format!("Successfully replaced {replacements} occurrence(s){edit_count}"),
+            encoding: text_encoding.label(),
+            content_hash: Some(new_hash),
+            undo_id,
+            dry_run: false,
+            diff: Some(diff),
+            replaced,
+            whitespace_normalized,
+            fuzzy_matches,
         }))
     }
 }
@@ -0,0 +1,142 @@
+//! Regex matching for `pattern_mode: "regex"` edits
+//!
+//! Patterns are compiled by the search tool's [`build_pattern_matcher`]:
+//! Rust regex, falling back to PCRE2 for patterns it cannot compile
+//! (look-around, backreferences). Replacements expand `$1`, `${1}` and
+//! `${name}` capture references; `$$` is a literal `$`.
+
+use crate::search::rg::{PatternMatcher, build_pattern_matcher};
+use crate::search::types::{CaseMode, Engine};
+use grep::matcher::{Captures, Matcher};
+use kodegen_mcp_schema::McpError;
+use std::ops::Range;
+
+/// One regex match and the text that replaces it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexReplacement {
+    /// Byte range of the match in the searched content
+    pub span: Range<usize>,
+    /// The replacement with its capture references expanded
+    pub text: String,
+}
+
+/// Every match of `pattern` lying in `span` of `content`, with its expanded
+/// replacement
+///
+/// # Errors
+/// Returns `InvalidArguments` if the pattern compiles with neither engine or
+/// matches the empty string
+pub fn regex_replacements(
+    content: &str,
+    span: Range<usize>,
+    pattern: &str,
+    replacement: &str,
+) -> Result<Vec<RegexReplacement>, McpError> {
+    let matcher = build_pattern_matcher(pattern, Engine::Auto, CaseMode::Sensitive, false, false)
+        .map_err(|e| McpError::InvalidArguments(format!("Invalid regex pattern: {e}")))?;
+    reject_empty_matches(&matcher, pattern).map_err(McpError::InvalidArguments)?;
+    let offset = span.start;
+    let haystack = &content[span];
+    let found = expand_matches(&matcher, haystack, replacement)
+        .map_err(|e| McpError::InvalidArguments(format!("Regex search failed: {e}")))?;
+
+    Ok(found
+        .into_iter()
+        .map(|found| RegexReplacement {
+            span: found.span.start + offset..found.span.end + offset,
+            text: found.text,
+        })
+        .collect())
+}
+
+/// Refuse a pattern that matches the empty string
+///
+/// Such a pattern (`x*`, `a?`, an empty pattern) matches between every pair
+/// of characters, so replacing its matches inserts the replacement
+/// everywhere instead of editing anything.
+///
+/// # Errors
+/// Returns the message explaining the refusal
+pub fn reject_empty_matches(matcher: &PatternMatcher, pattern: &str) -> Result<(), String> {
+    let matches_empty = match matcher {
+        PatternMatcher::RustRegex(m) => m.is_match(b"").map_err(|e| e.to_string())?,
+        PatternMatcher::PCRE2(m) => m.is_match(b"").map_err(|e| e.to_string())?,
+    };
+    if matches_empty {
+        return Err(format!(
+            "Pattern '{pattern}' matches the empty string, so it would insert the replacement \
+             at every position; make it match at least one character"
+        ));
+    }
+    Ok(())
+}
+
+/// Every match of `matcher` in `haystack`, with `replacement` expanded
+/// against its captures; spans are relative to `haystack`
+///
+/// # Errors
+/// Returns the matcher's error message if the search fails
+pub fn expand_matches(
+    matcher: &PatternMatcher,
+    haystack: &str,
+    replacement: &str,
+) -> Result<Vec<RegexReplacement>, String> {
+    match matcher {
+        PatternMatcher::RustRegex(m) => expand_all(m, haystack, replacement),
+        PatternMatcher::PCRE2(m) => expand_all(m, haystack, replacement),
+    }
+}
+
+fn expand_all<M: Matcher>(
+    matcher: &M,
+    haystack: &str,
+    replacement: &str,
+) -> Result<Vec<RegexReplacement>, String> {
+    let mut caps = matcher.new_captures().map_err(|e| e.to_string())?;
+    let mut found = Vec::new();
+    matcher
+        .captures_iter(haystack.as_bytes(), &mut caps, |caps| {
+            let Some(whole) = caps.get(0) else {
+                return true;
+            };
+            // Empty matches advance a byte at a time, which can land inside
+            // a multi-byte character
+            if !haystack.is_char_boundary(whole.start()) {
+                return true;
+            }
+            let mut text = Vec::new();
+            caps.interpolate(
+                |name| matcher.capture_index(name),
+                haystack.as_bytes(),
+                replacement.as_bytes(),
+                &mut text,
+            );
+            found.push(RegexReplacement {
+                span: whole.start()..whole.end(),
+                text: String::from_utf8_lossy(&text).into_owned(),
+            });
+            true
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(found)
+}
+
+/// Replace each match (ascending, non-overlapping) with its text; returns the
+/// new content and where each replacement starts in it
+#[must_use]
+pub fn apply_replacements(
+    content: &str,
+    replacements: &[RegexReplacement],
+) -> (String, Vec<usize>) {
+    let mut out = String::with_capacity(content.len());
+    let mut starts = Vec::with_capacity(replacements.len());
+    let mut last = 0;
+    for replacement in replacements {
+        out.push_str(&content[last..replacement.span.start]);
+        starts.push(out.len());
+        out.push_str(&replacement.text);
+        last = replacement.span.end;
+    }
+    out.push_str(&content[last..]);
+    (out, starts)
+}
@@ -0,0 +1,117 @@
+//! Which matches of `old_string` an edit touches: a line range to search in
+//! and an occurrence selector
+
+use kodegen_mcp_schema::McpError;
+use std::ops::Range;
+
+/// Byte range covering lines `start_line..=end_line` (1-based, inclusive)
+///
+/// Either bound may be omitted; the range then runs from the start or to the
+/// end of the file. The range ends after the last line's newline, so
+/// `old_string` values ending in a newline still match on that line.
+///
+/// # Errors
+/// Returns `InvalidArguments` for a zero or reversed range, or a start past
+/// the end of the file
+pub fn line_span(
+    content: &str,
+    start_line: Option<u64>,
+    end_line: Option<u64>,
+) -> Result<Range<usize>, McpError> {
+    let start = start_line.unwrap_or(1);
+    if start == 0 || end_line == Some(0) {
+        return Err(McpError::InvalidArguments(
+            "start_line and end_line are 1-based".to_string(),
+        ));
+    }
+    if let Some(end) = end_line
+        && end < start
+    {
+        return Err(McpError::InvalidArguments(format!(
+            "end_line ({end}) is before start_line ({start})"
+        )));
+    }
+
+    // Byte offset where each line begins
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(
+            content
+                .match_indices('\n')
+                .map(|(i, _)| i + 1)
+                .filter(|&i| i < content.len()),
+        )
+        .collect();
+
+    let begin = usize::try_from(start - 1)
+        .ok()
+        .and_then(|i| line_starts.get(i))
+        .copied()
+        .ok_or_else(|| {
+            McpError::InvalidArguments(format!(
+                "start_line {start} is past the end of the file ({} lines)",
+                line_starts.len()
+            ))
+        })?;
+    let end = end_line
+        .and_then(|end| usize::try_from(end).ok())
+        .and_then(|end| line_starts.get(end))
+        .copied()
+        .unwrap_or(content.len());
+
+    Ok(begin..end)
+}
+
+/// Byte offsets of the non-overlapping matches of `needle` lying wholly
+/// inside `span`
+#[must_use]
+pub fn find_in_span(content: &str, span: Range<usize>, needle: &str) -> Vec<usize> {
+    let offset = span.start;
+    content[span]
+        .match_indices(needle)
+        .map(|(i, _)| offset + i)
+        .collect()
+}
+
+/// Pick one match: 1 for the first, 2 for the second, -1 for the last, -2 for
+/// the one before it
+///
+/// # Errors
+/// Returns `InvalidArguments` for 0 or an occurrence beyond the matches found
+pub fn nth_occurrence(matches: &[usize], occurrence: i64) -> Result<usize, McpError> {
+    let index = match occurrence {
+        0 => None,
+        n if n > 0 => usize::try_from(n - 1).ok(),
+        n => usize::try_from(n.unsigned_abs())
+            .ok()
+            .and_then(|back| matches.len().checked_sub(back)),
+    };
+    index.and_then(|i| matches.get(i)).copied().ok_or_else(|| {
+        McpError::InvalidArguments(if occurrence == 0 {
+            "occurrence is 1-based; use -1 for the last match".to_string()
+        } else {
+            format!(
+                "occurrence {occurrence} requested, but old_string matches {} time(s)",
+                matches.len()
+            )
+        })
+    })
+}
+
+/// Replace the `needle_len`-byte matches starting at `positions` (ascending)
+#[must_use]
+pub fn replace_at(
+    content: &str,
+    positions: &[usize],
+    needle_len: usize,
+    replacement: &str,
+) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for &pos in positions {
+        out.push_str(&content[last..pos]);
+        out.push_str(replacement);
+        last = pos + needle_len;
+    }
+    out.push_str(&content[last..]);
+    out
+}
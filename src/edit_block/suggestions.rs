@@ -50,6 +50,8 @@ pub struct SuggestionContext {
     pub file_path: String,
     pub search_string: String,
     pub line_number: Option<usize>,
+    /// Similarity `accept_fuzzy` requires, when the edit set it
+    pub accept_fuzzy: Option<f64>,
 
     // Future hooks for EDIT_05 and EDIT_06
     pub log_path: Option<PathBuf>,
@@ -128,6 +130,25 @@ impl Suggestion {
                 "The difference is only whitespace - check for extra/missing spaces or tabs"
                     .to_string(),
             );
+            actions.push(
+                "Or set pattern_mode: \"normalize_whitespace\" to ignore indentation and trailing whitespace"
+                    .to_string(),
+            );
+        }
+
+        match context.accept_fuzzy {
+            None => actions.push(
+                "Or set accept_fuzzy: true to apply the edit to a match this close (see accept_fuzzy_threshold)"
+                    .to_string(),
+            ),
+            Some(threshold) if similarity < threshold => actions.push(format!(
+                "Or lower accept_fuzzy_threshold to {:.2} or less (it is {threshold:.2}) to apply the edit to this match",
+                (similarity * 100.0).floor() / 100.0
+            )),
+            Some(_) => actions.push(
+                "accept_fuzzy only applies single-match edits: set expected_replacements: 1 or occurrence"
+                    .to_string(),
+            ),
         }
 
         // Future hook for EDIT_05
@@ -213,7 +234,9 @@ impl Suggestion {
                     found, found
                 ),
                 format!("   Example: edit_block(..., expected_replacements: {})", found),
-                "To replace specific occurrences, make your search string more unique by including surrounding context".to_string(),
+                "To replace one specific occurrence, set occurrence (1 = first, -1 = last)".to_string(),
+                "   Example: edit_block(..., occurrence: 2)".to_string(),
+                "Or narrow the search with start_line/end_line, or make your search string more unique by including surrounding context".to_string(),
                 "   Example: Instead of 'foo', use 'function bar() {\\n  foo\\n}'".to_string(),
             ]
         } else {
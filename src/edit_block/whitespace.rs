@@ -0,0 +1,142 @@
+//! Whitespace-normalized matching for `pattern_mode: "normalize_whitespace"`
+//!
+//! `old_string` is compared with the file line by line, after removing the
+//! common leading indentation of each side and all trailing whitespace, so a
+//! block quoted at the wrong indentation or with stray trailing spaces still
+//! matches. Every other character, including indentation relative to the
+//! block's first level, must be equal. Each match is replaced by
+//! `new_string` moved from `old_string`'s indentation to the one found in
+//! the file.
+
+use super::pattern::RegexReplacement;
+use std::ops::Range;
+
+/// A line of text without its terminator
+struct Line<'a> {
+    text: &'a str,
+    /// Byte range of the line, with and without its terminator
+    start: usize,
+    end: usize,
+    end_with_terminator: usize,
+}
+
+/// Every whitespace-normalized match of `old` in `span` of `content`, with
+/// `new` re-indented for it
+///
+/// Matches cover whole lines and do not overlap. `line_ending` joins the
+/// lines of each replacement.
+#[must_use]
+pub fn normalized_replacements(
+    content: &str,
+    span: Range<usize>,
+    old: &str,
+    new: &str,
+    line_ending: &str,
+) -> Vec<RegexReplacement> {
+    let takes_terminator = old.ends_with('\n');
+    let old_lines: Vec<&str> = split_lines(old.strip_suffix('\n').unwrap_or(old));
+    let Some(old_indent) = common_indent(&old_lines) else {
+        return Vec::new();
+    };
+    let old_key = dedent(&old_lines, old_indent.len());
+
+    let mut lines = Vec::new();
+    let mut start = span.start;
+    for text in content[span.clone()].split_inclusive('\n') {
+        let end_with_terminator = start + text.len();
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        lines.push(Line {
+            text,
+            start,
+            end: start + text.len(),
+            end_with_terminator,
+        });
+        start = end_with_terminator;
+    }
+
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i + old_lines.len() <= lines.len() {
+        let window = &lines[i..i + old_lines.len()];
+        let texts: Vec<&str> = window.iter().map(|line| line.text).collect();
+        let Some(file_indent) = common_indent(&texts) else {
+            i += 1;
+            continue;
+        };
+        if dedent(&texts, file_indent.len()) != old_key {
+            i += 1;
+            continue;
+        }
+
+        let last = &window[window.len() - 1];
+        found.push(RegexReplacement {
+            span: window[0].start..if takes_terminator {
+                last.end_with_terminator
+            } else {
+                last.end
+            },
+            text: reindent(new, old_indent.len(), file_indent, line_ending),
+        });
+        i += old_lines.len();
+    }
+    found
+}
+
+/// Lines of `text`, without `\n` or `\r\n` terminators
+fn split_lines(text: &str) -> Vec<&str> {
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Leading spaces and tabs of `line`
+fn indent_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// The shortest indentation of the non-blank lines, or `None` if every line
+/// is blank
+fn common_indent<'a>(lines: &[&'a str]) -> Option<&'a str> {
+    lines
+        .iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| indent_of(line))
+        .min_by_key(|indent| indent.len())
+}
+
+/// Lines with `width` bytes of indentation and all trailing whitespace
+/// removed; blank lines become empty
+fn dedent(lines: &[&str], width: usize) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            if is_blank(line) {
+                String::new()
+            } else {
+                line[width..].trim_end().to_string()
+            }
+        })
+        .collect()
+}
+
+/// `text` with up to `old_width` bytes of each line's indentation replaced
+/// by `indent`; blank lines stay empty
+fn reindent(text: &str, old_width: usize, indent: &str, line_ending: &str) -> String {
+    split_lines(text)
+        .iter()
+        .map(|line| {
+            if is_blank(line) {
+                String::new()
+            } else {
+                let strip = indent_of(line).len().min(old_width);
+                format!("{indent}{}", &line[strip..])
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(line_ending)
+}
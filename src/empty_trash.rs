@@ -0,0 +1,96 @@
+use crate::journal::DEFAULT_CONNECTION;
+use crate::schema::{FS_EMPTY_TRASH, FsEmptyTrashArgs, FsEmptyTrashOutput, TrashEntry};
+use crate::trash::Trash;
+use kodegen_mcp_schema::filesystem::DeleteDirectoryPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use std::time::Duration;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct EmptyTrashTool;
+
+impl EmptyTrashTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for EmptyTrashTool {
+    type Args = FsEmptyTrashArgs;
+    type Prompts = DeleteDirectoryPrompts;
+
+    fn name() -> &'static str {
+        FS_EMPTY_TRASH
+    }
+
+    fn description() -> &'static str {
+        "Permanently remove files and directories that fs_delete_file and fs_delete_directory \
+         moved to the trash. Removes this connection's items unless all_connections is true; \
+         pass ids to remove specific items or older_than_hours to keep recent deletions. Items \
+         the user trashed outside kodegen are never touched. This cannot be undone."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true // Permanently deletes trashed data
+    }
+
+    fn idempotent() -> bool {
+        true // Emptying an empty trash is a no-op
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let trash = Trash::open_default().ok_or_else(|| {
+            McpError::InvalidArguments("The trash is disabled (KODEGEN_TRASH=0)".to_string())
+        })?;
+        let connection = ctx.connection_id().unwrap_or(DEFAULT_CONNECTION);
+        let older_than = args
+            .older_than_hours
+            .map(|hours| Duration::from_secs(hours * 60 * 60));
+
+        let removed: Vec<TrashEntry> = trash
+            .empty(
+                (!args.all_connections).then_some(connection),
+                &args.ids,
+                older_than,
+            )
+            .await?
+            .iter()
+            .map(TrashEntry::from)
+            .collect();
+
+        // Human summary
+        let mut summary = format!(
+            "\x1b[31mEmptied trash: {} item(s) removed permanently\x1b[0m",
+            removed.len()
+        );
+        for item in &removed {
+            summary.push_str(&format!("\n  {} · {}", item.id, item.original_path));
+        }
+        let message = format!("Removed {} item(s) from the trash", removed.len());
+
+        Ok(ToolResponse::new(
+            summary,
+            FsEmptyTrashOutput {
+                success: true,
+                removed,
+                message,
+            },
+        ))
+    }
+}
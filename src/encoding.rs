@@ -0,0 +1,227 @@
+//! Text encoding detection and round-tripping for the file tools
+//!
+//! `fs_search` reads legacy encodings through `grep::searcher::Encoding`.
+//! This module gives `fs_read_file`, `fs_write_file` and `fs_edit_block` the
+//! same coverage with the same rules:
+//!
+//! - Labels are WHATWG encoding labels as understood by encoding_rs
+//!   (`shift_jis`, `utf-16le`, `latin1`, `euc-kr`, ...), plus the unhyphenated
+//!   spellings `fs_search` documents (`shiftjis`, `utf16le`, `euckr`, ...)
+//! - A byte order mark wins over an explicit label, as with the searcher's
+//!   `EncodingMode::Some`
+//! - Without a BOM or a label, files are UTF-8
+//!
+//! Files are written back with the encoding and BOM they were read with, so
+//! an edit never silently transcodes a file.
+
+use encoding_rs::{EncoderResult, Encoding, UTF_8, UTF_16BE, UTF_16LE};
+use kodegen_mcp_schema::McpError;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// Longest byte order mark (UTF-8)
+const MAX_BOM_LEN: usize = 3;
+
+/// Common spellings that are not WHATWG labels, mapped to one that is
+const LABEL_ALIASES: &[(&str, &str)] = &[
+    ("utf16", "utf-16"),
+    ("utf16le", "utf-16le"),
+    ("utf16be", "utf-16be"),
+    ("shiftjis", "shift_jis"),
+    ("eucjp", "euc-jp"),
+    ("euckr", "euc-kr"),
+    ("iso2022jp", "iso-2022-jp"),
+    ("big5hkscs", "big5-hkscs"),
+];
+
+/// The encoding of a text file, including whether it starts with a BOM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEncoding {
+    /// Character encoding of the file body
+    pub encoding: &'static Encoding,
+
+    /// Whether the file starts with a byte order mark
+    pub bom: bool,
+}
+
+impl TextEncoding {
+    /// Plain UTF-8 without a BOM (the default for files without hints)
+    pub const UTF8: Self = Self {
+        encoding: UTF_8,
+        bom: false,
+    };
+
+    /// Encoding used for a file that does not exist yet
+    ///
+    /// UTF-16 files get a BOM since readers cannot otherwise tell their byte
+    /// order; every other encoding is written without one.
+    #[must_use]
+    pub fn for_new_file(encoding: &'static Encoding) -> Self {
+        Self {
+            encoding,
+            bom: encoding == UTF_16LE || encoding == UTF_16BE,
+        }
+    }
+
+    /// Encoding name for responses, or `None` for plain UTF-8
+    ///
+    /// Plain UTF-8 is left unreported so existing responses are unchanged.
+    #[must_use]
+    pub fn label(&self) -> Option<String> {
+        if *self == Self::UTF8 {
+            return None;
+        }
+        let bom = if self.bom { " (BOM)" } else { "" };
+        Some(format!("{}{bom}", self.encoding.name()))
+    }
+
+    /// Byte order mark written at the start of the file, if any
+    fn bom_bytes(&self) -> &'static [u8] {
+        if !self.bom {
+            return &[];
+        }
+        if self.encoding == UTF_16LE {
+            b"\xff\xfe"
+        } else if self.encoding == UTF_16BE {
+            b"\xfe\xff"
+        } else if self.encoding == UTF_8 {
+            b"\xef\xbb\xbf"
+        } else {
+            // Only Unicode encodings have a BOM
+            &[]
+        }
+    }
+}
+
+/// Resolve an encoding label (shared with `fs_search`)
+pub fn resolve_label(label: &str) -> Result<&'static Encoding, McpError> {
+    let label = label.trim();
+    let compact = label.replace(['-', '_'], "").to_ascii_lowercase();
+    let canonical = LABEL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == compact)
+        .map_or(label, |(_, canonical)| canonical);
+
+    Encoding::for_label_no_replacement(canonical.as_bytes()).ok_or_else(|| {
+        McpError::InvalidArguments(format!(
+            "Unknown encoding '{label}'. Use an encoding label such as \
+             \"utf-8\", \"utf-16le\", \"utf-16be\", \"shiftjis\", \"latin1\" or \"gbk\"."
+        ))
+    })
+}
+
+/// Detect the encoding of file contents from its BOM, falling back to `explicit` or UTF-8
+#[must_use]
+pub fn detect(bytes: &[u8], explicit: Option<&'static Encoding>) -> TextEncoding {
+    match Encoding::for_bom(bytes) {
+        Some((encoding, _)) => TextEncoding {
+            encoding,
+            bom: true,
+        },
+        None => TextEncoding {
+            encoding: explicit.unwrap_or(UTF_8),
+            bom: false,
+        },
+    }
+}
+
+/// Check whether the leading bytes of a file carry a byte order mark
+#[must_use]
+pub fn has_bom(header: &[u8]) -> bool {
+    Encoding::for_bom(header).is_some()
+}
+
+/// Decode file contents, failing on malformed input rather than guessing
+pub fn decode(bytes: &[u8], text_encoding: TextEncoding) -> Result<String, McpError> {
+    let body = &bytes[text_encoding.bom_bytes().len()..];
+
+    text_encoding
+        .encoding
+        .decode_without_bom_handling_and_without_replacement(body)
+        .map(std::borrow::Cow::into_owned)
+        .ok_or_else(|| {
+            McpError::InvalidArguments(format!(
+                "File is not valid {}. Pass `encoding` with the file's encoding \
+                 (e.g. \"shiftjis\", \"utf-16le\", \"latin1\").",
+                text_encoding.encoding.name()
+            ))
+        })
+}
+
+/// Encode text for writing, including the BOM if the encoding has one
+///
+/// Fails if the text contains a character the encoding cannot represent,
+/// instead of substituting it.
+pub fn encode(text: &str, text_encoding: TextEncoding) -> Result<Vec<u8>, McpError> {
+    let mut out = text_encoding.bom_bytes().to_vec();
+    let encoding = text_encoding.encoding;
+
+    // encoding_rs only decodes UTF-16; its encoders emit UTF-8 for it
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        out.reserve(text.len() * 2);
+        for unit in text.encode_utf16() {
+            if encoding == UTF_16LE {
+                out.extend_from_slice(&unit.to_le_bytes());
+            } else {
+                out.extend_from_slice(&unit.to_be_bytes());
+            }
+        }
+        return Ok(out);
+    }
+
+    let mut encoder = encoding.new_encoder();
+    let capacity = encoder
+        .max_buffer_length_from_utf8_without_replacement(text.len())
+        .ok_or_else(|| McpError::InvalidArguments("Content is too large to encode".to_string()))?;
+    out.reserve(capacity);
+
+    let (result, _) = encoder.encode_from_utf8_to_vec_without_replacement(text, &mut out, true);
+    match result {
+        EncoderResult::InputEmpty => Ok(out),
+        EncoderResult::Unmappable(c) => Err(McpError::InvalidArguments(format!(
+            "Character '{c}' (U+{:04X}) cannot be represented in {}",
+            u32::from(c),
+            encoding.name()
+        ))),
+        EncoderResult::OutputFull => Err(McpError::InvalidArguments(format!(
+            "Failed to encode content as {}",
+            encoding.name()
+        ))),
+    }
+}
+
+/// Read a whole text file, detecting its encoding
+pub async fn read_text(
+    path: &Path,
+    explicit: Option<&'static Encoding>,
+) -> Result<(String, TextEncoding), McpError> {
+    let bytes = tokio::fs::read(path).await?;
+    let text_encoding = detect(&bytes, explicit);
+    let text = decode(&bytes, text_encoding)?;
+    Ok((text, text_encoding))
+}
+
+/// Detect the encoding of an existing file from its BOM without reading it whole
+///
+/// Returns `None` if the file does not exist or is empty, since there is no
+/// encoding to preserve.
+pub async fn detect_file(
+    path: &Path,
+    explicit: Option<&'static Encoding>,
+) -> Result<Option<TextEncoding>, McpError> {
+    let file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut header = Vec::with_capacity(MAX_BOM_LEN);
+    file.take(MAX_BOM_LEN as u64)
+        .read_to_end(&mut header)
+        .await?;
+
+    if header.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(detect(&header, explicit)))
+}
@@ -0,0 +1,98 @@
+use crate::journal::{DEFAULT_CONNECTION, Journal};
+use crate::schema::{FS_HISTORY, FsHistoryArgs, FsHistoryOutput, JournalEntry};
+use kodegen_mcp_schema::filesystem::GetFileInfoPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+
+/// Operations listed when no limit is given
+const DEFAULT_LIMIT: usize = 20;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct HistoryTool;
+
+impl HistoryTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for HistoryTool {
+    type Args = FsHistoryArgs;
+    type Prompts = GetFileInfoPrompts;
+
+    fn name() -> &'static str {
+        FS_HISTORY
+    }
+
+    fn description() -> &'static str {
+        "List the file operations recorded in the undo journal, newest first: their IDs, the \
+         tool that ran, the paths changed and whether they were already undone. Pass an ID to \
+         fs_undo as operation_id to revert that operation. Shows this connection's operations \
+         unless all_connections is true."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let operations = match Journal::open_default() {
+            Some(journal) => {
+                let connection = ctx.connection_id().unwrap_or(DEFAULT_CONNECTION);
+                journal
+                    .history((!args.all_connections).then_some(connection))
+                    .await?
+            }
+            None => Vec::new(),
+        };
+        let total = operations.len();
+        let operations: Vec<JournalEntry> = operations
+            .iter()
+            .take(args.limit.unwrap_or(DEFAULT_LIMIT))
+            .map(JournalEntry::from)
+            .collect();
+
+        // Human summary
+        let mut summary = format!(
+            "\x1b[36mUndo journal: {} of {} operation(s)\x1b[0m",
+            operations.len(),
+            total
+        );
+        for entry in &operations {
+            let undone = if entry.undone { " (undone)" } else { "" };
+            summary.push_str(&format!(
+                "\n  {} · {} · {}{undone}",
+                entry.id, entry.tool, entry.summary
+            ));
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            FsHistoryOutput {
+                success: true,
+                operations,
+                total,
+            },
+        ))
+    }
+}
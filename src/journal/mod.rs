@@ -0,0 +1,504 @@
+//! Undo journal for the mutating file tools
+//!
+//! Before `fs_write_file`, `fs_edit_block`, `fs_apply_patch`, `fs_replace`,
+//! `fs_move_file`, `fs_delete_file` or `fs_delete_directory` changes
+//! anything, the prior state of every path it touches is copied under the
+//! kodegen state directory, one directory per operation:
+//! `state/fs-journal/<connection>/<operation>/`. `fs_history` lists
+//! the recorded operations and `fs_undo` puts the snapshots back, newest
+//! first.
+//!
+//! Undo refuses to overwrite a path that changed after the operation (its
+//! hash no longer matches what the operation left behind) unless forced.
+//! The journal is pruned after every operation: entries older than the
+//! maximum age go first, then the oldest until the journal fits its size
+//! budget. Journaling is best effort: if a snapshot cannot be taken the
+//! operation still runs, it just cannot be undone.
+
+mod restore;
+mod snapshot;
+
+use crate::precondition::fingerprint;
+use crate::schema::JournalEntry;
+use chrono::{DateTime, Utc};
+use kodegen_config::KodegenConfig;
+use kodegen_mcp_schema::{McpError, ToolExecutionContext};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default size budget for the whole journal (512 MiB)
+pub const DEFAULT_MAX_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Default age after which operations are pruned (7 days)
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Connection name used when the transport does not provide one
+pub const DEFAULT_CONNECTION: &str = "default";
+
+/// Name of the metadata file in each operation directory
+const RECORD_FILE: &str = "record.json";
+
+/// Staging directories without a record are abandoned after this long
+const STALE_STAGING: Duration = Duration::from_secs(60 * 60);
+
+// ============================================================================
+// RECORDS
+// ============================================================================
+
+/// Prior state of one path touched by an operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Snapshot {
+    /// A file that existed before the operation; its bytes are in `blob`
+    File {
+        path: PathBuf,
+        blob: String,
+        /// Unix permission bits
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+        /// Content hash the operation left behind (`None`: path removed)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<String>,
+    },
+    /// A path that did not exist before the operation
+    Absent {
+        path: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<String>,
+    },
+    /// A directory tree that existed before the operation; copied to `blob`
+    Tree { path: PathBuf, blob: String },
+    /// A rename, undone by renaming back
+    Moved { from: PathBuf, to: PathBuf },
+}
+
+impl Snapshot {
+    /// Paths this snapshot covers, for display
+    #[must_use]
+    pub fn paths(&self) -> Vec<&Path> {
+        match self {
+            Self::File { path, .. } | Self::Absent { path, .. } | Self::Tree { path, .. } => {
+                vec![path]
+            }
+            Self::Moved { from, to } => vec![from, to],
+        }
+    }
+}
+
+/// One recorded operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    /// Sortable ID: UTC timestamp plus a random suffix
+    pub id: String,
+    pub connection: String,
+    /// Tool that made the change, e.g. "fs_write_file"
+    pub tool: String,
+    /// What the tool did, e.g. "rewrite src/lib.rs"
+    pub summary: String,
+    pub timestamp: DateTime<Utc>,
+    /// Bytes stored for this operation's snapshots
+    pub size_bytes: u64,
+    pub snapshots: Vec<Snapshot>,
+    #[serde(default)]
+    pub undone: bool,
+}
+
+impl From<&Operation> for JournalEntry {
+    fn from(operation: &Operation) -> Self {
+        Self {
+            id: operation.id.clone(),
+            connection: operation.connection.clone(),
+            tool: operation.tool.clone(),
+            summary: operation.summary.clone(),
+            timestamp: operation.timestamp.to_rfc3339(),
+            paths: operation
+                .snapshots
+                .iter()
+                .flat_map(Snapshot::paths)
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            size_bytes: operation.size_bytes,
+            undone: operation.undone,
+        }
+    }
+}
+
+// ============================================================================
+// JOURNAL
+// ============================================================================
+
+/// The on-disk journal
+#[derive(Debug, Clone)]
+pub struct Journal {
+    root: PathBuf,
+    max_bytes: u64,
+    max_age: Duration,
+}
+
+impl Journal {
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>, max_bytes: u64, max_age: Duration) -> Self {
+        Self {
+            root: root.into(),
+            max_bytes,
+            max_age,
+        }
+    }
+
+    /// The journal under the kodegen state directory, sized by
+    /// `KODEGEN_UNDO_MAX_BYTES` and `KODEGEN_UNDO_MAX_AGE_HOURS`
+    ///
+    /// Returns `None` when `KODEGEN_UNDO_JOURNAL=0` or the state directory
+    /// cannot be determined.
+    #[must_use]
+    pub fn open_default() -> Option<Self> {
+        if std::env::var("KODEGEN_UNDO_JOURNAL")
+            .is_ok_and(|v| matches!(v.trim(), "0" | "false" | "no"))
+        {
+            return None;
+        }
+        let root = KodegenConfig::state_dir().ok()?.join("fs-journal");
+        let number = |var: &str| -> Option<u64> { std::env::var(var).ok()?.trim().parse().ok() };
+        Some(Self::new(
+            root,
+            number("KODEGEN_UNDO_MAX_BYTES").unwrap_or(DEFAULT_MAX_BYTES),
+            number("KODEGEN_UNDO_MAX_AGE_HOURS").map_or(DEFAULT_MAX_AGE, |hours| {
+                Duration::from_secs(hours * 60 * 60)
+            }),
+        ))
+    }
+
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Start recording an operation
+    #[must_use]
+    pub fn begin(&self, connection: &str, tool: &str, summary: impl Into<String>) -> Recorder {
+        let connection = sanitize(connection);
+        let timestamp = Utc::now();
+        let id = format!(
+            "{}-{}",
+            timestamp.format("%Y%m%dT%H%M%S%.6fZ"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        Recorder {
+            dir: self.root.join(&connection).join(&id),
+            operation: Operation {
+                id,
+                connection,
+                tool: tool.to_string(),
+                summary: summary.into(),
+                timestamp,
+                size_bytes: 0,
+                snapshots: Vec::new(),
+                undone: false,
+            },
+            journal: Some(self.clone()),
+        }
+    }
+
+    /// Recorded operations, newest first; all connections when `connection`
+    /// is `None`
+    ///
+    /// # Errors
+    /// Returns `McpError::Io` if the journal directory cannot be listed.
+    pub async fn history(&self, connection: Option<&str>) -> Result<Vec<Operation>, McpError> {
+        let root = self.root.clone();
+        let connection = connection.map(sanitize);
+        tokio::task::spawn_blocking(move || {
+            let mut operations: Vec<Operation> = list_records(&root, connection.as_deref())?
+                .into_iter()
+                .map(|(_, operation)| operation)
+                .collect();
+            operations.sort_by(|a, b| b.id.cmp(&a.id));
+            Ok(operations)
+        })
+        .await
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Journal task failed: {e}")))?
+    }
+
+    /// Undo the newest `count` operations of `connection` that are not undone
+    /// yet, or just `operation_id`
+    ///
+    /// Operations are undone newest first and stop at the first failure.
+    /// Returns the operations that were undone.
+    ///
+    /// # Errors
+    /// Returns `McpError::ResourceNotFound` when there is nothing to undo and
+    /// `McpError::InvalidArguments` when a path changed after the operation
+    /// (without `force`) or cannot be restored.
+    pub async fn undo(
+        &self,
+        connection: &str,
+        count: usize,
+        operation_id: Option<&str>,
+        force: bool,
+    ) -> Result<Vec<Operation>, McpError> {
+        let root = self.root.clone();
+        let connection = sanitize(connection);
+        let operation_id = operation_id.map(str::to_string);
+        tokio::task::spawn_blocking(move || {
+            restore::undo(&root, &connection, count, operation_id.as_deref(), force)
+        })
+        .await
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Journal task failed: {e}")))?
+    }
+
+    /// Drop operations past the age limit, then the oldest ones until the
+    /// journal fits in its size budget
+    pub async fn prune(&self) {
+        let journal = self.clone();
+        let result = tokio::task::spawn_blocking(move || journal.prune_blocking()).await;
+        if let Ok(Err(e)) = result {
+            log::warn!("Failed to prune undo journal: {e}");
+        }
+    }
+
+    fn prune_blocking(&self) -> std::io::Result<()> {
+        remove_stale_staging(&self.root);
+
+        let mut records = list_records(&self.root, None)?;
+        records.sort_by(|a, b| a.1.id.cmp(&b.1.id));
+
+        let now = Utc::now();
+        let mut total: u64 = records.iter().map(|(_, op)| op.size_bytes).sum();
+        for (dir, operation) in records {
+            let age = now
+                .signed_duration_since(operation.timestamp)
+                .to_std()
+                .unwrap_or_default();
+            if age <= self.max_age && total <= self.max_bytes {
+                continue;
+            }
+            total = total.saturating_sub(operation.size_bytes);
+            std::fs::remove_dir_all(&dir)?;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// RECORDER
+// ============================================================================
+
+/// Collects snapshots for one operation; the operation is journaled on
+/// [`Recorder::commit`] and discarded if the recorder is dropped first
+#[derive(Debug)]
+pub struct Recorder {
+    /// `None` when journaling is disabled or a snapshot failed
+    journal: Option<Journal>,
+    dir: PathBuf,
+    operation: Operation,
+}
+
+impl Recorder {
+    /// A recorder that records nothing
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            journal: None,
+            dir: PathBuf::new(),
+            operation: Operation {
+                id: String::new(),
+                connection: String::new(),
+                tool: String::new(),
+                summary: String::new(),
+                timestamp: Utc::now(),
+                size_bytes: 0,
+                snapshots: Vec::new(),
+                undone: false,
+            },
+        }
+    }
+
+    /// Start recording a tool call in the default journal
+    #[must_use]
+    pub fn for_call(ctx: &ToolExecutionContext, tool: &str, summary: impl Into<String>) -> Self {
+        Journal::open_default().map_or_else(Self::disabled, |journal| {
+            journal.begin(
+                ctx.connection_id().unwrap_or(DEFAULT_CONNECTION),
+                tool,
+                summary,
+            )
+        })
+    }
+
+    /// Save the current state of `path` (a file, a directory tree, or the
+    /// fact that nothing is there) before it is changed
+    pub async fn snapshot(&mut self, path: &Path) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        let dir = self.dir.clone();
+        let path = path.to_path_buf();
+        let index = self.operation.snapshots.len();
+        let budget = journal.max_bytes.saturating_sub(self.operation.size_bytes);
+
+        let result =
+            tokio::task::spawn_blocking(move || snapshot::take(&dir, index, &path, budget)).await;
+        match result {
+            Ok(Ok((snapshot, size))) => {
+                self.operation.snapshots.push(snapshot);
+                self.operation.size_bytes += size;
+            }
+            Ok(Err(e)) => self.give_up(&e.to_string()),
+            Err(e) => self.give_up(&e.to_string()),
+        }
+    }
+
+    /// Note that `from` is about to be renamed to `to`
+    pub fn moved(&mut self, from: &Path, to: &Path) {
+        if self.journal.is_some() {
+            self.operation.snapshots.push(Snapshot::Moved {
+                from: from.to_path_buf(),
+                to: to.to_path_buf(),
+            });
+        }
+    }
+
+    /// Journal the operation once it has succeeded; returns its ID
+    pub async fn commit(mut self) -> Option<String> {
+        let journal = self.journal.take()?;
+
+        for snapshot in &mut self.operation.snapshots {
+            if let Snapshot::File { path, after, .. } | Snapshot::Absent { path, after } = snapshot
+            {
+                *after = fingerprint(path).await.ok().and_then(|f| f.content_hash);
+            }
+        }
+
+        let record = match serde_json::to_vec_pretty(&self.operation) {
+            Ok(record) => record,
+            Err(e) => {
+                self.journal = Some(journal);
+                self.give_up(&e.to_string());
+                return None;
+            }
+        };
+        if let Err(e) = tokio::fs::create_dir_all(&self.dir).await
+            && e.kind() != std::io::ErrorKind::AlreadyExists
+        {
+            log::warn!("Failed to journal {}: {e}", self.operation.summary);
+            return None;
+        }
+        if let Err(e) = tokio::fs::write(self.dir.join(RECORD_FILE), record).await {
+            log::warn!("Failed to journal {}: {e}", self.operation.summary);
+            let _ = std::fs::remove_dir_all(&self.dir);
+            return None;
+        }
+
+        journal.prune().await;
+        Some(std::mem::take(&mut self.operation.id))
+    }
+
+    /// Stop journaling this operation and discard what was staged
+    fn give_up(&mut self, reason: &str) {
+        log::warn!(
+            "Not journaling {} ({reason}); it cannot be undone",
+            self.operation.summary
+        );
+        self.journal = None;
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        // Dropped without commit: the operation failed, nothing to undo
+        if self.journal.is_some() {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+/// Connection IDs become directory names
+fn sanitize(connection: &str) -> String {
+    let name: String = connection
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        DEFAULT_CONNECTION.to_string()
+    } else {
+        name
+    }
+}
+
+/// Every committed operation as (directory, record), for one connection or all
+fn list_records(
+    root: &Path,
+    connection: Option<&str>,
+) -> std::io::Result<Vec<(PathBuf, Operation)>> {
+    let connections: Vec<PathBuf> = match connection {
+        Some(connection) => vec![root.join(connection)],
+        None => match std::fs::read_dir(root) {
+            Ok(entries) => entries.flatten().map(|e| e.path()).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        },
+    };
+
+    let mut records = Vec::new();
+    for connection_dir in connections {
+        let entries = match std::fs::read_dir(&connection_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) if e.kind() == std::io::ErrorKind::NotADirectory => continue,
+            Err(e) => return Err(e),
+        };
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            let Ok(bytes) = std::fs::read(dir.join(RECORD_FILE)) else {
+                continue;
+            };
+            match serde_json::from_slice::<Operation>(&bytes) {
+                Ok(operation) => records.push((dir, operation)),
+                Err(e) => log::warn!("Ignoring unreadable journal record {}: {e}", dir.display()),
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Remove staging directories left behind by a crash mid-operation
+fn remove_stale_staging(root: &Path) {
+    let Ok(connections) = std::fs::read_dir(root) else {
+        return;
+    };
+    for connection in connections.flatten() {
+        let Ok(operations) = std::fs::read_dir(connection.path()) else {
+            continue;
+        };
+        for operation in operations.flatten() {
+            let dir = operation.path();
+            let stale = operation
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > STALE_STAGING);
+            if stale && !dir.join(RECORD_FILE).exists() {
+                let _ = std::fs::remove_dir_all(&dir);
+            }
+        }
+    }
+}
+
+/// Write `operation`'s record back after it changed (e.g. marked undone)
+fn rewrite_record(dir: &Path, operation: &Operation) -> std::io::Result<()> {
+    let record = serde_json::to_vec_pretty(operation).map_err(std::io::Error::other)?;
+    crate::atomic_write::replace(&dir.join(RECORD_FILE), &record)
+}
@@ -0,0 +1,177 @@
+//! Putting snapshots back
+
+use super::snapshot::copy_tree;
+use super::{Operation, Snapshot, list_records, rewrite_record};
+use crate::atomic_write::replace;
+use crate::precondition::{MAX_HASH_BYTES, content_hash};
+use kodegen_mcp_schema::McpError;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Blocking implementation of [`super::Journal::undo`]
+pub(super) fn undo(
+    root: &Path,
+    connection: &str,
+    count: usize,
+    operation_id: Option<&str>,
+    force: bool,
+) -> Result<Vec<Operation>, McpError> {
+    let mut records = list_records(root, Some(connection))?;
+    records.retain(|(_, operation)| !operation.undone);
+    records.sort_by(|a, b| b.1.id.cmp(&a.1.id));
+
+    let targets = match operation_id {
+        Some(id) => {
+            let found = records
+                .into_iter()
+                .find(|(_, operation)| operation.id == id)
+                .ok_or_else(|| {
+                    McpError::ResourceNotFound(format!(
+                        "No operation {id} left to undo in this connection's journal \
+                         (see fs_history)"
+                    ))
+                })?;
+            vec![found]
+        }
+        None => records.into_iter().take(count).collect(),
+    };
+    if targets.is_empty() {
+        return Err(McpError::ResourceNotFound(
+            "Nothing to undo: this connection has no journaled operations left".to_string(),
+        ));
+    }
+
+    let mut undone = Vec::with_capacity(targets.len());
+    for (dir, mut operation) in targets {
+        if let Err(e) = restore(&dir, &operation, force) {
+            if undone.is_empty() {
+                return Err(e);
+            }
+            return Err(McpError::InvalidArguments(format!(
+                "Undid {} operation(s), then stopped at {} ({}): {e}",
+                undone.len(),
+                operation.id,
+                operation.summary
+            )));
+        }
+        operation.undone = true;
+        rewrite_record(&dir, &operation)?;
+        undone.push(operation);
+    }
+    Ok(undone)
+}
+
+/// Restore one operation: check every snapshot first, then put them back in
+/// reverse order
+fn restore(dir: &Path, operation: &Operation, force: bool) -> Result<(), McpError> {
+    if !force {
+        for snapshot in &operation.snapshots {
+            check(snapshot).map_err(|why| {
+                McpError::InvalidArguments(format!(
+                    "Conflict: cannot undo {} ({}): {why}. Nothing was restored; pass \
+                     force: true to overwrite the current state.",
+                    operation.id, operation.summary
+                ))
+            })?;
+        }
+    }
+    for snapshot in operation.snapshots.iter().rev() {
+        apply(dir, snapshot)?;
+    }
+    Ok(())
+}
+
+/// Whether the path still looks the way the operation left it
+fn check(snapshot: &Snapshot) -> Result<(), String> {
+    match snapshot {
+        Snapshot::File { path, after, .. } | Snapshot::Absent { path, after } => {
+            let current = current_hash(path).map_err(|e| format!("{}: {e}", path.display()))?;
+            if current != *after {
+                return Err(format!(
+                    "{} was changed after this operation",
+                    path.display()
+                ));
+            }
+        }
+        Snapshot::Tree { path, .. } => {
+            if fs::symlink_metadata(path).is_ok() {
+                return Err(format!("{} exists again", path.display()));
+            }
+        }
+        Snapshot::Moved { from, to } => {
+            if fs::symlink_metadata(to).is_err() {
+                return Err(format!("{} no longer exists", to.display()));
+            }
+            if fs::symlink_metadata(from).is_ok() {
+                return Err(format!("{} exists again", from.display()));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply(dir: &Path, snapshot: &Snapshot) -> io::Result<()> {
+    match snapshot {
+        Snapshot::File {
+            path, blob, mode, ..
+        } => {
+            let bytes = fs::read(dir.join("blobs").join(blob))?;
+            create_parent(path)?;
+            replace(path, &bytes)?;
+            set_mode(path, *mode)
+        }
+        Snapshot::Absent { path, .. } => match fs::symlink_metadata(path) {
+            // Directories are never removed on the strength of a file hash
+            Ok(metadata) if !metadata.is_dir() => fs::remove_file(path),
+            _ => Ok(()),
+        },
+        Snapshot::Tree { path, blob } => {
+            match fs::symlink_metadata(path) {
+                Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path)?,
+                Ok(_) => fs::remove_file(path)?,
+                Err(_) => {}
+            }
+            create_parent(path)?;
+            copy_tree(&dir.join("blobs").join(blob), path, u64::MAX).map(|_| ())
+        }
+        Snapshot::Moved { from, to } => {
+            create_parent(from)?;
+            fs::rename(to, from)
+        }
+    }
+}
+
+/// Hash of the file at `path` as [`crate::precondition::fingerprint`]
+/// reports it: `None` when missing, a directory, or too large to hash
+fn current_hash(path: &Path) -> io::Result<Option<String>> {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() && metadata.len() <= MAX_HASH_BYTES => {
+            Ok(Some(content_hash(&fs::read(path)?)))
+        }
+        Ok(_) => Ok(None),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn create_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: Option<u32>) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    match mode {
+        Some(mode) => fs::set_permissions(path, fs::Permissions::from_mode(mode)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: Option<u32>) -> io::Result<()> {
+    Ok(())
+}
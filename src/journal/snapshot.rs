@@ -0,0 +1,130 @@
+//! Copying prior state into an operation's staging directory
+
+use super::Snapshot;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Snapshot `path` as entry `index` of the operation staged in `dir`
+///
+/// Returns the snapshot and the number of bytes it stored.
+///
+/// # Errors
+/// Fails when the path cannot be copied or its contents exceed `budget`.
+pub(super) fn take(
+    dir: &Path,
+    index: usize,
+    path: &Path,
+    budget: u64,
+) -> io::Result<(Snapshot, u64)> {
+    // A dangling symlink counts as absent: writing through it creates the
+    // file it names, which undo then removes
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Ok((
+                Snapshot::Absent {
+                    path: path.to_path_buf(),
+                    after: None,
+                },
+                0,
+            ));
+        }
+        Err(e) => return Err(e),
+    };
+
+    let blobs = dir.join("blobs");
+    fs::create_dir_all(&blobs)?;
+    let blob = index.to_string();
+
+    if metadata.is_dir() {
+        let size = copy_tree(path, &blobs.join(&blob), budget)?;
+        return Ok((
+            Snapshot::Tree {
+                path: path.to_path_buf(),
+                blob,
+            },
+            size,
+        ));
+    }
+
+    if metadata.len() > budget {
+        return Err(too_large(path));
+    }
+    let size = fs::copy(path, blobs.join(&blob))?;
+    Ok((
+        Snapshot::File {
+            path: path.to_path_buf(),
+            blob,
+            mode: mode(&metadata),
+            after: None,
+        },
+        size,
+    ))
+}
+
+/// Recursively copy the directory `from` to `to` (which must not exist),
+/// failing once more than `budget` bytes of file contents were copied
+///
+/// Symlinks are recreated, not followed.
+///
+/// # Errors
+/// Returns the first I/O error, or an error when the budget is exceeded.
+pub(super) fn copy_tree(from: &Path, to: &Path, budget: u64) -> io::Result<u64> {
+    let mut copied = 0;
+    copy_dir(from, to, budget, &mut copied)?;
+    Ok(copied)
+}
+
+fn copy_dir(from: &Path, to: &Path, budget: u64, copied: &mut u64) -> io::Result<()> {
+    fs::create_dir(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            copy_dir(&source, &target, budget, copied)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        } else {
+            *copied += entry.metadata()?.len();
+            if *copied > budget {
+                return Err(too_large(from));
+            }
+            fs::copy(&source, &target)?;
+        }
+    }
+    // Permissions last, so a read-only directory can still be filled
+    fs::set_permissions(to, fs::metadata(from)?.permissions())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, _target: &Path) -> io::Result<()> {
+    log::debug!("Not journaling symlink {}", source.display());
+    Ok(())
+}
+
+#[cfg(unix)]
+fn mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+fn too_large(path: &Path) -> io::Error {
+    io::Error::other(format!(
+        "{} is larger than the undo journal's size limit",
+        path.display()
+    ))
+}
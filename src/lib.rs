@@ -1,6 +1,15 @@
 mod validation;
 pub use validation::*;
 
+pub mod atomic_write;
+pub mod diff;
+pub mod dry_run;
+pub mod encoding;
+pub mod journal;
+pub mod precondition;
+pub mod schema;
+pub mod trash;
+
 pub mod read_file;
 pub use read_file::*;
 
@@ -13,6 +22,9 @@ pub use write_file::*;
 pub mod edit_block;
 pub use edit_block::*;
 
+pub mod apply_patch;
+pub use apply_patch::*;
+
 pub mod create_directory;
 pub use create_directory::*;
 
@@ -31,6 +43,18 @@ pub use delete_directory::*;
 pub mod get_file_info;
 pub use get_file_info::*;
 
+pub mod undo;
+pub use undo::*;
+
+pub mod history;
+pub use history::*;
+
+pub mod restore;
+pub use restore::*;
+
+pub mod empty_trash;
+pub use empty_trash::*;
+
 pub mod search;
 
 /// Start the filesystem HTTP server programmatically
@@ -96,7 +120,7 @@ pub async fn start_server_with_listener(
 
             let file_read_line_limit = config.get_file_read_line_limit();
 
-            // Register all 11 filesystem tools
+            // Register all 17 filesystem tools
             let (tool_router, prompt_router) = register_tool(
                 tool_router,
                 prompt_router,
@@ -157,6 +181,36 @@ pub async fn start_server_with_listener(
                 crate::EditBlockTool::new(config.clone()),
             );
 
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::ApplyPatchTool::new(config.clone()),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::UndoTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::HistoryTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::RestoreTool::new(config.clone()),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::EmptyTrashTool::new(),
+            );
+
             // Search tools - create registry for connection isolation
             let search_registry = std::sync::Arc::new(crate::search::SearchRegistry::new());
 
@@ -166,6 +220,12 @@ pub async fn start_server_with_listener(
                 crate::search::FsSearchTool::new(search_registry),
             );
 
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::search::FsReplaceTool::new(config.clone()),
+            );
+
             Ok(RouterSet::new(tool_router, prompt_router, managers))
         })
         .with_listener(listener);
@@ -26,7 +26,7 @@ async fn main() -> Result<()> {
             // Get configuration values
             let file_read_line_limit = config.get_file_read_line_limit();
 
-        // Register all 11 filesystem tools
+        // Register all 17 filesystem tools
         let (tool_router, prompt_router) = register_tool(
             tool_router,
             prompt_router,
@@ -93,6 +93,36 @@ async fn main() -> Result<()> {
             kodegen_tools_filesystem::EditBlockTool::new(config.clone()),
         );
 
+        let (tool_router, prompt_router) = register_tool(
+            tool_router,
+            prompt_router,
+            kodegen_tools_filesystem::ApplyPatchTool::new(config.clone()),
+        );
+
+        let (tool_router, prompt_router) = register_tool(
+            tool_router,
+            prompt_router,
+            kodegen_tools_filesystem::UndoTool::new(),
+        );
+
+        let (tool_router, prompt_router) = register_tool(
+            tool_router,
+            prompt_router,
+            kodegen_tools_filesystem::HistoryTool::new(),
+        );
+
+        let (tool_router, prompt_router) = register_tool(
+            tool_router,
+            prompt_router,
+            kodegen_tools_filesystem::RestoreTool::new(config.clone()),
+        );
+
+        let (tool_router, prompt_router) = register_tool(
+            tool_router,
+            prompt_router,
+            kodegen_tools_filesystem::EmptyTrashTool::new(),
+        );
+
         // Search tools - create registry for connection isolation
         let search_registry = std::sync::Arc::new(kodegen_tools_filesystem::search::SearchRegistry::new());
         
@@ -102,6 +132,12 @@ async fn main() -> Result<()> {
             kodegen_tools_filesystem::search::FsSearchTool::new(search_registry.clone()),
         );
 
+        let (tool_router, prompt_router) = register_tool(
+            tool_router,
+            prompt_router,
+            kodegen_tools_filesystem::search::FsReplaceTool::new(config.clone()),
+        );
+
         // Create cleanup callback for connection dropped notification
         let cleanup: ConnectionCleanupFn = std::sync::Arc::new(move |connection_id: String| {
             let registry = search_registry.clone();
@@ -1,6 +1,10 @@
+use crate::atomic_write::rename_noreplace;
+use crate::journal::Recorder;
+use crate::precondition::check_if_match;
+use crate::schema::{FsMoveFileArgs, FsMoveFileOutput};
 use crate::validate_path;
 use kodegen_config::shorten_path_for_display;
-use kodegen_mcp_schema::filesystem::{FsMoveFileArgs, FsMoveFileOutput, MoveFilePrompts};
+use kodegen_mcp_schema::filesystem::MoveFilePrompts;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use tokio::fs;
 
@@ -26,7 +30,12 @@ impl Tool for MoveFileTool {
 
     fn description() -> &'static str {
         "Move or rename files and directories. Can move files between directories and rename \
-         them in a single operation. Both source and destination must be within allowed directories."
+         them in a single operation. Both source and destination must be within allowed directories. \
+         An existing destination is never replaced unless overwrite is true. \
+         Pass if_match (content_hash or modified from fs_read_file) to refuse the move if the source \
+         changed since it was read. Pass dry_run: true to validate and report what would happen \
+         without moving anything. The move is journaled: the response carries an undo_id \
+         for fs_undo."
     }
 
     fn read_only() -> bool {
@@ -34,7 +43,7 @@ impl Tool for MoveFileTool {
     }
 
     fn destructive() -> bool {
-        true // Can overwrite destination
+        true // Can overwrite destination (with overwrite: true)
     }
 
     fn idempotent() -> bool {
@@ -46,7 +55,75 @@ impl Tool for MoveFileTool {
         let source_path = validate_path(&args.source, &self.config_manager, client_pwd).await?;
         let dest_path = validate_path(&args.destination, &self.config_manager, client_pwd).await?;
 
-        fs::rename(&source_path, &dest_path).await?;
+        if args.dry_run {
+            check_if_match(&source_path, args.if_match.as_deref()).await?;
+            let source_meta = fs::symlink_metadata(&source_path).await?;
+            let replaces = fs::symlink_metadata(&dest_path).await.is_ok();
+            if replaces && !args.overwrite {
+                return Err(McpError::InvalidArguments(format!(
+                    "Destination {} already exists. Nothing would be moved; pass overwrite: true \
+                     to replace it.",
+                    args.destination
+                )));
+            }
+            let kind = if source_meta.is_dir() { "directory" } else { "file" };
+            let display_source = shorten_path_for_display(&source_path, ctx.git_root());
+            let display_dest = shorten_path_for_display(&dest_path, ctx.git_root());
+            let summary = format!(
+                "\x1b[36mDry run: would move {kind}\x1b[0m\n\
+                 From: {}\n\
+                 To:   {}{}",
+                display_source,
+                display_dest,
+                if replaces { " (replacing it)" } else { "" }
+            );
+            return Ok(ToolResponse::new(summary, FsMoveFileOutput {
+                success: true,
+                source: source_path.to_string_lossy().to_string(),
+                destination: dest_path.to_string_lossy().to_string(),
+                message: if replaces {
+                    format!("Dry run: would move the {kind}, replacing the destination")
+                } else {
+                    format!("Dry run: would move the {kind}")
+                },
+                undo_id: None,
+                dry_run: true,
+            }));
+        }
+
+        // Journal a replaced destination before the rename, so undo can
+        // move the source back and then restore what it overwrote
+        let mut undo = Recorder::for_call(
+            &ctx,
+            Self::name(),
+            format!("move {} -> {}", args.source, args.destination),
+        );
+        if args.overwrite && fs::symlink_metadata(&dest_path).await.is_ok() {
+            undo.snapshot(&dest_path).await;
+        }
+        undo.moved(&source_path, &dest_path);
+
+        // Checked after the journaling, right before the rename, so a change
+        // to the source has as little time as possible to slip in between
+        check_if_match(&source_path, args.if_match.as_deref()).await?;
+        if args.overwrite {
+            fs::rename(&source_path, &dest_path).await?;
+        } else {
+            let (from, to) = (source_path.clone(), dest_path.clone());
+            tokio::task::spawn_blocking(move || rename_noreplace(&from, &to))
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Move task failed: {e}")))?
+                .map_err(|e| match e.kind() {
+                    std::io::ErrorKind::AlreadyExists => McpError::InvalidArguments(format!(
+                        "Destination {} already exists. Nothing was moved; pass overwrite: true \
+                         to replace it.",
+                        args.destination
+                    )),
+                    _ => McpError::Io(e),
+                })?;
+        }
+
+        let undo_id = undo.commit().await;
 
         // Human summary
         let display_source = shorten_path_for_display(&source_path, ctx.git_root());
@@ -64,6 +141,8 @@ impl Tool for MoveFileTool {
             source: source_path.to_string_lossy().to_string(),
             destination: dest_path.to_string_lossy().to_string(),
             message: "File/directory moved successfully".to_string(),
+            undo_id,
+            dry_run: false,
         }))
     }
 }
@@ -0,0 +1,203 @@
+//! Compare-and-swap preconditions for the mutating file tools
+//!
+//! `fs_read_file` reports a file's `content_hash` (`sha256:<hex>`) and
+//! `modified` time. `fs_write_file`, `fs_edit_block`, `fs_move_file` and
+//! `fs_delete_file` accept either value back as `if_match` and refuse to touch
+//! a file that no longer matches, so an agent never silently overwrites a
+//! change someone else made after it last read the file.
+//!
+//! Both sides work from a single read: `fs_read_file` hashes the bytes it
+//! returns, and the writers check `if_match` against the bytes they go on to
+//! change, so no second read can slip a different version in between.
+//!
+//! Hashes are exact. Timestamps are compared at the precision the filesystem
+//! reports, which can miss two writes within the same tick on coarse-grained
+//! filesystems; prefer the hash where that matters.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use kodegen_mcp_schema::McpError;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::SystemTime;
+use tokio::io::AsyncReadExt;
+
+/// Files larger than this are not hashed by [`fingerprint`], which reads
+/// them only to hash them (only their mtime is reported)
+pub const MAX_HASH_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Prefix of content hashes
+pub const HASH_PREFIX: &str = "sha256:";
+
+/// What `if_match` can be compared against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint {
+    /// `sha256:<hex>` of the file's bytes; `None` for directories and files
+    /// over [`MAX_HASH_BYTES`]
+    pub content_hash: Option<String>,
+    /// Last modification time, RFC 3339 in UTC
+    pub modified: Option<String>,
+}
+
+/// Hash of `bytes` in the form `fs_read_file` reports
+#[must_use]
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{HASH_PREFIX}{:x}", Sha256::digest(bytes))
+}
+
+/// [`content_hash`] of bytes that arrive in pieces
+#[derive(Debug, Clone, Default)]
+pub struct ContentHasher(Sha256);
+
+impl ContentHasher {
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    #[must_use]
+    pub fn finish(self) -> String {
+        format!("{HASH_PREFIX}{:x}", self.0.finalize())
+    }
+}
+
+/// A modification time in the form `fs_read_file` reports
+#[must_use]
+pub fn format_modified(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Fingerprint the file or directory at `path`
+///
+/// # Errors
+/// Returns `McpError::Io` if the path cannot be read.
+pub async fn fingerprint(path: &Path) -> Result<Fingerprint, McpError> {
+    let metadata = tokio::fs::metadata(path).await?;
+    let modified = metadata.modified().ok().map(format_modified);
+
+    let content_hash = if metadata.is_file() && metadata.len() <= MAX_HASH_BYTES {
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut hasher = ContentHasher::default();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Some(hasher.finish())
+    } else {
+        None
+    };
+
+    Ok(Fingerprint {
+        content_hash,
+        modified,
+    })
+}
+
+/// Refuse with a conflict error unless `path` still matches `if_match`
+///
+/// `if_match` is a `content_hash` (`sha256:...`) or a `modified` timestamp as
+/// returned by `fs_read_file`. `None` always passes. For tools that rewrite
+/// the file's content, [`read_if_match`] checks the very bytes being edited.
+///
+/// # Errors
+/// Returns `McpError::InvalidArguments` when the precondition is malformed
+/// or fails (including when the file no longer exists).
+pub async fn check_if_match(path: &Path, if_match: Option<&str>) -> Result<(), McpError> {
+    let Some(expected) = if_match.map(str::trim) else {
+        return Ok(());
+    };
+
+    let current = match fingerprint(path).await {
+        Ok(current) => Some(current),
+        Err(McpError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    check(path, expected, current.as_ref())
+}
+
+/// Read the file at `path` once and check `if_match` against those bytes
+///
+/// Returns `None` when the file does not exist, which fails any `if_match`.
+/// The modification time is taken after the read, so a change racing the
+/// read shows up as a conflict rather than passing unnoticed.
+///
+/// # Errors
+/// Returns `McpError::Io` if the file cannot be read, and
+/// `McpError::InvalidArguments` when the precondition is malformed or fails.
+pub async fn read_if_match(
+    path: &Path,
+    if_match: Option<&str>,
+) -> Result<Option<Vec<u8>>, McpError> {
+    let bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => Some(bytes),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    if let Some(expected) = if_match.map(str::trim) {
+        let current = match &bytes {
+            Some(bytes) => Some(Fingerprint {
+                content_hash: Some(content_hash(bytes)),
+                modified: tokio::fs::metadata(path)
+                    .await?
+                    .modified()
+                    .ok()
+                    .map(format_modified),
+            }),
+            None => None,
+        };
+        check(path, expected, current.as_ref())?;
+    }
+    Ok(bytes)
+}
+
+/// Compare `expected` with the state of `path` (`None` if it is gone)
+fn check(path: &Path, expected: &str, current: Option<&Fingerprint>) -> Result<(), McpError> {
+    let Some(current) = current else {
+        return Err(conflict(path, expected, "the file no longer exists"));
+    };
+
+    if let Some(hash) = expected.strip_prefix(HASH_PREFIX) {
+        return match current.content_hash.as_deref() {
+            Some(actual) if actual[HASH_PREFIX.len()..].eq_ignore_ascii_case(hash) => Ok(()),
+            Some(actual) => Err(conflict(path, expected, &format!("it is now {actual}"))),
+            None => Err(McpError::InvalidArguments(format!(
+                "if_match: {} has no content hash (directory, or larger than {MAX_HASH_BYTES} \
+                 bytes); pass its modified timestamp instead",
+                path.display()
+            ))),
+        };
+    }
+
+    let expected_time = DateTime::parse_from_rfc3339(expected).map_err(|_| {
+        McpError::InvalidArguments(format!(
+            "if_match must be a content_hash (\"sha256:...\") or a modified timestamp \
+             (RFC 3339) from fs_read_file, got '{expected}'"
+        ))
+    })?;
+    let actual_time = current
+        .modified
+        .as_deref()
+        .and_then(|m| DateTime::parse_from_rfc3339(m).ok());
+    match actual_time {
+        Some(actual) if actual == expected_time => Ok(()),
+        _ => Err(conflict(
+            path,
+            expected,
+            &format!(
+                "it was modified at {}",
+                current.modified.as_deref().unwrap_or("an unknown time")
+            ),
+        )),
+    }
+}
+
+fn conflict(path: &Path, expected: &str, now: &str) -> McpError {
+    McpError::InvalidArguments(format!(
+        "Conflict: {} changed since it was read (if_match {expected}, but {now}). \
+         Nothing was modified; re-read the file and retry.",
+        path.display()
+    ))
+}
@@ -0,0 +1,218 @@
+//! Binary file detection and magic-number format identification
+//!
+//! Detection asks the same searcher `fs_search` uses with `BinaryMode::Auto`
+//! (ripgrep's default): a file is binary if a NUL byte appears in its leading
+//! buffer once a UTF-16 BOM has been transcoded. Identification only inspects
+//! well-known magic numbers and never parses the file further.
+
+use kodegen_mcp_schema::McpError;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// Number of leading bytes inspected for binary detection and magic numbers
+///
+/// Matches the capacity of the searcher's first buffer fill, which is where
+/// `fs_search` in `BinaryMode::Auto` sees the NUL byte of nearly all binaries.
+pub const SNIFF_LEN: usize = 64 * 1024;
+
+/// Hex dump window used for binary files when no `byte_length` is given
+///
+/// 4 KiB renders as 256 dump rows, which keeps one page readable.
+pub const DEFAULT_HEX_WINDOW: usize = 4 * 1024;
+
+/// A binary format recognized by its magic number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BinaryFormat {
+    /// Human-readable format name
+    pub name: &'static str,
+
+    /// MIME type commonly associated with the format
+    pub mime_type: &'static str,
+}
+
+/// Magic numbers checked at offset 0, most specific first
+const MAGIC_NUMBERS: &[(&[u8], BinaryFormat)] = &[
+    (
+        b"SQLite format 3\0",
+        BinaryFormat {
+            name: "SQLite database",
+            mime_type: "application/vnd.sqlite3",
+        },
+    ),
+    (
+        b"\x89PNG\r\n\x1a\n",
+        BinaryFormat {
+            name: "PNG image",
+            mime_type: "image/png",
+        },
+    ),
+    (
+        b"\x7fELF",
+        BinaryFormat {
+            name: "ELF executable",
+            mime_type: "application/x-elf",
+        },
+    ),
+    (
+        b"\0asm",
+        BinaryFormat {
+            name: "WebAssembly module",
+            mime_type: "application/wasm",
+        },
+    ),
+    (
+        b"\xfd7zXZ\0",
+        BinaryFormat {
+            name: "xz archive",
+            mime_type: "application/x-xz",
+        },
+    ),
+    (
+        b"7z\xbc\xaf\x27\x1c",
+        BinaryFormat {
+            name: "7-Zip archive",
+            mime_type: "application/x-7z-compressed",
+        },
+    ),
+    (
+        b"GIF87a",
+        BinaryFormat {
+            name: "GIF image",
+            mime_type: "image/gif",
+        },
+    ),
+    (
+        b"GIF89a",
+        BinaryFormat {
+            name: "GIF image",
+            mime_type: "image/gif",
+        },
+    ),
+    (
+        b"%PDF-",
+        BinaryFormat {
+            name: "PDF document",
+            mime_type: "application/pdf",
+        },
+    ),
+    (
+        b"PK\x03\x04",
+        BinaryFormat {
+            name: "ZIP archive",
+            mime_type: "application/zip",
+        },
+    ),
+    (
+        b"PK\x05\x06",
+        BinaryFormat {
+            name: "ZIP archive (empty)",
+            mime_type: "application/zip",
+        },
+    ),
+    (
+        b"\x28\xb5\x2f\xfd",
+        BinaryFormat {
+            name: "Zstandard archive",
+            mime_type: "application/zstd",
+        },
+    ),
+    (
+        b"\xfe\xed\xfa\xce",
+        BinaryFormat {
+            name: "Mach-O executable (32-bit)",
+            mime_type: "application/x-mach-binary",
+        },
+    ),
+    (
+        b"\xfe\xed\xfa\xcf",
+        BinaryFormat {
+            name: "Mach-O executable (64-bit)",
+            mime_type: "application/x-mach-binary",
+        },
+    ),
+    (
+        b"\xce\xfa\xed\xfe",
+        BinaryFormat {
+            name: "Mach-O executable (32-bit)",
+            mime_type: "application/x-mach-binary",
+        },
+    ),
+    (
+        b"\xcf\xfa\xed\xfe",
+        BinaryFormat {
+            name: "Mach-O executable (64-bit)",
+            mime_type: "application/x-mach-binary",
+        },
+    ),
+    (
+        b"\xca\xfe\xba\xbe",
+        BinaryFormat {
+            name: "Mach-O universal binary or Java class",
+            mime_type: "application/octet-stream",
+        },
+    ),
+    (
+        b"BZh",
+        BinaryFormat {
+            name: "bzip2 archive",
+            mime_type: "application/x-bzip2",
+        },
+    ),
+    (
+        b"\xff\xd8\xff",
+        BinaryFormat {
+            name: "JPEG image",
+            mime_type: "image/jpeg",
+        },
+    ),
+    (
+        b"\x1f\x8b",
+        BinaryFormat {
+            name: "gzip archive",
+            mime_type: "application/gzip",
+        },
+    ),
+    (
+        b"MZ",
+        BinaryFormat {
+            name: "PE/COFF executable",
+            mime_type: "application/vnd.microsoft.portable-executable",
+        },
+    ),
+];
+
+/// POSIX tar archives carry their magic at offset 257
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_FORMAT: BinaryFormat = BinaryFormat {
+    name: "tar archive",
+    mime_type: "application/x-tar",
+};
+
+/// Check whether a leading buffer looks like binary data
+#[must_use]
+pub fn looks_binary(header: &[u8]) -> bool {
+    crate::search::rg::binary::is_binary(header)
+}
+
+/// Identify a binary format from the first bytes of a file
+#[must_use]
+pub fn identify_format(header: &[u8]) -> Option<BinaryFormat> {
+    MAGIC_NUMBERS
+        .iter()
+        .find(|(magic, _)| header.starts_with(magic))
+        .map(|(_, format)| *format)
+        .or_else(|| {
+            header
+                .get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + 5)
+                .filter(|magic| *magic == b"ustar")
+                .map(|_| TAR_FORMAT)
+        })
+}
+
+/// Read up to `SNIFF_LEN` leading bytes of a file
+pub async fn read_header(path: &Path) -> Result<Vec<u8>, McpError> {
+    let file = tokio::fs::File::open(path).await?;
+    let mut header = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64).read_to_end(&mut header).await?;
+    Ok(header)
+}
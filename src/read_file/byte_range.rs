@@ -0,0 +1,106 @@
+//! Byte-addressed reads for `fs_read_file`
+//!
+//! Line-oriented reading breaks down on minified bundles, logs with a single
+//! giant line and fixed-record binary formats. These helpers seek straight to
+//! a byte offset and return a bounded window without touching the rest of
+//! the file.
+
+use kodegen_mcp_schema::McpError;
+use std::path::Path;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
+
+/// Window size used when `byte_length` is not specified
+pub const DEFAULT_BYTE_WINDOW: usize = 64 * 1024;
+
+/// A bounded slice of a file read at a byte offset
+#[derive(Debug, Clone)]
+pub struct ByteWindow {
+    /// Absolute file offset of `bytes[0]`
+    pub offset: u64,
+
+    /// Bytes read from the file (may be shorter than requested at EOF)
+    pub bytes: Vec<u8>,
+
+    /// Total size of the file in bytes
+    pub total_bytes: u64,
+}
+
+impl ByteWindow {
+    /// True if the window does not cover the entire file
+    #[must_use]
+    pub fn is_partial(&self) -> bool {
+        self.offset != 0 || self.offset + self.bytes.len() as u64 != self.total_bytes
+    }
+}
+
+/// Read a window of bytes from a file
+///
+/// A non-negative `byte_offset` reads up to `byte_length` bytes starting at
+/// that offset. A negative `byte_offset` reads the last `-byte_offset` bytes
+/// (tail behavior) and ignores `byte_length`, mirroring line-mode `offset`.
+///
+/// Memory: `O(byte_length)` regardless of file size
+pub async fn read_byte_window(
+    path: &Path,
+    byte_offset: i64,
+    byte_length: usize,
+) -> Result<ByteWindow, McpError> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let total_bytes = file.metadata().await?.len();
+
+    let (start, count) = if byte_offset < 0 {
+        let tail = byte_offset.unsigned_abs().min(total_bytes);
+        (total_bytes - tail, tail)
+    } else {
+        let start = byte_offset.unsigned_abs().min(total_bytes);
+        (start, (byte_length as u64).min(total_bytes - start))
+    };
+
+    file.seek(SeekFrom::Start(start)).await?;
+
+    let mut bytes = Vec::with_capacity(usize::try_from(count).unwrap_or(0));
+    file.take(count).read_to_end(&mut bytes).await?;
+
+    Ok(ByteWindow {
+        offset: start,
+        bytes,
+        total_bytes,
+    })
+}
+
+/// Decode a byte window as UTF-8 text, tolerating cut characters at its edges
+///
+/// A window that starts or ends in the middle of a multi-byte character is
+/// still text: up to three leading continuation bytes and a trailing
+/// incomplete sequence are trimmed. Returns the number of leading bytes
+/// skipped and the decoded text, or `None` if the window is not UTF-8 text
+/// (invalid sequences or NUL bytes).
+#[must_use]
+pub fn decode_utf8_window(bytes: &[u8], at_file_start: bool) -> Option<(usize, &str)> {
+    let skip = if at_file_start {
+        0
+    } else {
+        bytes
+            .iter()
+            .take(3)
+            .take_while(|&&b| b & 0b1100_0000 == 0b1000_0000)
+            .count()
+    };
+    let rest = &bytes[skip..];
+
+    let text = match std::str::from_utf8(rest) {
+        Ok(text) => text,
+        // Incomplete final character: the window boundary cut it in half
+        Err(e) if e.error_len().is_none() && rest.len() - e.valid_up_to() < 4 => {
+            // valid_up_to() marks a verified UTF-8 prefix
+            std::str::from_utf8(&rest[..e.valid_up_to()]).ok()?
+        }
+        Err(_) => return None,
+    };
+
+    if text.contains('\0') {
+        return None;
+    }
+
+    Some((skip, text))
+}
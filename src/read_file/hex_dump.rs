@@ -0,0 +1,56 @@
+//! xxd-style hex dump rendering for binary content
+//!
+//! Produces one row per 16 bytes in the familiar `xxd` layout:
+//! `00000010: 7f45 4c46 0201 0100 0000 0000 0000 0000  .ELF............`
+
+use std::fmt::Write as _;
+
+/// Number of bytes rendered per hex dump row
+pub const BYTES_PER_ROW: usize = 16;
+
+/// Render bytes as an xxd-style hex dump
+///
+/// `base_offset` is the file offset of `bytes[0]`, so the offset column
+/// always matches absolute positions in the file even for windowed reads.
+#[must_use]
+pub fn hex_dump(bytes: &[u8], base_offset: u64) -> String {
+    // 8 offset + 2 ": " + 40 hex groups + 2 gap + 16 ascii + newline
+    let mut out = String::with_capacity(bytes.len().div_ceil(BYTES_PER_ROW) * 69);
+
+    for (row, chunk) in bytes.chunks(BYTES_PER_ROW).enumerate() {
+        if row > 0 {
+            out.push('\n');
+        }
+
+        let offset = base_offset + (row * BYTES_PER_ROW) as u64;
+        let _ = write!(out, "{offset:08x}:");
+
+        // Hex columns, grouped in pairs like xxd; short rows are padded
+        // so the ASCII column stays aligned
+        for i in 0..BYTES_PER_ROW {
+            if i % 2 == 0 {
+                out.push(' ');
+            }
+            match chunk.get(i) {
+                Some(b) => {
+                    let _ = write!(out, "{b:02x}");
+                }
+                None => out.push_str("  "),
+            }
+        }
+
+        out.push_str("  ");
+        out.extend(chunk.iter().map(|&b| printable(b)));
+    }
+
+    out
+}
+
+/// Map a byte to its ASCII column representation
+fn printable(b: u8) -> char {
+    if b.is_ascii_graphic() || b == b' ' {
+        b as char
+    } else {
+        '.'
+    }
+}
@@ -0,0 +1,21 @@
+//! Line-numbered (cat -n style) rendering for `fs_read_file`
+//!
+//! Numbers are absolute and 1-based, matching the line numbers reported by
+//! `fs_search` and `fs_edit_block`, so a window read from the middle of a
+//! file can be cross-referenced without counting.
+
+/// Minimum width of the line number column (same as `cat -n`)
+const NUMBER_WIDTH: usize = 6;
+
+/// Prefix each line with its absolute 1-based number and a tab
+///
+/// `first_line` is the 1-based number of `lines[0]`.
+#[must_use]
+pub fn number_lines(lines: &[String], first_line: usize) -> String {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| format!("{:>NUMBER_WIDTH$}\t{line}", first_line + i))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
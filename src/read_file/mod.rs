@@ -0,0 +1,914 @@
+pub mod binary;
+pub mod byte_range;
+pub mod hex_dump;
+pub mod line_numbers;
+pub mod outline;
+pub mod web;
+
+use crate::encoding::{self, has_bom, resolve_label};
+use crate::precondition::{ContentHasher, content_hash, format_modified};
+use crate::schema::{FsReadFileArgs, FsReadFileOutput};
+use crate::validate_path;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use binary::{DEFAULT_HEX_WINDOW, identify_format, looks_binary, read_header};
+use byte_range::{DEFAULT_BYTE_WINDOW, decode_utf8_window, read_byte_window};
+use hex_dump::hex_dump;
+use line_numbers::number_lines;
+use outline::{Language, Symbol, find_symbols, outline};
+use web::cache::{CacheMode, HttpCache, fetch_cached};
+use web::convert::{UrlContent, convert};
+use web::policy::UrlPolicy;
+use kodegen_config::shorten_path_for_display;
+use kodegen_mcp_schema::filesystem::ReadFilePrompts;
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use mime_guess::from_path;
+
+use std::collections::VecDeque;
+use std::path::Path;
+use tokio::fs;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+// ============================================================================
+// INTERNAL TYPES
+// ============================================================================
+
+/// Internal file read result (before conversion to MCP output)
+/// 
+/// This struct mirrors FsReadFileOutput but excludes fields that are
+/// added by execute() (success, path). All internal methods return this
+/// instead of serde_json::Value for compile-time type safety.
+#[derive(Debug, Clone)]
+struct InternalReadResult {
+    /// File content (text or base64-encoded binary)
+    content: String,
+    
+    /// MIME type detected from file extension or HTTP headers
+    mime_type: String,
+    
+    /// Whether this is an image file (determines base64 encoding)
+    is_image: bool,
+    
+    /// Size in bytes (Some for images/URLs, None for text files from disk)
+    size_bytes: Option<u64>,
+    
+    /// Total lines in file (Some for text files from disk, None for images/URLs)
+    total_lines: Option<u64>,
+    
+    /// Number of lines actually read (Some for text files from disk, None for images/URLs)
+    lines_read: Option<u64>,
+    
+    /// Whether this is a partial read (offset != 0 or didn't read to EOF)
+    is_partial: bool,
+
+    /// Byte offset of the returned window (Some for byte-addressed reads only)
+    byte_offset: Option<u64>,
+
+    /// Bytes covered by the returned window (Some for byte-addressed reads only)
+    bytes_read: Option<u64>,
+
+    /// Whether content is a hex dump (binary files and non-UTF-8 byte windows)
+    is_hex_dump: bool,
+
+    /// Binary format identified from the file's magic number (hex dumps only)
+    binary_format: Option<String>,
+
+    /// Encoding the text was decoded from (None for plain UTF-8)
+    encoding: Option<String>,
+
+    /// First returned line, 1-based (Some for line-mode text reads only)
+    start_line: Option<u64>,
+
+    /// Last returned line, 1-based inclusive (Some for line-mode text reads only)
+    end_line: Option<u64>,
+
+    /// Display name of the selected symbol (symbol reads only)
+    symbol: Option<String>,
+
+    /// Conversion applied to a URL response ("html-to-markdown", "json-pretty")
+    conversion: Option<String>,
+
+    /// Where a URL body came from ("miss", "hit", "revalidated", "stale")
+    cache_status: Option<String>,
+
+    /// Hash of the bytes read (Some only when the whole local file was read)
+    content_hash: Option<String>,
+
+    /// Modification time of a local file, taken before its content was read
+    modified: Option<String>,
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+/// Symbols listed in the error when a selector matches nothing
+const MAX_LISTED_SYMBOLS: usize = 40;
+
+fn is_image_mime(mt: &str) -> bool {
+    mt.to_lowercase().starts_with("image/")
+}
+
+/// Read lines starting from offset, returning both lines and total count in ONE pass
+///
+/// This is more efficient than separate counting because we iterate once:
+/// - Count all lines while processing
+/// - Skip lines until we reach start position
+/// - Collect lines until we reach start + count
+///
+/// When the lines collected turn out to be the whole file, the hash of the
+/// raw bytes read is returned too; hashing stops as soon as a line falls
+/// outside the window.
+///
+/// Memory: O(count) instead of `O(total_lines)`
+async fn read_lines_forward_with_total(
+    path: &std::path::Path,
+    start: usize,
+    count: usize,
+) -> Result<(Vec<String>, Option<usize>, Option<String>), McpError> {
+    let file = tokio::fs::File::open(path).await?;
+
+    let mut reader = BufReader::new(file);
+    let mut raw = Vec::new();
+    let mut hasher = (start == 0).then(ContentHasher::default);
+
+    let mut result = Vec::with_capacity(count);
+    let mut line_number = 0;
+
+    loop {
+        raw.clear();
+        if reader.read_until(b'\n', &mut raw).await? == 0 {
+            break;
+        }
+
+        // Collect lines in target range
+        if line_number >= start && result.len() < count {
+            if let Some(hasher) = hasher.as_mut() {
+                hasher.update(&raw);
+            }
+            result.push(utf8_line(&raw)?);
+        } else {
+            hasher = None;
+            utf8_line(&raw)?;
+        }
+
+        line_number += 1;
+    }
+
+    Ok((result, Some(line_number), hasher.map(ContentHasher::finish)))
+}
+
+/// A raw line without its line ending, as `AsyncBufReadExt::lines` yields it
+fn utf8_line(raw: &[u8]) -> std::io::Result<String> {
+    let line = raw.strip_suffix(b"\n").unwrap_or(raw);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    String::from_utf8(line.to_vec()).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })
+}
+
+/// Read last N lines using ring buffer, returning both lines and total count in ONE pass
+///
+/// Uses `VecDeque` as circular buffer:
+/// - Capacity = `tail_count` (memory efficient)
+/// - Pop oldest when full, push newest
+/// - Result: last N lines + total line count
+///
+/// Memory: `O(tail_count)` instead of `O(total_lines)`
+async fn read_lines_tail_with_total(
+    path: &std::path::Path,
+    tail_count: usize,
+) -> Result<(Vec<String>, Option<usize>), McpError> {
+    let file = tokio::fs::File::open(path).await?;
+
+    let reader = BufReader::new(file);
+    let mut lines_stream = reader.lines();
+
+    // Handle tail_count = 0 edge case
+    if tail_count == 0 {
+        // Count total lines but don't collect any
+        let mut total_lines = 0;
+        while lines_stream.next_line().await?.is_some() {
+            total_lines += 1;
+        }
+        return Ok((Vec::new(), Some(total_lines)));
+    }
+
+    // Ring buffer for last N lines
+    let mut ring_buffer = VecDeque::with_capacity(tail_count);
+    let mut total_lines = 0;
+
+    while let Some(line) = lines_stream.next_line().await? {
+        if ring_buffer.len() == tail_count {
+            ring_buffer.pop_front(); // Remove oldest
+        }
+        ring_buffer.push_back(line);
+        total_lines += 1;
+    }
+
+    Ok((ring_buffer.into_iter().collect(), Some(total_lines)))
+}
+
+/// Select lines from already-decoded text with the same semantics as the
+/// streaming readers (negative `offset` reads the last N lines)
+///
+/// Used for non-UTF-8 files, which must be decoded whole before they can be
+/// split into lines, and for converted URL responses.
+fn select_lines(text: &str, offset: i64, count: usize) -> (Vec<String>, Option<usize>) {
+    let lines: Vec<&str> = text.lines().collect();
+    let total = lines.len();
+
+    let selected = if offset < 0 {
+        let tail_count = usize::try_from(-offset).unwrap_or(0).min(total);
+        &lines[total - tail_count..]
+    } else {
+        let start = usize::try_from(offset).unwrap_or(0).min(total);
+        &lines[start..(start.saturating_add(count)).min(total)]
+    };
+
+    (
+        selected.iter().map(|line| (*line).to_owned()).collect(),
+        Some(total),
+    )
+}
+
+/// Render a selected line window, prefixing a notice for partial reads
+///
+/// Returns the content, whether the read was partial and the 0-based
+/// `start..end` line range it covers. The notice names the lines as
+/// `start_line`/`end_line` do: 1-based and inclusive.
+#[must_use]
+pub fn render_line_window(
+    lines_vec: Vec<String>,
+    total: Option<usize>,
+    offset: i64,
+    line_numbers: bool,
+) -> (String, bool, usize, usize) {
+    let lines_read = lines_vec.len();
+
+    // Calculate start/end for notice formatting
+    let (start, end) = if offset < 0 {
+        // Tail reads always have total
+        if let Some(t) = total {
+            let start = t.saturating_sub(lines_read);
+            (start, t)
+        } else {
+            // Fallback (should not happen for tail reads)
+            (0, lines_read)
+        }
+    } else {
+        let start = usize::try_from(offset).unwrap_or(0);
+        let end = start + lines_read;
+        (start, end)
+    };
+
+    let mut content = if line_numbers {
+        number_lines(&lines_vec, start + 1)
+    } else {
+        lines_vec.join("\n")
+    };
+
+    // Determine if this is a partial read
+    let is_partial = offset != 0 || total.is_none_or(|t| end < t);
+
+    // If partial read, add a notice
+    if is_partial {
+        let count = end - start;
+        let range = if count > 0 {
+            format!("lines {}-{}", start + 1, end)
+        } else {
+            format!("none at line {}", start + 1)
+        };
+        let notice = match total {
+            // Tail reads always have total
+            Some(t) if offset < 0 => {
+                format!("[Reading last {count} lines ({range}) of {t} total lines]\n\n")
+            }
+            Some(t) => format!("[Reading {count} lines ({range}) of {t} total lines]\n\n"),
+            None => format!("[Reading {count} lines ({range})]\n\n"),
+        };
+        content = format!("{notice}{content}");
+    }
+
+    (content, is_partial, start, end)
+}
+
+/// Modification time of `path`, for `if_match` on later writes
+///
+/// Taken before the content is read, so a change racing the read leaves a
+/// stamp older than the content, which fails `if_match` instead of passing.
+async fn modified_before_read(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).await.ok()?;
+    metadata.modified().ok().map(format_modified)
+}
+
+/// Read a byte window of an already-validated file
+///
+/// Text windows are returned as UTF-8 (trimming characters cut by the window
+/// edges). Binary files, detected with the search layer's NUL-byte heuristic
+/// on `header`, and non-UTF-8 windows are rendered as an xxd-style hex dump
+/// with the file's magic-number format identified.
+async fn read_byte_range(
+    valid_path: &Path,
+    header: &[u8],
+    byte_offset: i64,
+    byte_length: Option<usize>,
+) -> Result<InternalReadResult, McpError> {
+    let modified = modified_before_read(valid_path).await;
+    let guessed = from_path(valid_path)
+        .first_or_octet_stream()
+        .essence_str()
+        .to_owned();
+
+    let is_binary = looks_binary(header);
+    let default_length = if is_binary {
+        DEFAULT_HEX_WINDOW
+    } else {
+        DEFAULT_BYTE_WINDOW
+    };
+
+    let window = read_byte_window(
+        valid_path,
+        byte_offset,
+        byte_length.unwrap_or(default_length),
+    )
+    .await?;
+    let is_partial = window.is_partial();
+
+    let decoded = if is_binary {
+        None
+    } else {
+        decode_utf8_window(&window.bytes, window.offset == 0)
+    };
+
+    let (offset, len, body, is_hex_dump) = match decoded {
+        Some((skip, text)) => (
+            window.offset + skip as u64,
+            text.len() as u64,
+            text.to_owned(),
+            false,
+        ),
+        None => (
+            window.offset,
+            window.bytes.len() as u64,
+            hex_dump(&window.bytes, window.offset),
+            true,
+        ),
+    };
+
+    let last_byte = (offset + len).saturating_sub(1);
+    let format = if is_hex_dump { identify_format(header) } else { None };
+
+    let content = if is_hex_dump {
+        let name = format.map_or("unknown format", |f| f.name);
+        let paging = if is_partial {
+            " · use byte_offset/byte_length to page"
+        } else {
+            ""
+        };
+        format!(
+            "[Binary file: {name} · bytes {offset}-{last_byte} of {} total bytes{paging}]\n\n{body}",
+            window.total_bytes
+        )
+    } else if is_partial {
+        format!(
+            "[Reading {len} bytes (bytes {offset}-{last_byte}) of {} total bytes]\n\n{body}",
+            window.total_bytes
+        )
+    } else {
+        body
+    };
+
+    // Prefer the identified format's MIME type over a generic guess
+    let mime_type = match format {
+        Some(f) if guessed == "application/octet-stream" => f.mime_type.to_owned(),
+        _ => guessed,
+    };
+
+    Ok(InternalReadResult {
+        content,
+        mime_type,
+        is_image: false,
+        size_bytes: Some(window.total_bytes),
+        total_lines: None,
+        lines_read: None,
+        is_partial,
+        byte_offset: Some(offset),
+        bytes_read: Some(len),
+        is_hex_dump,
+        binary_format: format.map(|f| f.name.to_owned()),
+        encoding: None,
+        start_line: None,
+        end_line: None,
+        symbol: None,
+        conversion: None,
+        cache_status: None,
+        content_hash: (!is_partial).then(|| content_hash(&window.bytes)),
+        modified,
+    })
+}
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone)]
+pub struct ReadFileTool {
+    default_line_limit: usize,
+    config_manager: kodegen_config_manager::ConfigManager,
+}
+
+impl ReadFileTool {
+    #[must_use]
+    pub fn new(
+        default_line_limit: usize,
+        config_manager: kodegen_config_manager::ConfigManager,
+    ) -> Self {
+        Self {
+            default_line_limit,
+            config_manager,
+        }
+    }
+
+    /// Read file from disk with full validation
+    async fn read_file_from_disk(
+        &self,
+        path: &str,
+        offset: i64,
+        length: Option<usize>,
+        encoding: Option<&str>,
+        line_numbers: bool,
+        client_pwd: Option<&Path>,
+    ) -> Result<InternalReadResult, McpError> {
+        let valid_path = validate_path(path, &self.config_manager, client_pwd).await?;
+        let explicit_encoding = encoding.map(resolve_label).transpose()?;
+        let modified = modified_before_read(&valid_path).await;
+
+        let guessed = from_path(&valid_path)
+            .first_or_octet_stream()
+            .essence_str()
+            .to_owned();
+
+        let is_img = is_image_mime(&guessed);
+
+        // If image, read as base64
+        if is_img {
+            let bytes = fs::read(&valid_path).await?;
+            let base64_content = BASE64.encode(&bytes);
+
+            return Ok(InternalReadResult {
+                content: base64_content,
+                mime_type: guessed,
+                is_image: true,
+                size_bytes: Some(bytes.len() as u64),
+                total_lines: None,
+                lines_read: None,
+                is_partial: false,
+                byte_offset: None,
+                bytes_read: None,
+                is_hex_dump: false,
+                binary_format: None,
+                encoding: None,
+                start_line: None,
+                end_line: None,
+                symbol: None,
+                conversion: None,
+                cache_status: None,
+                content_hash: Some(content_hash(&bytes)),
+                modified,
+            });
+        }
+
+        let header = read_header(&valid_path).await?;
+
+        // A BOM or an explicit encoding marks the file as text, even though
+        // UTF-16 is full of NUL bytes
+        let text_encoding = (explicit_encoding.is_some() || has_bom(&header))
+            .then(|| encoding::detect(&header, explicit_encoding));
+
+        // Binary files can't be read line by line - return the first hex dump page
+        if text_encoding.is_none() && looks_binary(&header) {
+            return read_byte_range(&valid_path, &header, 0, None).await;
+        }
+
+        // The hash is kept only if the window turns out to be the whole file
+        let (lines_vec, total, whole_file_hash) = if let Some(text_encoding) = text_encoding {
+            // Non-UTF-8 text must be decoded whole before it can be split into lines
+            let bytes = fs::read(&valid_path).await?;
+            let text = encoding::decode(&bytes, text_encoding)?;
+            let (lines, total) =
+                select_lines(&text, offset, length.unwrap_or(self.default_line_limit));
+            (lines, total, (offset == 0).then(|| content_hash(&bytes)))
+        } else if offset < 0 {
+            // Handle text files - use streaming to avoid loading entire file
+            // Tail behavior: read last N lines (always partial, so never hashed)
+            let tail_count = usize::try_from(-offset).unwrap_or(0);
+            let (lines, total) = read_lines_tail_with_total(&valid_path, tail_count).await?;
+            (lines, total, None)
+        } else {
+            // Forward read: skip to offset, read length lines
+            let start = usize::try_from(offset).unwrap_or(0);
+            let count = length.unwrap_or(self.default_line_limit);
+            read_lines_forward_with_total(&valid_path, start, count).await?
+        };
+
+        let (content, is_partial, start, end) =
+            render_line_window(lines_vec, total, offset, line_numbers);
+
+        Ok(InternalReadResult {
+            content,
+            mime_type: guessed,
+            is_image: false,
+            size_bytes: None,
+            total_lines: total.map(|t| t as u64),
+            lines_read: Some((end - start) as u64),
+            is_partial,
+            byte_offset: None,
+            bytes_read: None,
+            is_hex_dump: false,
+            binary_format: None,
+            encoding: text_encoding.and_then(|e| e.label()),
+            start_line: (end > start).then_some(start as u64 + 1),
+            end_line: (end > start).then_some(end as u64),
+            symbol: None,
+            conversion: None,
+            cache_status: None,
+            content_hash: whole_file_hash.filter(|_| !is_partial),
+            modified,
+        })
+    }
+
+    /// Read a byte window from disk with full validation
+    async fn read_bytes_from_disk(
+        &self,
+        path: &str,
+        byte_offset: i64,
+        byte_length: Option<usize>,
+        client_pwd: Option<&Path>,
+    ) -> Result<InternalReadResult, McpError> {
+        let valid_path = validate_path(path, &self.config_manager, client_pwd).await?;
+        let header = read_header(&valid_path).await?;
+
+        read_byte_range(&valid_path, &header, byte_offset, byte_length).await
+    }
+
+    /// Read the span of a named symbol (function, type, heading or key path)
+    async fn read_symbol_from_disk(
+        &self,
+        path: &str,
+        selector: &str,
+        encoding: Option<&str>,
+        line_numbers: bool,
+        client_pwd: Option<&Path>,
+    ) -> Result<InternalReadResult, McpError> {
+        let valid_path = validate_path(path, &self.config_manager, client_pwd).await?;
+        let explicit_encoding = encoding.map(resolve_label).transpose()?;
+        let modified = modified_before_read(&valid_path).await;
+
+        let language = Language::from_path(&valid_path).ok_or_else(|| {
+            McpError::InvalidArguments(format!(
+                "Symbol reads are not supported for {path}. Supported: Rust, C/C++/C#, Go, Java, \
+                 JavaScript/TypeScript, Kotlin, Swift, Python, Markdown, JSON and TOML files."
+            ))
+        })?;
+
+        let (text, text_encoding) = encoding::read_text(&valid_path, explicit_encoding).await?;
+        let matches = find_symbols(&text, language, selector);
+
+        let Some(symbol) = matches.first() else {
+            let available: Vec<String> = outline(&text, language)
+                .iter()
+                .take(MAX_LISTED_SYMBOLS)
+                .map(Symbol::display_name)
+                .collect();
+            let hint = if available.is_empty() {
+                String::new()
+            } else {
+                format!("\n\nSymbols in this file:\n  {}", available.join("\n  "))
+            };
+            return Err(McpError::InvalidArguments(format!(
+                "Symbol '{selector}' not found in {path}{hint}"
+            )));
+        };
+
+        let total = text.lines().count();
+        let lines: Vec<String> = text
+            .lines()
+            .skip(symbol.start_line)
+            .take(symbol.end_line + 1 - symbol.start_line)
+            .map(String::from)
+            .collect();
+        let (first, last) = (symbol.start_line + 1, symbol.end_line + 1);
+
+        let body = if line_numbers {
+            number_lines(&lines, first)
+        } else {
+            lines.join("\n")
+        };
+
+        // Later matches (overloads, several impls) are listed rather than merged
+        let also = if matches.len() > 1 {
+            let others: Vec<String> = matches[1..]
+                .iter()
+                .map(|other| (other.start_line + 1).to_string())
+                .collect();
+            format!(" · also at line(s) {}", others.join(", "))
+        } else {
+            String::new()
+        };
+
+        let display_name = symbol.display_name();
+        let content = format!(
+            "[{display_name} · lines {first}-{last} of {total} total lines{also}]\n\n{body}"
+        );
+
+        Ok(InternalReadResult {
+            content,
+            mime_type: from_path(&valid_path)
+                .first_or_octet_stream()
+                .essence_str()
+                .to_owned(),
+            is_image: false,
+            size_bytes: None,
+            total_lines: Some(total as u64),
+            lines_read: Some(lines.len() as u64),
+            is_partial: lines.len() < total,
+            byte_offset: None,
+            bytes_read: None,
+            is_hex_dump: false,
+            binary_format: None,
+            encoding: text_encoding.label(),
+            start_line: Some(first as u64),
+            end_line: Some(last as u64),
+            symbol: Some(display_name),
+            conversion: None,
+            cache_status: None,
+            content_hash: None,
+            modified,
+        })
+    }
+
+    /// Read file from URL with timeout
+    async fn read_file_from_url(
+        &self,
+        url: &str,
+        offset: i64,
+        length: Option<usize>,
+        line_numbers: bool,
+        cache_mode: Option<&str>,
+    ) -> Result<InternalReadResult, McpError> {
+        let mode = cache_mode.map(CacheMode::parse).transpose()?.unwrap_or_default();
+        let policy = UrlPolicy::from_config(&self.config_manager);
+
+        // Without a state directory, reads go straight to the network
+        let (body, cache_status) = match HttpCache::open_default() {
+            Some(cache) => {
+                let (body, status) = fetch_cached(url, &policy, &cache, mode).await?;
+                (body, Some(status.label().to_string()))
+            }
+            None if mode == CacheMode::Only => {
+                return Err(McpError::ResourceNotFound(format!(
+                    "{url}: the URL cache is unavailable (no kodegen state directory)"
+                )));
+            }
+            None => (web::fetch(url, &policy).await?, None),
+        };
+        let size_bytes = Some(body.bytes.len() as u64);
+
+        let (text, conversion) = match convert(&body)? {
+            UrlContent::Image => {
+                return Ok(InternalReadResult {
+                    content: BASE64.encode(&body.bytes),
+                    mime_type: body.content_type,
+                    is_image: true,
+                    size_bytes,
+                    total_lines: None,
+                    lines_read: None,
+                    is_partial: false,
+                    byte_offset: None,
+                    bytes_read: None,
+                    is_hex_dump: false,
+                    binary_format: None,
+                    encoding: None,
+                    start_line: None,
+                    end_line: None,
+                    symbol: None,
+                    conversion: None,
+                    cache_status,
+                    content_hash: None,
+                    modified: None,
+                });
+            }
+            UrlContent::Text { text, conversion } => (text, conversion),
+        };
+
+        // Windows apply to the converted text, exactly like disk reads
+        let (lines_vec, total) =
+            select_lines(&text, offset, length.unwrap_or(self.default_line_limit));
+        let (content, is_partial, start, end) =
+            render_line_window(lines_vec, total, offset, line_numbers);
+
+        let mime_type = if body.content_type.is_empty() {
+            "text/plain".to_string()
+        } else {
+            body.content_type
+        };
+
+        Ok(InternalReadResult {
+            content,
+            mime_type,
+            is_image: false,
+            size_bytes,
+            total_lines: total.map(|t| t as u64),
+            lines_read: Some((end - start) as u64),
+            is_partial,
+            byte_offset: None,
+            bytes_read: None,
+            is_hex_dump: false,
+            binary_format: None,
+            encoding: None,
+            start_line: (end > start).then_some(start as u64 + 1),
+            end_line: (end > start).then_some(end as u64),
+            symbol: None,
+            conversion: conversion.map(|c| c.label().to_string()),
+            cache_status,
+            content_hash: None,
+            modified: None,
+        })
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for ReadFileTool {
+    type Args = FsReadFileArgs;
+    type Prompts = ReadFilePrompts;
+
+    fn name() -> &'static str {
+        kodegen_mcp_schema::filesystem::FS_READ_FILE
+    }
+
+    fn description() -> &'static str {
+        "Read the contents of a file from the filesystem or a URL. Supports text files (returned as text) \
+         and image files (returned as base64). Use offset and length parameters to read specific \
+         portions of large files. Supports negative offsets for tail behavior (offset: -N reads last N lines). \
+         When offset is negative, length is ignored. Set line_numbers to prefix each line with its \
+         absolute 1-based line number (cat -n style); start_line/end_line report the returned \
+         window in the same numbering used by fs_search and fs_edit_block. Set symbol to read just \
+         one definition: a function, struct, impl or class (\"execute\", \"EditBlockTool::execute\", \
+         \"struct Foo\"), a markdown heading, or a JSON/TOML key path (\"dependencies.serde\"). Use byte_offset and byte_length to read a byte \
+         range instead of lines (negative byte_offset reads the last N bytes). Text in other encodings \
+         is detected from its BOM or read with the `encoding` parameter (e.g. \"shiftjis\", \"utf-16le\"). Binary files (ELF, PE, \
+         wasm, sqlite, zip, ...) are detected automatically and returned as a paginated xxd-style hex \
+         dump with the format identified. URL reads are converted by content type: \
+         HTML pages become markdown without scripts, styles or navigation, JSON is pretty-printed, \
+         and PDFs/binary downloads are refused; offset and length apply to the converted text. \
+         Private, loopback and link-local hosts are blocked unless allowed by the server's URL policy. \
+         URL responses are cached on disk and revalidated with ETag/Last-Modified; set cache to \
+         \"refresh\" to force a download or \"only\" to read offline from the cache. \
+         Automatically validates paths and handles symlinks."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        true // Can read from URLs
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        // Auto-detect URL if not specified
+        let is_url =
+            args.is_url || args.path.starts_with("http://") || args.path.starts_with("https://");
+
+        // Get result from helper
+        let result = if is_url {
+            self.read_file_from_url(
+                &args.path,
+                args.offset,
+                args.length,
+                args.line_numbers,
+                args.cache.as_deref(),
+            )
+            .await?
+        } else if let Some(selector) = args.symbol.as_deref() {
+            self.read_symbol_from_disk(
+                &args.path,
+                selector,
+                args.encoding.as_deref(),
+                args.line_numbers,
+                ctx.pwd(),
+            )
+            .await?
+        } else if let Some(byte_offset) = args.byte_offset {
+            self.read_bytes_from_disk(&args.path, byte_offset, args.byte_length, ctx.pwd())
+                .await?
+        } else {
+            self.read_file_from_disk(
+                &args.path,
+                args.offset,
+                args.length,
+                args.encoding.as_deref(),
+                args.line_numbers,
+                ctx.pwd(),
+            )
+            .await?
+        };
+
+        // Direct struct field access - compile-time guaranteed
+        let content = result.content;
+        let mime_type = result.mime_type;
+        let is_image = result.is_image;
+        let size_bytes = result.size_bytes;
+        let total_lines = result.total_lines;
+        let lines_read = result.lines_read;
+        let is_partial = result.is_partial;
+        let byte_offset = result.byte_offset;
+        let bytes_read = result.bytes_read;
+        let is_hex_dump = result.is_hex_dump;
+        let binary_format = result.binary_format;
+        let encoding = result.encoding;
+        let start_line = result.start_line;
+        let end_line = result.end_line;
+        let symbol = result.symbol;
+        let conversion = result.conversion;
+        let cache_status = result.cache_status;
+
+        let content_hash = result.content_hash;
+        let modified = result.modified;
+
+        // For URLs, display as-is; for file paths, use relative path if in git repo
+        let display_path = if is_url {
+            args.path.clone()
+        } else {
+            shorten_path_for_display(
+                std::path::Path::new(&args.path),
+                ctx.git_root()
+            )
+        };
+        
+        let summary = if is_image {
+            let size_kb = size_bytes.map_or(0.0, |b| b as f64 / 1024.0);
+            format!(
+                "\x1b[36mRead image: {display_path}\x1b[0m\nFormat: {mime_type} · Size: {size_kb:.1} KB"
+            )
+        } else if let (Some(offset), Some(read)) = (byte_offset, bytes_read) {
+            let total = size_bytes.unwrap_or(0);
+            let kind = if is_hex_dump { "hex dump" } else { "text" };
+            format!(
+                "\x1b[36mRead file: {display_path}\x1b[0m\nContent: {read} bytes at offset {offset} of {total} bytes · {kind}"
+            )
+        } else {
+            let read = lines_read.unwrap_or(0);
+            let encoding_note = encoding
+                .as_deref()
+                .or(conversion.as_deref())
+                .map(|e| format!(" · {e}"))
+                .unwrap_or_default();
+            let range_note = match (start_line, end_line) {
+                (Some(first), Some(last)) => format!(" ({first}-{last})"),
+                _ => String::new(),
+            };
+            let cache_note = cache_status
+                .as_deref()
+                .map(|status| format!(" · cache {status}"))
+                .unwrap_or_default();
+            format!(
+                "\x1b[36mRead file: {display_path}\x1b[0m\nContent: {read} lines{range_note} · {} bytes{encoding_note}{cache_note}",
+                content.len()
+            )
+        };
+
+        // For read_file, display includes the content itself
+        let display = format!("{summary}\n\n{content}");
+
+        Ok(ToolResponse::new(display, FsReadFileOutput {
+            success: true,
+            path: args.path,
+            mime_type,
+            is_image,
+            size_bytes,
+            total_lines,
+            lines_read,
+            is_partial,
+            content,
+            byte_offset,
+            bytes_read,
+            is_hex_dump,
+            binary_format,
+            encoding,
+            start_line,
+            end_line,
+            symbol,
+            conversion,
+            cache_status,
+            content_hash,
+            modified,
+        }))
+    }
+}
@@ -0,0 +1,535 @@
+//! Outline extraction for brace languages and Python
+//!
+//! Source is first masked: comments and string/char literal contents become
+//! spaces, so declarations and braces inside them are never seen. Declaration
+//! lines are then recognized with per-language patterns, and each span is
+//! closed by brace matching (brace languages) or indentation (Python).
+
+use super::{Language, Symbol, SymbolKind};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Lines scanned for an opening brace (or `;`) before a declaration is dropped
+const MAX_SIGNATURE_LINES: usize = 20;
+
+/// Words that look like calls but never start a definition
+const NOT_A_NAME: &[&str] = &[
+    "if", "for", "foreach", "while", "switch", "catch", "return", "new", "throw", "else", "do",
+    "await", "yield", "case", "typeof", "delete", "sizeof", "using", "lock", "super", "this",
+    "with", "when", "match", "assert", "defer", "go", "select", "synchronized",
+];
+
+static RUST_IMPL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:#\[[^\]]*\]\s*)*(?:unsafe\s+)?impl\b(?:\s*<.*?>)?\s+(?:!?[\w:]+(?:<.*?>)?\s+for\s+)?(?:dyn\s+)?([\w:]+)",
+    )
+    .expect("valid regex")
+});
+
+static RUST_FN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^(?:#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?(?:default\s+)?(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(?:extern\s+(?:"[^"]*"\s+)?)?fn\s+(\w+)"#,
+    )
+    .expect("valid regex")
+});
+
+static RUST_ITEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?(?:unsafe\s+)?(struct|enum|union|trait|mod)\s+(\w+)",
+    )
+    .expect("valid regex")
+});
+
+static TYPE_DECL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:@\w+(?:\([^)]*\))?\s+)*(?:(?:export|default|public|private|protected|internal|abstract|final|sealed|static|data|open|partial|declare|inline|enum|annotation|case|typedef)\s+)*(class|interface|struct|enum|trait|object|protocol|namespace|record)\s+(\w+)",
+    )
+    .expect("valid regex")
+});
+
+static GO_FUNC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^func\s+(?:\(\s*(?:\w+\s+)?\*?\s*(\w+)[^)]*\)\s*)?(\w+)").expect("valid regex")
+});
+
+static JS_FUNCTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:export\s+)?(?:default\s+)?(?:async\s+)?function\s*\*?\s*(\w+)")
+        .expect("valid regex")
+});
+
+static JS_ARROW: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:export\s+)?(?:const|let|var)\s+(\w+)\s*(?::[^=]+)?=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*(?::[^=]+)?=>|\w+\s*=>)",
+    )
+    .expect("valid regex")
+});
+
+static KEYWORD_FN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:@\w+(?:\([^)]*\))?\s+)*(?:[a-z]+\s+)*(?:fun|func|def)\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?(\w+)",
+    )
+    .expect("valid regex")
+});
+
+static METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:@\w+(?:\([^)]*\))?\s+)*((?:[\w<>\[\],.?*&:~]+\s+)*?)(~?\w+)\s*(?:<[^()]*>)?\s*\(")
+        .expect("valid regex")
+});
+
+static PY_DECL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\s*)(?:async\s+)?(def|class)\s+(\w+)").expect("valid regex")
+});
+
+/// A declaration recognized on a single (masked, trimmed) line
+struct Declaration {
+    kind: SymbolKind,
+    name: String,
+    parent: Option<String>,
+    /// Drop the declaration if it ends with `;` before any `{` (prototypes, calls)
+    requires_body: bool,
+}
+
+/// A symbol plus the line its declaration keyword is on
+struct Located {
+    symbol: Symbol,
+    decl_line: usize,
+}
+
+/// Extract the outline of a brace-language or Python source file
+pub(super) fn outline(text: &str, language: Language) -> Vec<Symbol> {
+    let lines: Vec<&str> = text.lines().collect();
+    let masked = mask(text, language);
+
+    let mut located = if language == Language::Python {
+        python_symbols(&masked)
+    } else {
+        brace_symbols(&masked, language)
+    };
+
+    for item in &mut located {
+        item.symbol.start_line = attached_start(&lines, item.decl_line);
+    }
+    assign_parents(&mut located);
+
+    located.into_iter().map(|item| item.symbol).collect()
+}
+
+fn brace_symbols(masked: &[String], language: Language) -> Vec<Located> {
+    let mut located: Vec<Located> = Vec::new();
+
+    for (i, line) in masked.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let in_container = located.iter().any(|item| {
+            item.symbol.kind.is_container() && item.decl_line < i && item.symbol.end_line >= i
+        });
+        let Some(decl) = match_declaration(trimmed, language, in_container) else {
+            continue;
+        };
+        let Some((end_line, has_body)) = block_end(masked, i) else {
+            continue;
+        };
+        if decl.requires_body && !has_body {
+            continue;
+        }
+
+        located.push(Located {
+            symbol: Symbol {
+                kind: decl.kind,
+                name: decl.name,
+                parent: decl.parent,
+                start_line: i,
+                end_line,
+            },
+            decl_line: i,
+        });
+    }
+
+    located
+}
+
+fn match_declaration(line: &str, language: Language, in_container: bool) -> Option<Declaration> {
+    let declaration = |kind, name: &str, requires_body| Declaration {
+        kind,
+        name: name.to_string(),
+        parent: None,
+        requires_body,
+    };
+
+    if language == Language::Rust {
+        if let Some(caps) = RUST_IMPL.captures(line) {
+            let path = &caps[1];
+            let name = path.rsplit("::").next().unwrap_or(path);
+            return Some(declaration(SymbolKind::Impl, name, true));
+        }
+        if let Some(caps) = RUST_FN.captures(line) {
+            return Some(declaration(SymbolKind::Function, &caps[1], false));
+        }
+        if let Some(caps) = RUST_ITEM.captures(line) {
+            let kind = match &caps[1] {
+                "enum" => SymbolKind::Enum,
+                "trait" => SymbolKind::Trait,
+                "mod" => SymbolKind::Module,
+                _ => SymbolKind::Struct,
+            };
+            return Some(declaration(kind, &caps[2], false));
+        }
+        return None;
+    }
+
+    if let Some(caps) = TYPE_DECL.captures(line) {
+        let kind = match &caps[1] {
+            "interface" => SymbolKind::Interface,
+            "struct" => SymbolKind::Struct,
+            "enum" => SymbolKind::Enum,
+            "trait" | "protocol" => SymbolKind::Trait,
+            "namespace" => SymbolKind::Module,
+            _ => SymbolKind::Class,
+        };
+        return Some(declaration(kind, &caps[2], true));
+    }
+    if let Some(caps) = GO_FUNC.captures(line) {
+        return Some(Declaration {
+            parent: caps.get(1).map(|m| m.as_str().to_string()),
+            ..declaration(SymbolKind::Function, &caps[2], true)
+        });
+    }
+    for pattern in [&*JS_FUNCTION, &*JS_ARROW, &*KEYWORD_FN] {
+        if let Some(caps) = pattern.captures(line) {
+            return Some(declaration(SymbolKind::Function, &caps[1], true));
+        }
+    }
+
+    // Typed functions and methods: `int main(`, `public void run(`, or a
+    // bare `name(` inside a class body
+    let caps = METHOD.captures(line)?;
+    let prefix = caps.get(1).map_or("", |m| m.as_str()).trim();
+    let name = &caps[2];
+    let first_word = prefix.split_whitespace().next().unwrap_or(name);
+    if NOT_A_NAME.contains(&name) || NOT_A_NAME.contains(&first_word) {
+        return None;
+    }
+    if prefix.is_empty() && !in_container {
+        return None;
+    }
+    Some(declaration(SymbolKind::Function, name, true))
+}
+
+/// Find the last line of a declaration starting at `start`
+///
+/// Returns the line of the matching close brace and `true`, or the line of a
+/// terminating `;` and `false` for bodiless declarations. `None` if neither
+/// shows up within `MAX_SIGNATURE_LINES`.
+fn block_end(masked: &[String], start: usize) -> Option<(usize, bool)> {
+    let mut depth = 0usize;
+    let mut nesting = 0i32;
+    let mut opened = false;
+
+    for (i, line) in masked.iter().enumerate().skip(start) {
+        for c in line.chars() {
+            match c {
+                '(' | '[' => nesting += 1,
+                ')' | ']' => nesting -= 1,
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' if opened => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return Some((i, true));
+                    }
+                }
+                ';' if !opened && nesting <= 0 => return Some((i, false)),
+                _ => {}
+            }
+        }
+        if !opened && i - start >= MAX_SIGNATURE_LINES {
+            return None;
+        }
+    }
+
+    // Unterminated block: runs to end of file
+    opened.then(|| (masked.len().saturating_sub(1), true))
+}
+
+fn python_symbols(masked: &[String]) -> Vec<Located> {
+    let mut located = Vec::new();
+
+    for (i, line) in masked.iter().enumerate() {
+        let Some(caps) = PY_DECL.captures(line) else {
+            continue;
+        };
+        let indent = caps[1].len();
+        let kind = if &caps[2] == "class" {
+            SymbolKind::Class
+        } else {
+            SymbolKind::Function
+        };
+
+        // The signature may span lines; it ends at the first `:` outside brackets
+        let mut nesting = 0i32;
+        let mut signature_end = i;
+        for (j, sig_line) in masked.iter().enumerate().skip(i).take(MAX_SIGNATURE_LINES) {
+            let mut closed = false;
+            for c in sig_line.chars() {
+                match c {
+                    '(' | '[' | '{' => nesting += 1,
+                    ')' | ']' | '}' => nesting -= 1,
+                    ':' if nesting <= 0 => closed = true,
+                    _ => {}
+                }
+            }
+            signature_end = j;
+            if closed {
+                break;
+            }
+        }
+
+        // The body is every following line indented deeper than the declaration
+        let mut end_line = signature_end;
+        for (j, body_line) in masked.iter().enumerate().skip(signature_end + 1) {
+            if body_line.trim().is_empty() {
+                continue;
+            }
+            if leading_whitespace(body_line) <= indent {
+                break;
+            }
+            end_line = j;
+        }
+
+        located.push(Located {
+            symbol: Symbol {
+                kind,
+                name: caps[3].to_string(),
+                parent: None,
+                start_line: i,
+                end_line,
+            },
+            decl_line: i,
+        });
+    }
+
+    located
+}
+
+fn leading_whitespace(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Extend a span upward over doc comments, attributes and decorators
+fn attached_start(lines: &[&str], decl_line: usize) -> usize {
+    let mut start = decl_line;
+    while start > 0 {
+        let prev = lines[start - 1].trim();
+        let attached = prev.starts_with("///")
+            || prev.starts_with("#[")
+            || prev.starts_with('@')
+            || prev.starts_with("/**")
+            || prev.starts_with("* ")
+            || prev == "*"
+            || prev.starts_with("*/");
+        if !attached {
+            break;
+        }
+        start -= 1;
+    }
+    start
+}
+
+/// Set each symbol's parent to its innermost enclosing container
+fn assign_parents(located: &mut [Located]) {
+    let spans: Vec<(SymbolKind, String, usize, usize)> = located
+        .iter()
+        .map(|item| {
+            (
+                item.symbol.kind,
+                item.symbol.name.clone(),
+                item.decl_line,
+                item.symbol.end_line,
+            )
+        })
+        .collect();
+
+    for item in located.iter_mut() {
+        if item.symbol.parent.is_some() {
+            continue;
+        }
+        item.symbol.parent = spans
+            .iter()
+            .filter(|(kind, _, decl, end)| {
+                kind.is_container() && *decl < item.decl_line && *end >= item.symbol.end_line
+            })
+            .max_by_key(|(_, _, decl, _)| *decl)
+            .map(|(_, name, _, _)| name.clone());
+    }
+}
+
+/// Lexer state for `mask`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MaskState {
+    Code,
+    LineComment,
+    BlockComment(u32),
+    Str { quote: char, triple: bool },
+    RawStr { hashes: usize },
+}
+
+/// Blank out comments and literal contents, keeping line structure
+///
+/// String delimiters are kept so the line shape stays recognizable; every
+/// masked character becomes a space.
+fn mask(text: &str, language: Language) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut state = MaskState::Code;
+    let python = language == Language::Python;
+    let rust = language == Language::Rust;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c == '\n' {
+            out.push('\n');
+            match state {
+                MaskState::LineComment => state = MaskState::Code,
+                // Only Python's single-quoted strings end at a newline
+                MaskState::Str { triple: false, .. } if python => state = MaskState::Code,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+
+        match state {
+            MaskState::Code => match c {
+                '#' if python => {
+                    state = MaskState::LineComment;
+                    out.push(' ');
+                }
+                '/' if !python && next == Some('/') => {
+                    state = MaskState::LineComment;
+                    out.push_str("  ");
+                    i += 1;
+                }
+                '/' if !python && next == Some('*') => {
+                    state = MaskState::BlockComment(1);
+                    out.push_str("  ");
+                    i += 1;
+                }
+                'r' if rust
+                    && matches!(next, Some('"' | '#'))
+                    && !chars[..i].last().is_some_and(|p| p.is_alphanumeric() || *p == '_') =>
+                {
+                    let hashes = chars[i + 1..].iter().take_while(|&&h| h == '#').count();
+                    if chars.get(i + 1 + hashes) == Some(&'"') {
+                        state = MaskState::RawStr { hashes };
+                        out.push('r');
+                        out.extend(std::iter::repeat_n(' ', hashes));
+                        out.push('"');
+                        i += hashes + 1;
+                    } else {
+                        out.push(c);
+                    }
+                }
+                '\'' if rust => {
+                    // Char literal ('a', '\n', '\u{1F600}') vs lifetime ('a)
+                    let len = if next == Some('\\') {
+                        // Skip the escaped character itself, which may be a quote
+                        chars
+                            .get(i + 3..)
+                            .unwrap_or_default()
+                            .iter()
+                            .take(10)
+                            .position(|&q| q == '\'')
+                            .map(|p| p + 4)
+                    } else if chars.get(i + 2) == Some(&'\'') {
+                        Some(3)
+                    } else {
+                        None
+                    };
+                    match len {
+                        Some(len) => {
+                            out.push('\'');
+                            out.extend(std::iter::repeat_n(' ', len - 2));
+                            out.push('\'');
+                            i += len - 1;
+                        }
+                        None => out.push(c),
+                    }
+                }
+                '"' | '\'' | '`' if c != '`' || language == Language::CLike => {
+                    let triple = python && next == Some(c) && chars.get(i + 2) == Some(&c);
+                    state = MaskState::Str { quote: c, triple };
+                    if triple {
+                        out.extend([c, c, c]);
+                        i += 2;
+                    } else {
+                        out.push(c);
+                    }
+                }
+                _ => out.push(c),
+            },
+            MaskState::LineComment => out.push(' '),
+            MaskState::BlockComment(depth) => {
+                if c == '*' && next == Some('/') {
+                    state = if depth > 1 {
+                        MaskState::BlockComment(depth - 1)
+                    } else {
+                        MaskState::Code
+                    };
+                    out.push_str("  ");
+                    i += 1;
+                } else if rust && c == '/' && next == Some('*') {
+                    state = MaskState::BlockComment(depth + 1);
+                    out.push_str("  ");
+                    i += 1;
+                } else {
+                    out.push(' ');
+                }
+            }
+            MaskState::Str { quote, triple } => {
+                if c == '\\' {
+                    out.push(' ');
+                    if next.is_some_and(|n| n != '\n') {
+                        out.push(' ');
+                        i += 1;
+                    }
+                } else if c == quote
+                    && (!triple || (next == Some(quote) && chars.get(i + 2) == Some(&quote)))
+                {
+                    state = MaskState::Code;
+                    if triple {
+                        out.extend([c, c, c]);
+                        i += 2;
+                    } else {
+                        out.push(c);
+                    }
+                } else {
+                    out.push(' ');
+                }
+            }
+            MaskState::RawStr { hashes } => {
+                let closes = c == '"'
+                    && chars[i + 1..].iter().take(hashes).filter(|&&h| h == '#').count() == hashes;
+                if closes {
+                    state = MaskState::Code;
+                    out.push('"');
+                    out.extend(std::iter::repeat_n(' ', hashes));
+                    i += hashes;
+                } else {
+                    out.push(' ');
+                }
+            }
+        }
+        i += 1;
+    }
+
+    out.split('\n')
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect()
+}
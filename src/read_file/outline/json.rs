@@ -0,0 +1,193 @@
+//! Key-path lookup in JSON documents
+//!
+//! A small span-tracking scanner walks the document without building a
+//! value tree, so the selected member is returned exactly as written, from
+//! its key to the end of its value.
+
+use super::{Symbol, SymbolKind, line_of_offset};
+
+/// Locate the member at a dotted key path (`a.b.0.c` or `a.b[0].c`)
+pub(super) fn locate(text: &str, selector: &str) -> Option<Symbol> {
+    let path = split_path(selector);
+    if path.is_empty() {
+        return None;
+    }
+
+    let mut scanner = Scanner {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    let (start, end) = scanner.find(&path, None)?;
+
+    let parent = (path.len() > 1).then(|| path[..path.len() - 1].join("."));
+    Some(Symbol {
+        kind: SymbolKind::Key,
+        name: path.join("."),
+        parent,
+        start_line: line_of_offset(text, start),
+        end_line: line_of_offset(text, end.saturating_sub(1)),
+    })
+}
+
+/// Split `a.b[0].c` into `["a", "b", "0", "c"]`
+fn split_path(selector: &str) -> Vec<String> {
+    selector
+        .replace('[', ".")
+        .replace(']', "")
+        .split('.')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn skip_ws(&mut self) {
+        while self.bytes.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Descend into the value at `pos` following `path`
+    ///
+    /// `member_start` is where the enclosing member's key begins, so the
+    /// returned span covers `"key": value` rather than just the value.
+    fn find(&mut self, path: &[String], member_start: Option<usize>) -> Option<(usize, usize)> {
+        self.skip_ws();
+        let value_start = self.pos;
+
+        let Some((segment, rest)) = path.split_first() else {
+            let (_, end) = self.skip_value()?;
+            return Some((member_start.unwrap_or(value_start), end));
+        };
+
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_ws();
+                    if self.peek()? == b'}' {
+                        return None;
+                    }
+                    let key_start = self.pos;
+                    let key = self.string()?;
+                    self.skip_ws();
+                    if self.peek()? != b':' {
+                        return None;
+                    }
+                    self.pos += 1;
+
+                    if key == *segment {
+                        return self.find(rest, Some(key_start));
+                    }
+                    self.skip_value()?;
+                    self.skip_ws();
+                    match self.peek()? {
+                        b',' => self.pos += 1,
+                        _ => return None,
+                    }
+                }
+            }
+            b'[' => {
+                let index: usize = segment.parse().ok()?;
+                self.pos += 1;
+                for i in 0.. {
+                    self.skip_ws();
+                    if self.peek()? == b']' {
+                        return None;
+                    }
+                    if i == index {
+                        return self.find(rest, None);
+                    }
+                    self.skip_value()?;
+                    self.skip_ws();
+                    match self.peek()? {
+                        b',' => self.pos += 1,
+                        _ => return None,
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse a string token and return its decoded contents
+    fn string(&mut self) -> Option<String> {
+        let (start, end) = self.skip_string()?;
+        serde_json::from_slice(&self.bytes[start..end]).ok()
+    }
+
+    fn skip_string(&mut self) -> Option<(usize, usize)> {
+        let start = self.pos;
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some((start, self.pos));
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Skip over any value, returning its byte span
+    fn skip_value(&mut self) -> Option<(usize, usize)> {
+        self.skip_ws();
+        let start = self.pos;
+        match self.peek()? {
+            b'"' => {
+                self.skip_string()?;
+            }
+            open @ (b'{' | b'[') => {
+                let close = if open == b'{' { b'}' } else { b']' };
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.skip_string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                if self.peek()? != close {
+                                    return None;
+                                }
+                                self.pos += 1;
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                // Numbers, true, false, null
+                while self
+                    .peek()
+                    .is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+            }
+        }
+        (self.pos > start).then_some((start, self.pos))
+    }
+}
@@ -0,0 +1,89 @@
+//! Heading outline for markdown files
+//!
+//! A heading's span runs until the next heading of the same or a higher
+//! level, so selecting a section includes its subsections. Headings inside
+//! fenced code blocks are ignored.
+
+use super::{Symbol, SymbolKind};
+use regex::Regex;
+use std::sync::LazyLock;
+
+static ATX_HEADING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^ {0,3}(#{1,6})(?:\s+(.*?))?(?:\s+#+)?\s*$").expect("valid regex")
+});
+
+static SETEXT_UNDERLINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^ {0,3}(=+|-+)\s*$").expect("valid regex"));
+
+/// Extract headings with their section spans
+pub(super) fn outline(text: &str) -> Vec<Symbol> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut headings: Vec<(usize, usize, String)> = Vec::new(); // (line, level, text)
+    let mut fence: Option<&str> = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+
+        // Fenced code blocks close with the same fence marker
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        if let Some(caps) = ATX_HEADING.captures(line) {
+            let text = caps.get(2).map_or("", |m| m.as_str()).trim().to_string();
+            headings.push((i, caps[1].len(), text));
+            continue;
+        }
+
+        // Setext: a paragraph line underlined with === (level 1) or --- (level 2)
+        let underlined = lines
+            .get(i + 1)
+            .and_then(|next| SETEXT_UNDERLINE.captures(next));
+        if let Some(caps) = underlined
+            && !trimmed.is_empty()
+            && lines.get(i.wrapping_sub(1)).is_none_or(|prev| prev.trim().is_empty())
+        {
+            let level = if caps[1].starts_with('=') { 1 } else { 2 };
+            headings.push((i, level, trimmed.trim_end().to_string()));
+        }
+    }
+
+    let last_line = lines.len().saturating_sub(1);
+    headings
+        .iter()
+        .enumerate()
+        .map(|(idx, (line, level, text))| {
+            let next_section = headings[idx + 1..]
+                .iter()
+                .find(|(_, other_level, _)| other_level <= level)
+                .map_or(last_line + 1, |(other_line, _, _)| *other_line);
+
+            // Trailing blank lines belong to no section
+            let mut end_line = next_section.saturating_sub(1).max(*line);
+            while end_line > *line && lines[end_line].trim().is_empty() {
+                end_line -= 1;
+            }
+
+            let parent = headings[..idx]
+                .iter()
+                .rev()
+                .find(|(_, other_level, _)| other_level < level)
+                .map(|(_, _, parent)| parent.clone());
+
+            Symbol {
+                kind: SymbolKind::Heading,
+                name: text.clone(),
+                parent,
+                start_line: *line,
+                end_line,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,220 @@
+//! Symbol-aware reads for `fs_read_file`
+//!
+//! Resolves a selector such as `execute`, `EditBlockTool::execute`,
+//! `struct Foo`, a markdown heading or a JSON/TOML key path to the exact
+//! line span that defines it. Code outlines come from a lightweight
+//! bracket/indentation-aware scanner rather than a full parser, so they work
+//! for any brace language or Python without per-language grammars.
+
+mod code;
+mod json;
+mod markdown;
+mod toml;
+
+use std::path::Path;
+
+/// Kind of a symbol in a file outline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+    Module,
+    Class,
+    Interface,
+    Heading,
+    Key,
+}
+
+impl SymbolKind {
+    /// Keyword used when displaying the symbol
+    #[must_use]
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Function => "fn",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Trait => "trait",
+            Self::Impl => "impl",
+            Self::Module => "mod",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Heading => "heading",
+            Self::Key => "key",
+        }
+    }
+
+    /// Parse a selector keyword prefix (`fn execute`, `class Foo`, ...)
+    fn from_selector_keyword(word: &str) -> Option<Self> {
+        Some(match word {
+            "fn" | "function" | "func" | "def" | "method" => Self::Function,
+            "struct" | "union" => Self::Struct,
+            "enum" => Self::Enum,
+            "trait" | "protocol" => Self::Trait,
+            "impl" => Self::Impl,
+            "mod" | "module" | "namespace" => Self::Module,
+            "class" | "object" => Self::Class,
+            "interface" => Self::Interface,
+            _ => return None,
+        })
+    }
+
+    /// Whether symbols of this kind can contain other symbols
+    fn is_container(self) -> bool {
+        matches!(
+            self,
+            Self::Struct
+                | Self::Enum
+                | Self::Trait
+                | Self::Impl
+                | Self::Module
+                | Self::Class
+                | Self::Interface
+        )
+    }
+}
+
+/// A located symbol with its line span (0-based, inclusive)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+
+    /// Symbol name (heading text or key path for headings and keys)
+    pub name: String,
+
+    /// Name of the enclosing container (impl/class/module/heading), if any
+    pub parent: Option<String>,
+
+    /// First line of the span, including attached doc comments and attributes
+    pub start_line: usize,
+
+    /// Last line of the span
+    pub end_line: usize,
+}
+
+impl Symbol {
+    /// Human-readable description, e.g. `fn EditBlockTool::execute`
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        match (&self.parent, self.kind) {
+            (Some(parent), kind) if kind != SymbolKind::Heading => {
+                format!("{} {parent}::{}", self.kind.keyword(), self.name)
+            }
+            _ => format!("{} {}", self.kind.keyword(), self.name),
+        }
+    }
+}
+
+/// File formats the outline extractor understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// Rust (lifetimes and raw strings aware)
+    Rust,
+    /// Other brace languages: C, C++, C#, Go, Java, JavaScript, TypeScript, Kotlin, Swift, ...
+    CLike,
+    Python,
+    Markdown,
+    Json,
+    Toml,
+}
+
+impl Language {
+    /// Pick a language from a file extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        Some(match ext.as_str() {
+            "rs" => Self::Rust,
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "cs" | "go" | "java" | "js"
+            | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" | "kt" | "kts" | "swift"
+            | "scala" | "php" | "dart" | "groovy" => Self::CLike,
+            "py" | "pyi" => Self::Python,
+            "md" | "markdown" | "mdx" => Self::Markdown,
+            "json" | "jsonc" => Self::Json,
+            "toml" => Self::Toml,
+            _ => return None,
+        })
+    }
+}
+
+/// List the symbols of a code or markdown file, in source order
+///
+/// JSON and TOML have no outline; they are addressed by key path.
+#[must_use]
+pub fn outline(text: &str, language: Language) -> Vec<Symbol> {
+    match language {
+        Language::Rust | Language::CLike | Language::Python => code::outline(text, language),
+        Language::Markdown => markdown::outline(text),
+        Language::Json | Language::Toml => Vec::new(),
+    }
+}
+
+/// Find all symbols matching a selector, in source order
+///
+/// Code selectors are a name, optionally qualified by its container
+/// (`Type::method` or `Type.method`) and prefixed by a kind keyword
+/// (`fn`, `struct`, `impl`, `class`, ...). Markdown selectors are heading
+/// text, with or without leading `#`s. JSON and TOML selectors are dotted key
+/// paths (`dependencies.serde`, `items.0.name`, `items[0].name`).
+#[must_use]
+pub fn find_symbols(text: &str, language: Language, selector: &str) -> Vec<Symbol> {
+    let selector = selector.trim();
+    match language {
+        Language::Json => json::locate(text, selector).into_iter().collect(),
+        Language::Toml => toml::locate(text, selector).into_iter().collect(),
+        Language::Markdown => {
+            let wanted = selector.trim_start_matches('#').trim();
+            markdown::outline(text)
+                .into_iter()
+                .filter(|s| s.name.eq_ignore_ascii_case(wanted))
+                .collect()
+        }
+        Language::Rust | Language::CLike | Language::Python => {
+            let (kind, path) = parse_code_selector(selector);
+            let mut parts: Vec<&str> = path
+                .split("::")
+                .flat_map(|part| part.split('.'))
+                .map(str::trim)
+                .collect();
+            let name = parts.pop().unwrap_or_default();
+            let parent = parts.pop();
+
+            code::outline(text, language)
+                .into_iter()
+                .filter(|s| s.name == name)
+                .filter(|s| kind.is_none_or(|k| s.kind == k))
+                .filter(|s| parent.is_none_or(|p| s.parent.as_deref() == Some(p)))
+                .collect()
+        }
+    }
+}
+
+/// Split an optional kind keyword off a code selector
+///
+/// `impl Trait for Type` selects the impl of `Type`.
+fn parse_code_selector(selector: &str) -> (Option<SymbolKind>, &str) {
+    let Some((word, rest)) = selector.split_once(char::is_whitespace) else {
+        return (None, selector);
+    };
+    let Some(kind) = SymbolKind::from_selector_keyword(word) else {
+        return (None, selector);
+    };
+
+    let rest = rest.trim();
+    let rest = if kind == SymbolKind::Impl {
+        rest.rsplit_once(" for ").map_or(rest, |(_, ty)| ty.trim())
+    } else {
+        rest
+    };
+    (Some(kind), rest)
+}
+
+/// Convert a byte offset to a 0-based line index
+fn line_of_offset(text: &str, offset: usize) -> usize {
+    text.as_bytes()[..offset.min(text.len())]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count()
+}
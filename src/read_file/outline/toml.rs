@@ -0,0 +1,221 @@
+//! Key-path lookup in TOML documents
+//!
+//! Tables (`[a.b]`), arrays of tables (`[[bin]]`, addressed as `bin.0`) and
+//! keys (including dotted keys and multi-line values) are resolved to the
+//! lines that define them. Keys inside inline tables are not addressable;
+//! select the inline table's own key instead.
+
+use super::{Symbol, SymbolKind};
+use std::collections::HashMap;
+
+/// A table or key definition and its line span
+struct Entry {
+    path: Vec<String>,
+    start: usize,
+    end: usize,
+}
+
+/// Locate the table or key at a dotted key path
+pub(super) fn locate(text: &str, selector: &str) -> Option<Symbol> {
+    let wanted = split_key(selector);
+    if wanted.is_empty() {
+        return None;
+    }
+
+    let lines: Vec<&str> = text.lines().collect();
+    let entries = entries(&lines);
+
+    // `bin` selects the first `[[bin]]` when no index is given
+    let mut first_element = wanted.clone();
+    first_element.push("0".to_string());
+
+    let entry = entries
+        .iter()
+        .find(|entry| entry.path == wanted)
+        .or_else(|| entries.iter().find(|entry| entry.path == first_element))?;
+
+    let parent = (wanted.len() > 1).then(|| wanted[..wanted.len() - 1].join("."));
+    Some(Symbol {
+        kind: SymbolKind::Key,
+        name: wanted.join("."),
+        parent,
+        start_line: entry.start,
+        end_line: entry.end,
+    })
+}
+
+/// Collect every table and key with its span, in document order
+fn entries(lines: &[&str]) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut table_indices = Vec::new();
+    let mut array_counts: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut table: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+
+        if let Some(header) = parse_header(trimmed) {
+            let (path, is_array) = header;
+            table = path.clone();
+            if is_array {
+                let count = array_counts.entry(path).or_insert(0);
+                table.push(count.to_string());
+                *count += 1;
+            }
+            table_indices.push(entries.len());
+            entries.push(Entry {
+                path: table.clone(),
+                start: i,
+                end: i,
+            });
+            i += 1;
+            continue;
+        }
+
+        if let Some((key, value)) = split_key_value(trimmed) {
+            let end = value_end(lines, i, value);
+            let mut path = table.clone();
+            path.extend(split_key(key));
+            entries.push(Entry {
+                path,
+                start: i,
+                end,
+            });
+            i = end + 1;
+            continue;
+        }
+
+        i += 1;
+    }
+
+    // A table runs until the next header, minus trailing blank lines
+    for (n, &index) in table_indices.iter().enumerate() {
+        let next_header = table_indices
+            .get(n + 1)
+            .map_or(lines.len(), |&next| entries[next].start);
+        let mut end = next_header.saturating_sub(1).max(entries[index].start);
+        while end > entries[index].start && lines[end].trim().is_empty() {
+            end -= 1;
+        }
+        entries[index].end = end;
+    }
+
+    entries
+}
+
+/// Parse `[table]` or `[[array]]`, returning the key path and whether it is an array
+fn parse_header(trimmed: &str) -> Option<(Vec<String>, bool)> {
+    let is_array = trimmed.starts_with("[[");
+    let open = if is_array { 2 } else { 1 };
+    if !trimmed.starts_with('[') {
+        return None;
+    }
+
+    let close = if is_array { "]]" } else { "]" };
+    let inner_end = find_unquoted(&trimmed[open..], close)? + open;
+    let after = trimmed[inner_end + close.len()..].trim_start();
+    if !(after.is_empty() || after.starts_with('#')) {
+        return None;
+    }
+
+    Some((split_key(&trimmed[open..inner_end]), is_array))
+}
+
+/// Split `key = value` at the first unquoted `=`
+fn split_key_value(trimmed: &str) -> Option<(&str, &str)> {
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    let eq = find_unquoted(trimmed, "=")?;
+    let key = trimmed[..eq].trim();
+    (!key.is_empty()).then(|| (key, &trimmed[eq + 1..]))
+}
+
+/// Find `needle` outside of quoted key segments
+fn find_unquoted(s: &str, needle: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if s[i..].starts_with(needle) => return Some(i),
+            None => {}
+        }
+    }
+    None
+}
+
+/// Split a dotted key into segments, unquoting quoted segments
+fn split_key(key: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+
+    for c in key.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '.' => segments.push(std::mem::take(&mut current)),
+            None if c.is_whitespace() => {}
+            None => current.push(c),
+        }
+    }
+    segments.push(current);
+
+    segments.retain(|segment| !segment.is_empty());
+    segments
+}
+
+/// Find the last line of a value starting on `start` (multi-line arrays,
+/// inline tables and strings)
+fn value_end(lines: &[&str], start: usize, value: &str) -> usize {
+    let mut depth = 0i32;
+    let mut string: Option<&str> = None;
+    let mut line_index = start;
+    let mut rest = value;
+
+    loop {
+        let mut i = 0;
+        while i < rest.len() {
+            let tail = &rest[i..];
+            if let Some(delim) = string {
+                // Basic strings ("..." and """...""") have backslash escapes
+                if delim.starts_with('"') && tail.starts_with('\\') {
+                    i += 1 + tail[1..].chars().next().map_or(0, char::len_utf8);
+                    continue;
+                }
+                if tail.starts_with(delim) {
+                    string = None;
+                    i += delim.len();
+                    continue;
+                }
+            } else if tail.starts_with("\"\"\"") || tail.starts_with("'''") {
+                string = Some(&tail[..3]);
+                i += 3;
+                continue;
+            } else if tail.starts_with('"') || tail.starts_with('\'') {
+                string = Some(&tail[..1]);
+            } else if tail.starts_with('#') {
+                break;
+            } else if tail.starts_with('[') || tail.starts_with('{') {
+                depth += 1;
+            } else if tail.starts_with(']') || tail.starts_with('}') {
+                depth -= 1;
+            }
+            i += tail.chars().next().map_or(1, char::len_utf8);
+        }
+
+        // Single-line strings end at the line end
+        if string.is_some_and(|delim| delim.len() == 1) {
+            string = None;
+        }
+        if (depth <= 0 && string.is_none()) || line_index + 1 >= lines.len() {
+            return line_index;
+        }
+        line_index += 1;
+        rest = lines[line_index];
+    }
+}
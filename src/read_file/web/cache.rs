@@ -0,0 +1,409 @@
+//! On-disk HTTP cache for URL reads
+//!
+//! Raw response bodies are stored under the kodegen state directory
+//! (`state/http-cache/`), keyed by the SHA-256 of the requested URL, next to
+//! a JSON record of their validators. Entries are served without touching
+//! the network while fresh (`Cache-Control: max-age`, or the default TTL),
+//! revalidated with `If-None-Match`/`If-Modified-Since` once stale, and
+//! served stale when the network or server fails.
+//!
+//! The cache is pruned after every store, the way the undo journal is:
+//! entries stored longer ago than the maximum age go first, then the least
+//! recently stored until the cache fits its size budget.
+
+use super::policy::UrlPolicy;
+use super::{Conditions, FetchedBody, Outcome, request, status_error};
+use crate::atomic_write::write_atomic;
+use chrono::{DateTime, Utc};
+use kodegen_config::KodegenConfig;
+use kodegen_mcp_schema::McpError;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+
+/// How long entries without `Cache-Control: max-age` stay fresh
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Default size budget for the whole cache (256 MiB)
+pub const DEFAULT_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Default age after which entries are pruned, fresh or not (7 days)
+pub const DEFAULT_CACHE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Bodies without a record (a store interrupted by a crash) are removed
+/// after this long
+const STALE_BODY: Duration = Duration::from_secs(60 * 60);
+
+/// How a read uses the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Serve fresh entries, revalidate stale ones, fall back to stale copies
+    #[default]
+    Use,
+    /// Always download, then update the cache
+    Refresh,
+    /// Never touch the network; fail if the URL is not cached
+    Only,
+}
+
+impl CacheMode {
+    /// Parse the `cache` argument of `fs_read_file`
+    ///
+    /// # Errors
+    /// Returns `McpError::InvalidArguments` for anything but "use", "refresh"
+    /// or "only".
+    pub fn parse(value: &str) -> Result<Self, McpError> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "use" => Ok(Self::Use),
+            "refresh" => Ok(Self::Refresh),
+            "only" => Ok(Self::Only),
+            other => Err(McpError::InvalidArguments(format!(
+                "Unknown cache mode '{other}': expected \"use\", \"refresh\" or \"only\""
+            ))),
+        }
+    }
+}
+
+/// Where a returned body came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// Downloaded (and stored, unless the server forbade it)
+    Miss,
+    /// Fresh entry served without a request
+    Hit,
+    /// Stale entry confirmed unchanged by a 304
+    Revalidated,
+    /// Stale entry served because the fetch failed
+    Stale,
+}
+
+impl CacheStatus {
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Miss => "miss",
+            Self::Hit => "hit",
+            Self::Revalidated => "revalidated",
+            Self::Stale => "stale",
+        }
+    }
+}
+
+/// Metadata stored next to each cached body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Record {
+    url: String,
+    final_url: String,
+    content_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cache_control: Option<String>,
+    stored_at: DateTime<Utc>,
+    size: u64,
+}
+
+/// A cached response
+struct Entry {
+    record: Record,
+    bytes: Vec<u8>,
+}
+
+impl Entry {
+    fn body(self) -> FetchedBody {
+        FetchedBody {
+            url: self.record.final_url,
+            content_type: self.record.content_type,
+            bytes: self.bytes,
+            etag: self.record.etag,
+            last_modified: self.record.last_modified,
+            cache_control: self.record.cache_control,
+        }
+    }
+
+    fn is_fresh(&self, default_ttl: Duration) -> bool {
+        let lifetime = freshness(self.record.cache_control.as_deref(), default_ttl);
+        let age = Utc::now()
+            .signed_duration_since(self.record.stored_at)
+            .to_std()
+            .unwrap_or_default();
+        age < lifetime
+    }
+}
+
+/// Directory of cached responses
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+    default_ttl: Duration,
+    max_bytes: u64,
+    max_age: Duration,
+}
+
+impl HttpCache {
+    #[must_use]
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        default_ttl: Duration,
+        max_bytes: u64,
+        max_age: Duration,
+    ) -> Self {
+        Self {
+            dir: dir.into(),
+            default_ttl,
+            max_bytes,
+            max_age,
+        }
+    }
+
+    /// The cache under the kodegen state directory, with the TTL from
+    /// `KODEGEN_URL_CACHE_TTL_SECS`, sized by `KODEGEN_URL_CACHE_MAX_BYTES`
+    /// and `KODEGEN_URL_CACHE_MAX_AGE_HOURS`
+    ///
+    /// Returns `None` when the state directory cannot be determined.
+    #[must_use]
+    pub fn open_default() -> Option<Self> {
+        let dir = KodegenConfig::state_dir().ok()?.join("http-cache");
+        let number = |var: &str| -> Option<u64> { std::env::var(var).ok()?.trim().parse().ok() };
+        Some(Self::new(
+            dir,
+            number("KODEGEN_URL_CACHE_TTL_SECS").map_or(DEFAULT_CACHE_TTL, Duration::from_secs),
+            number("KODEGEN_URL_CACHE_MAX_BYTES").unwrap_or(DEFAULT_CACHE_MAX_BYTES),
+            number("KODEGEN_URL_CACHE_MAX_AGE_HOURS").map_or(DEFAULT_CACHE_MAX_AGE, |hours| {
+                Duration::from_secs(hours * 60 * 60)
+            }),
+        ))
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+        (
+            self.dir.join(format!("{key}.json")),
+            self.dir.join(format!("{key}.body")),
+        )
+    }
+
+    /// Load the entry for `url`, ignoring missing, unreadable or torn entries
+    async fn load(&self, url: &str) -> Option<Entry> {
+        let (record_path, body_path) = self.paths(url);
+        let record: Record = serde_json::from_slice(&fs::read(record_path).await.ok()?).ok()?;
+        let bytes = fs::read(body_path).await.ok()?;
+        (record.url == url && record.size == bytes.len() as u64).then_some(Entry { record, bytes })
+    }
+
+    /// Store a downloaded body unless the server marked it `no-store`, then
+    /// prune
+    ///
+    /// The body and its record are each replaced atomically; `load` checks
+    /// the recorded size, so a reader racing a store sees a complete entry
+    /// or none at all.
+    async fn store(&self, url: &str, body: &FetchedBody) -> Result<(), McpError> {
+        if has_directive(body.cache_control.as_deref(), "no-store") {
+            return Ok(());
+        }
+
+        let record = Record {
+            url: url.to_string(),
+            final_url: body.url.clone(),
+            content_type: body.content_type.clone(),
+            etag: body.etag.clone(),
+            last_modified: body.last_modified.clone(),
+            cache_control: body.cache_control.clone(),
+            stored_at: Utc::now(),
+            size: body.bytes.len() as u64,
+        };
+        let record = serde_json::to_vec_pretty(&record).map_err(std::io::Error::other)?;
+
+        fs::create_dir_all(&self.dir).await?;
+        let (record_path, body_path) = self.paths(url);
+        write_atomic(&body_path, body.bytes.clone()).await?;
+        write_atomic(&record_path, record).await?;
+        self.prune().await;
+        Ok(())
+    }
+
+    /// Refresh an entry's timestamp after a 304
+    async fn touch(&self, url: &str, entry: &Entry) -> Result<(), McpError> {
+        let mut record = entry.record.clone();
+        record.stored_at = Utc::now();
+        let record = serde_json::to_vec_pretty(&record).map_err(std::io::Error::other)?;
+        write_atomic(&self.paths(url).0, record).await
+    }
+
+    /// Drop entries past the age limit, then the least recently stored ones
+    /// until the cache fits in its size budget
+    pub async fn prune(&self) {
+        let cache = self.clone();
+        let result = tokio::task::spawn_blocking(move || cache.prune_blocking()).await;
+        if let Ok(Err(e)) = result {
+            log::warn!("Failed to prune URL cache: {e}");
+        }
+    }
+
+    fn prune_blocking(&self) -> std::io::Result<()> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        // Every entry as (stored_at, record path, body path, bytes on disk)
+        let mut records = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("json") => {
+                    let body_path = path.with_extension("body");
+                    let record = std::fs::read(&path)
+                        .ok()
+                        .and_then(|bytes| serde_json::from_slice::<Record>(&bytes).ok());
+                    let Some(record) = record else {
+                        // Unreadable records are useless to `load`
+                        let _ = std::fs::remove_file(&body_path);
+                        std::fs::remove_file(&path)?;
+                        continue;
+                    };
+                    let size = entry.metadata().map_or(0, |m| m.len()) + record.size;
+                    records.push((record.stored_at, path, body_path, size));
+                }
+                Some("body") if !path.with_extension("json").exists() => {
+                    let stale = entry
+                        .metadata()
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_BODY);
+                    if stale {
+                        std::fs::remove_file(&path)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        records.sort_by_key(|(stored_at, ..)| *stored_at);
+
+        let now = Utc::now();
+        let mut total: u64 = records.iter().map(|(_, _, _, size)| size).sum();
+        for (stored_at, record_path, body_path, size) in records {
+            let age = now
+                .signed_duration_since(stored_at)
+                .to_std()
+                .unwrap_or_default();
+            if age <= self.max_age && total <= self.max_bytes {
+                continue;
+            }
+            total = total.saturating_sub(size);
+            // The record goes first so a concurrent `load` never sees a
+            // record without its body
+            std::fs::remove_file(&record_path)?;
+            let _ = std::fs::remove_file(&body_path);
+        }
+        Ok(())
+    }
+}
+
+/// Fetch `url` through `cache` according to `mode`
+///
+/// The policy's host rules apply to cached URLs too, so a host denied after
+/// it was cached is not served from the cache.
+///
+/// # Errors
+/// Returns `McpError::ResourceNotFound` when `mode` is [`CacheMode::Only`]
+/// and the URL is not cached, and the errors of [`super::fetch`] when a
+/// download fails with nothing cached to fall back on.
+pub async fn fetch_cached(
+    url: &str,
+    policy: &UrlPolicy,
+    cache: &HttpCache,
+    mode: CacheMode,
+) -> Result<(FetchedBody, CacheStatus), McpError> {
+    let parsed = Url::parse(url).map_err(|e| McpError::InvalidUrl(format!("{url}: {e}")))?;
+    policy.check_url(&parsed)?;
+
+    let cached = match mode {
+        CacheMode::Refresh => None,
+        CacheMode::Use | CacheMode::Only => cache.load(url).await,
+    };
+
+    if mode == CacheMode::Only {
+        return cached
+            .map(|entry| (entry.body(), CacheStatus::Hit))
+            .ok_or_else(|| {
+                McpError::ResourceNotFound(format!(
+                    "{url} is not in the URL cache ({}); read it once with cache: \"use\" first",
+                    cache.dir().display()
+                ))
+            });
+    }
+
+    let cached = match cached {
+        Some(entry) if entry.is_fresh(cache.default_ttl) => {
+            return Ok((entry.body(), CacheStatus::Hit));
+        }
+        other => other,
+    };
+
+    let conditions = cached
+        .as_ref()
+        .map_or_else(Conditions::default, |entry| Conditions {
+            etag: entry.record.etag.as_deref(),
+            last_modified: entry.record.last_modified.as_deref(),
+        });
+
+    match (request(url, policy, conditions).await, cached) {
+        (Ok(Outcome::Body(body)), _) => {
+            if let Err(e) = cache.store(url, &body).await {
+                log::warn!("Failed to cache {url}: {e}");
+            }
+            Ok((body, CacheStatus::Miss))
+        }
+        (Ok(Outcome::NotModified), Some(entry)) => {
+            if let Err(e) = cache.touch(url, &entry).await {
+                log::warn!("Failed to refresh cache entry for {url}: {e}");
+            }
+            Ok((entry.body(), CacheStatus::Revalidated))
+        }
+        // Client errors mean the resource is gone or forbidden: don't mask them
+        (Ok(Outcome::Status(status)), Some(entry)) if status.is_server_error() => {
+            Ok((entry.body(), CacheStatus::Stale))
+        }
+        (Ok(Outcome::Status(status)), _) => Err(status_error(status)),
+        (Ok(Outcome::NotModified), None) => Err(status_error(reqwest::StatusCode::NOT_MODIFIED)),
+        (Err(McpError::Network(_) | McpError::Other(_)), Some(entry)) => {
+            Ok((entry.body(), CacheStatus::Stale))
+        }
+        (Err(e), _) => Err(e),
+    }
+}
+
+/// How long a response stays fresh: `max-age` when given, zero for
+/// `no-cache`, otherwise `default_ttl`
+fn freshness(cache_control: Option<&str>, default_ttl: Duration) -> Duration {
+    if has_directive(cache_control, "no-cache") {
+        return Duration::ZERO;
+    }
+    cache_control
+        .into_iter()
+        .flat_map(|header| header.split(','))
+        .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+        .find_map(|seconds| seconds.trim_matches('"').parse().ok())
+        .map_or(default_ttl, Duration::from_secs)
+}
+
+fn has_directive(cache_control: Option<&str>, name: &str) -> bool {
+    cache_control.is_some_and(|header| {
+        header
+            .split(',')
+            .any(|directive| directive.trim().eq_ignore_ascii_case(name))
+    })
+}
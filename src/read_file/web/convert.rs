@@ -0,0 +1,249 @@
+//! Content-type driven conversion of URL responses
+//!
+//! Web pages become readable markdown, JSON is pretty-printed and other text
+//! is decoded with its declared charset. PDFs and binary payloads are refused
+//! with a description of what was fetched instead of being dumped as text.
+
+use super::FetchedBody;
+use crate::read_file::binary::{identify_format, looks_binary};
+use kodegen_mcp_schema::McpError;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Elements that never carry readable page content
+const SKIPPED_HTML_TAGS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "nav", "iframe", "svg", "canvas", "aside",
+    "footer",
+];
+
+/// How far into an HTML document to look for a `<meta charset>` declaration
+const META_CHARSET_WINDOW: usize = 1024;
+
+static META_CHARSET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([\w:.-]+)"#).expect("valid regex")
+});
+
+static EXCESS_BLANK_LINES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\n[ \t]*(?:\n[ \t]*){2,}").expect("valid regex"));
+
+/// How a response body was transformed into the returned text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    HtmlToMarkdown,
+    PrettyJson,
+}
+
+impl Conversion {
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::HtmlToMarkdown => "html-to-markdown",
+            Self::PrettyJson => "json-pretty",
+        }
+    }
+}
+
+/// A response body ready to be returned to the caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlContent {
+    /// Image bytes, returned base64-encoded like images read from disk
+    Image,
+    /// Decoded (and possibly converted) text
+    Text {
+        text: String,
+        conversion: Option<Conversion>,
+    },
+}
+
+/// Broad category of a response, from its content type or, failing that,
+/// from the body itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Html,
+    Json,
+    Text,
+    Image,
+    Pdf,
+    Binary,
+}
+
+/// Convert a fetched body according to its content type
+///
+/// # Errors
+/// Returns `McpError::InvalidArguments` for PDFs and binary payloads, with
+/// the content type, size and detected format in the message.
+pub fn convert(body: &FetchedBody) -> Result<UrlContent, McpError> {
+    let essence = essence(&body.content_type);
+
+    match classify(&essence, &body.bytes) {
+        Kind::Image => Ok(UrlContent::Image),
+        Kind::Pdf | Kind::Binary => Err(refusal(body, &essence)),
+        Kind::Html => {
+            let html = decode_text(&body.bytes, &body.content_type, true);
+            Ok(UrlContent::Text {
+                text: html_to_markdown(&html),
+                conversion: Some(Conversion::HtmlToMarkdown),
+            })
+        }
+        Kind::Json => {
+            let text = decode_text(&body.bytes, &body.content_type, false);
+            // Malformed JSON is still worth reading as-is
+            Ok(match pretty_json(&text) {
+                Some(pretty) => UrlContent::Text {
+                    text: pretty,
+                    conversion: Some(Conversion::PrettyJson),
+                },
+                None => UrlContent::Text {
+                    text,
+                    conversion: None,
+                },
+            })
+        }
+        Kind::Text => Ok(UrlContent::Text {
+            text: decode_text(&body.bytes, &body.content_type, false),
+            conversion: None,
+        }),
+    }
+}
+
+/// Convert an HTML document to markdown, dropping scripts, styles,
+/// navigation and other page chrome
+#[must_use]
+pub fn html_to_markdown(html: &str) -> String {
+    let converter = htmd::HtmlToMarkdown::builder()
+        .skip_tags(SKIPPED_HTML_TAGS.to_vec())
+        .build();
+
+    // The converter only fails on I/O errors from its in-memory reader
+    let markdown = converter.convert(html).unwrap_or_default();
+    EXCESS_BLANK_LINES
+        .replace_all(markdown.trim(), "\n\n")
+        .into_owned()
+}
+
+/// Pretty-print a JSON document, or `None` if it does not parse
+#[must_use]
+pub fn pretty_json(text: &str) -> Option<String> {
+    let value: serde_json::Value =
+        serde_json::from_str(text.trim_start_matches('\u{feff}')).ok()?;
+    serde_json::to_string_pretty(&value).ok()
+}
+
+/// Lowercased `type/subtype` without parameters
+fn essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+fn classify(essence: &str, bytes: &[u8]) -> Kind {
+    match essence {
+        "text/html" | "application/xhtml+xml" => Kind::Html,
+        "application/json" | "text/json" => Kind::Json,
+        "application/pdf" => Kind::Pdf,
+        _ if essence.ends_with("+json") => Kind::Json,
+        _ if essence.starts_with("image/") => Kind::Image,
+        _ if essence.starts_with("text/") || is_textual_application(essence) => Kind::Text,
+        // Missing, generic or unknown types: look at the body
+        _ => sniff(bytes),
+    }
+}
+
+/// `application/*` types that are text in practice
+fn is_textual_application(essence: &str) -> bool {
+    essence.ends_with("+xml")
+        || matches!(
+            essence,
+            "application/xml"
+                | "application/javascript"
+                | "application/ecmascript"
+                | "application/x-sh"
+                | "application/x-yaml"
+                | "application/yaml"
+                | "application/toml"
+                | "application/sql"
+                | "application/graphql"
+        )
+}
+
+fn sniff(bytes: &[u8]) -> Kind {
+    if let Some(format) = identify_format(bytes) {
+        return match format.mime_type {
+            "application/pdf" => Kind::Pdf,
+            mime if mime.starts_with("image/") => Kind::Image,
+            _ => Kind::Binary,
+        };
+    }
+    if looks_binary(bytes) {
+        return Kind::Binary;
+    }
+
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).to_ascii_lowercase();
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    if head.starts_with("<!doctype html") || head.starts_with("<html") {
+        Kind::Html
+    } else if (head.starts_with('{') || head.starts_with('['))
+        && serde_json::from_slice::<serde_json::Value>(bytes).is_ok()
+    {
+        Kind::Json
+    } else {
+        Kind::Text
+    }
+}
+
+/// Decode a body with the charset from its content type (or, for HTML, a
+/// `<meta charset>` declaration), falling back to UTF-8
+///
+/// A BOM overrides both. Undecodable bytes become U+FFFD rather than failing
+/// the read, since servers routinely mislabel their charsets.
+fn decode_text(bytes: &[u8], content_type: &str, is_html: bool) -> String {
+    let declared = content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        .map(|(_, value)| value.trim().trim_matches('"').to_owned());
+
+    let declared = declared.or_else(|| {
+        is_html.then(|| {
+            let head = String::from_utf8_lossy(&bytes[..bytes.len().min(META_CHARSET_WINDOW)]);
+            META_CHARSET.captures(&head).map(|caps| caps[1].to_owned())
+        })?
+    });
+
+    let encoding = declared
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+fn refusal(body: &FetchedBody, essence: &str) -> McpError {
+    let kind = if essence.is_empty() {
+        "no content type".to_string()
+    } else {
+        essence.to_string()
+    };
+
+    let format_note = match identify_format(&body.bytes) {
+        // PDFs declare their version right after the magic number
+        Some(format) if format.mime_type == "application/pdf" => {
+            let version = body.bytes.get(5..8).map(String::from_utf8_lossy);
+            format!(" · {} {}", format.name, version.unwrap_or_default())
+        }
+        Some(format) => format!(" · {}", format.name),
+        None => String::new(),
+    };
+
+    McpError::InvalidArguments(format!(
+        "Refusing to return binary content from {}: {kind}{format_note} · {} bytes. \
+         URL reads return text only; download the file and read it from disk \
+         (binary files are shown as a hex dump) instead.",
+        body.url,
+        body.bytes.len()
+    ))
+}
@@ -0,0 +1,216 @@
+//! Fetching URLs for fs_read_file
+//!
+//! [`fetch`] downloads a response body under a [`policy::UrlPolicy`],
+//! [`cache::fetch_cached`] puts an on-disk HTTP cache in front of it, and
+//! [`convert::convert`] turns the body into text the way its content type
+//! calls for. Fetching and conversion are separate so the conversion can be
+//! exercised without a network.
+
+pub mod cache;
+pub mod convert;
+pub mod policy;
+
+use kodegen_mcp_schema::McpError;
+use policy::{PolicyResolver, UrlPolicy};
+use reqwest::Url;
+use std::sync::Arc;
+use tokio::time::timeout;
+
+/// A successful HTTP response, read in full
+#[derive(Debug, Clone, Default)]
+pub struct FetchedBody {
+    /// Final URL after redirects
+    pub url: String,
+    /// Raw `content-type` header (empty if the server sent none)
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+    /// `ETag` validator, for conditional re-fetches
+    pub etag: Option<String>,
+    /// `Last-Modified` validator, for conditional re-fetches
+    pub last_modified: Option<String>,
+    /// Raw `cache-control` header
+    pub cache_control: Option<String>,
+}
+
+/// Validators sent with a conditional request
+#[derive(Debug, Clone, Copy, Default)]
+struct Conditions<'a> {
+    etag: Option<&'a str>,
+    last_modified: Option<&'a str>,
+}
+
+/// Result of a (possibly conditional) request
+enum Outcome {
+    Body(FetchedBody),
+    /// 304 to a conditional request
+    NotModified,
+    /// Any other non-success status
+    Status(reqwest::StatusCode),
+}
+
+fn status_error(status: reqwest::StatusCode) -> McpError {
+    McpError::Network(format!("HTTP error, status: {status}"))
+}
+
+/// Fetch `url` under `policy`, failing on non-success statuses
+///
+/// The host is checked before connecting, again on every redirect and on
+/// every address it resolves to. Proxies from the environment are not used,
+/// since they would do the resolving instead. The body is read chunk by chunk and the
+/// fetch aborted as soon as it grows past `policy.max_body_bytes`.
+///
+/// # Errors
+/// Returns `McpError::PermissionDenied` or `McpError::InvalidUrl` when the
+/// policy refuses the URL, `McpError::Network` for connection failures, HTTP
+/// errors and oversized bodies, and `McpError::Other` when the fetch times out.
+pub async fn fetch(url: &str, policy: &UrlPolicy) -> Result<FetchedBody, McpError> {
+    match request(url, policy, Conditions::default()).await? {
+        Outcome::Body(body) => Ok(body),
+        Outcome::NotModified => Err(status_error(reqwest::StatusCode::NOT_MODIFIED)),
+        Outcome::Status(status) => Err(status_error(status)),
+    }
+}
+
+/// Send a request for `url`, conditional when `conditions` carries validators
+async fn request(
+    url: &str,
+    policy: &UrlPolicy,
+    conditions: Conditions<'_>,
+) -> Result<Outcome, McpError> {
+    let parsed = Url::parse(url).map_err(|e| McpError::InvalidUrl(format!("{url}: {e}")))?;
+    policy.check_url(&parsed)?;
+
+    let policy = Arc::new(policy.clone());
+    let limit = policy.timeout;
+
+    let fetch_operation = async {
+        // Create HTTP client with proper User-Agent header
+        // This prevents 403 Forbidden errors from modern websites (AWS, CloudFront, etc.)
+        // that block requests without User-Agent to prevent bot scraping
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("kodegen-filesystem/", env!("CARGO_PKG_VERSION")))
+            .timeout(limit)
+            // A proxy would resolve the host itself, past PolicyResolver's
+            // address checks, so HTTP(S)_PROXY from the environment is ignored
+            .no_proxy()
+            .redirect(redirect_policy(Arc::clone(&policy)))
+            .dns_resolver(Arc::new(PolicyResolver {
+                policy: Arc::clone(&policy),
+            }))
+            .build()
+            .map_err(|e| McpError::Network(format!("Failed to build HTTP client: {}", e)))?;
+
+        let mut builder = client.get(parsed);
+        if let Some(etag) = conditions.etag {
+            builder = builder.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = conditions.last_modified {
+            builder = builder.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let mut resp = builder
+            .send()
+            .await
+            .map_err(|e| McpError::Network(describe(&e)))?;
+
+        let status = resp.status();
+        if status == reqwest::StatusCode::NOT_MODIFIED
+            && (conditions.etag.is_some() || conditions.last_modified.is_some())
+        {
+            return Ok(Outcome::NotModified);
+        }
+        if !status.is_success() {
+            return Ok(Outcome::Status(status));
+        }
+
+        let final_url = resp.url().to_string();
+        let header = |name: reqwest::header::HeaderName| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned)
+        };
+        let content_type = header(reqwest::header::CONTENT_TYPE).unwrap_or_default();
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+        let cache_control = header(reqwest::header::CACHE_CONTROL);
+
+        let too_large = || {
+            McpError::Network(format!(
+                "Response from {final_url} exceeds the {} byte limit for URL reads \
+                 (KODEGEN_URL_MAX_BYTES)",
+                policy.max_body_bytes
+            ))
+        };
+
+        // Reject declared oversized bodies up front, and enforce the cap while
+        // streaming for servers that don't declare a length
+        if resp
+            .content_length()
+            .is_some_and(|len| len > policy.max_body_bytes)
+        {
+            return Err(too_large());
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = resp
+            .chunk()
+            .await
+            .map_err(|e| McpError::Network(describe(&e)))?
+        {
+            if (bytes.len() + chunk.len()) as u64 > policy.max_body_bytes {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(Outcome::Body(FetchedBody {
+            url: final_url,
+            content_type,
+            bytes,
+            etag,
+            last_modified,
+            cache_control,
+        }))
+    };
+
+    match timeout(limit, fetch_operation).await {
+        Ok(result) => result,
+        Err(_) => Err(McpError::Other(anyhow::anyhow!(
+            "Fetch timed out after {}ms: {url}",
+            limit.as_millis()
+        ))),
+    }
+}
+
+/// Follow at most `max_redirects` redirects, re-checking each target
+fn redirect_policy(policy: Arc<UrlPolicy>) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() > policy.max_redirects {
+            let limit = policy.max_redirects;
+            return attempt.error(format!(
+                "stopped after {limit} redirects (KODEGEN_URL_MAX_REDIRECTS)"
+            ));
+        }
+        match policy.check_url(attempt.url()) {
+            Ok(()) => attempt.follow(),
+            Err(e) => attempt.error(format!("redirect refused: {e}")),
+        }
+    })
+}
+
+/// Render a reqwest error with its sources, which carry policy refusals
+/// raised by the resolver and redirect policy
+fn describe(error: &reqwest::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        let cause_text = cause.to_string();
+        if !message.contains(&cause_text) {
+            message.push_str(": ");
+            message.push_str(&cause_text);
+        }
+        source = cause.source();
+    }
+    message
+}
@@ -0,0 +1,272 @@
+//! Access policy for URL reads
+//!
+//! Mirrors `allowed_directories`/`denied_directories` for hosts: denied hosts
+//! are checked first, and a non-empty allow list admits only the hosts on it.
+//! Loopback, private, link-local and other non-public addresses are blocked
+//! unless private URLs are enabled or the host is explicitly allowed. The
+//! check runs on the URL, on every redirect and on the addresses each host
+//! name resolves to, so a public name pointing at 169.254.169.254 is refused
+//! as well.
+
+use kodegen_mcp_schema::McpError;
+use reqwest::Url;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default cap on response bodies (10 MiB)
+pub const DEFAULT_MAX_BODY_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default number of redirects followed before giving up
+pub const DEFAULT_MAX_REDIRECTS: usize = 5;
+
+/// Limits and host rules applied to every URL read
+#[derive(Debug, Clone)]
+pub struct UrlPolicy {
+    /// Hosts that may be fetched (empty = any public host)
+    pub allowed_hosts: Vec<String>,
+    /// Hosts that may never be fetched (checked first)
+    pub denied_hosts: Vec<String>,
+    /// Allow loopback, private and link-local addresses for every host
+    pub allow_private: bool,
+    /// Largest response body read before the fetch is aborted
+    pub max_body_bytes: u64,
+    /// Redirects followed before the fetch fails
+    pub max_redirects: usize,
+    /// Timeout for the whole request, including reading the body
+    pub timeout: Duration,
+}
+
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: Vec::new(),
+            denied_hosts: Vec::new(),
+            allow_private: false,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl UrlPolicy {
+    /// Build the policy from the server config and `KODEGEN_*` environment
+    /// variables
+    ///
+    /// The timeout comes from the `http_connection_timeout_secs` config value;
+    /// hosts and limits from `KODEGEN_ALLOWED_HOSTS`, `KODEGEN_DENIED_HOSTS`
+    /// (comma-separated), `KODEGEN_ALLOW_PRIVATE_URLS`, `KODEGEN_URL_MAX_BYTES`
+    /// and `KODEGEN_URL_MAX_REDIRECTS`.
+    #[must_use]
+    pub fn from_config(config_manager: &kodegen_config_manager::ConfigManager) -> Self {
+        let defaults = Self::default();
+        Self {
+            allowed_hosts: hosts_from_env("KODEGEN_ALLOWED_HOSTS"),
+            denied_hosts: hosts_from_env("KODEGEN_DENIED_HOSTS"),
+            allow_private: std::env::var("KODEGEN_ALLOW_PRIVATE_URLS")
+                .is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes")),
+            max_body_bytes: number_from_env("KODEGEN_URL_MAX_BYTES")
+                .unwrap_or(defaults.max_body_bytes),
+            max_redirects: number_from_env("KODEGEN_URL_MAX_REDIRECTS")
+                .unwrap_or(defaults.max_redirects),
+            timeout: Duration::from_secs(config_manager.get_http_connection_timeout_secs().max(1)),
+        }
+    }
+
+    /// Check a URL's scheme and host, and its address if the host is an IP literal
+    ///
+    /// # Errors
+    /// Returns `McpError::InvalidUrl` for unsupported URLs and
+    /// `McpError::PermissionDenied` when the policy refuses the host.
+    pub fn check_url(&self, url: &Url) -> Result<(), McpError> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(McpError::InvalidUrl(format!(
+                "Only http and https URLs can be read: {url}"
+            )));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| McpError::InvalidUrl(format!("URL has no host: {url}")))?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let port = url.port_or_known_default();
+
+        if let Some(rule) = self
+            .denied_hosts
+            .iter()
+            .find(|rule| host_matches(rule, host, port))
+        {
+            return Err(McpError::PermissionDenied(format!(
+                "URL host '{host}' is denied by '{rule}' in KODEGEN_DENIED_HOSTS"
+            )));
+        }
+
+        if !self.allowed_hosts.is_empty()
+            && !self
+                .allowed_hosts
+                .iter()
+                .any(|rule| host_matches(rule, host, port))
+        {
+            return Err(McpError::PermissionDenied(format!(
+                "URL host '{host}' is not in KODEGEN_ALLOWED_HOSTS ({})",
+                self.allowed_hosts.join(", ")
+            )));
+        }
+
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            self.check_addr(host, ip)?;
+        }
+        Ok(())
+    }
+
+    /// Check an address `host` resolved (or is written as)
+    fn check_addr(&self, host: &str, ip: IpAddr) -> Result<(), McpError> {
+        if is_public(ip) || self.allow_private || self.explicitly_allows(host) {
+            return Ok(());
+        }
+        Err(McpError::PermissionDenied(format!(
+            "URL host '{host}' resolves to non-public address {ip}. Private, loopback and \
+             link-local addresses are blocked; add the host to KODEGEN_ALLOWED_HOSTS or set \
+             KODEGEN_ALLOW_PRIVATE_URLS=1 to permit it"
+        )))
+    }
+
+    fn explicitly_allows(&self, host: &str) -> bool {
+        self.allowed_hosts
+            .iter()
+            .any(|rule| host_matches(rule, host, None))
+    }
+}
+
+/// Whether a host rule matches `host` (and `port`, when the rule has one)
+///
+/// `example.com` matches the host and its subdomains, `*.example.com` only
+/// subdomains. Rules may carry a port (`localhost:8080`, `[::1]:8080`); a
+/// `None` port matches any rule port.
+#[must_use]
+pub fn host_matches(rule: &str, host: &str, port: Option<u16>) -> bool {
+    let rule = rule.trim().to_ascii_lowercase();
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+
+    let (rule_host, rule_port) = split_rule(&rule);
+    if let (Some(rule_port), Some(port)) = (rule_port, port)
+        && rule_port != port
+    {
+        return false;
+    }
+
+    if let Some(suffix) = rule_host.strip_prefix("*.") {
+        return host.ends_with(&format!(".{suffix}"));
+    }
+    let rule_host = rule_host.trim_start_matches('.');
+    host == rule_host || host.ends_with(&format!(".{rule_host}"))
+}
+
+/// Split `host[:port]` or `[v6]:port`
+fn split_rule(rule: &str) -> (&str, Option<u16>) {
+    if let Some(rest) = rule.strip_prefix('[') {
+        let (host, after) = rest.split_once(']').unwrap_or((rest, ""));
+        return (host, after.strip_prefix(':').and_then(|p| p.parse().ok()));
+    }
+    match rule.rsplit_once(':') {
+        // A bare IPv6 address has several colons and no port
+        Some((host, port)) if !host.contains(':') => (host, port.parse().ok()),
+        _ => (rule, None),
+    }
+}
+
+/// Whether an address is publicly routable
+#[must_use]
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_v4(v4),
+        IpAddr::V6(v6) => {
+            if v6.is_loopback() || v6.is_unspecified() {
+                return false;
+            }
+            if let Some(v4) = embedded_v4(v6) {
+                return is_public_v4(v4);
+            }
+            let s = v6.segments();
+            !(v6.is_multicast()
+                || (s[0] & 0xfe00) == 0xfc00 // unique local
+                || (s[0] & 0xffc0) == 0xfe80 // link-local
+                || s[..4] == [0x0100, 0, 0, 0] // discard-only
+                || s[..3] == [0x0064, 0xff9b, 0x0001] // local-use NAT64
+                || s[0] == 0x2001 && s[1] < 0x0200 // IETF protocol assignments
+                || s[0] == 0x2001 && s[1] == 0x0db8) // documentation
+        }
+    }
+}
+
+/// The IPv4 address an IPv6 address reaches: IPv4-mapped (`::ffff:a.b.c.d`),
+/// IPv4-compatible (`::a.b.c.d`), NAT64 (`64:ff9b::a.b.c.d`) or 6to4
+/// (`2002:aabb:ccdd::`)
+fn embedded_v4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let s = ip.segments();
+    let v4 = |high: u16, low: u16| {
+        let [a, b] = high.to_be_bytes();
+        let [c, d] = low.to_be_bytes();
+        Ipv4Addr::new(a, b, c, d)
+    };
+    match s {
+        [0, 0, 0, 0, 0, 0xffff | 0, high, low] => Some(v4(high, low)),
+        [0x0064, 0xff9b, 0, 0, 0, 0, high, low] => Some(v4(high, low)),
+        [0x2002, high, low, ..] => Some(v4(high, low)),
+        _ => None,
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT
+        || (a == 198 && (b == 18 || b == 19)) // benchmarking
+        || a >= 240)
+}
+
+/// DNS resolver that drops the fetch when a name resolves to an address the
+/// policy does not permit
+pub(super) struct PolicyResolver {
+    pub(super) policy: Arc<UrlPolicy>,
+}
+
+impl Resolve for PolicyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = Arc::clone(&self.policy);
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            for addr in &addrs {
+                policy.check_addr(&host, addr.ip())?;
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn hosts_from_env(var: &str) -> Vec<String> {
+    std::env::var(var)
+        .ok()
+        .map(|hosts| {
+            hosts
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn number_from_env<T: std::str::FromStr>(var: &str) -> Option<T> {
+    std::env::var(var).ok()?.trim().parse().ok()
+}
@@ -0,0 +1,79 @@
+//! Glob expansion for `fs_read_multiple_files` entries
+//!
+//! A pattern is split into its literal base directory and a glob for the
+//! rest (`src/**/mod.rs` walks `src` matching `**/mod.rs`). The base is
+//! walked with the `ignore` crate, so .gitignore, .ignore and hidden-file
+//! rules apply the same way they do for fs_search.
+
+use globset::GlobBuilder;
+use ignore::WalkBuilder;
+use kodegen_mcp_schema::McpError;
+use std::path::{Path, PathBuf};
+
+/// Most files a single pattern may expand to
+pub const MAX_GLOB_MATCHES: usize = 1000;
+
+/// Whether an entry is a glob pattern rather than a literal path
+#[must_use]
+pub fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '[', '{'])
+}
+
+/// Split a pattern into its literal leading directory and the glob for the
+/// remaining components
+///
+/// `src/**/mod.rs` → (`src`, `**/mod.rs`); `*.rs` → (`.`, `*.rs`).
+#[must_use]
+pub fn split_pattern(pattern: &str) -> (PathBuf, String) {
+    let components: Vec<&str> = pattern.split('/').collect();
+    let literal = components
+        .iter()
+        .take_while(|component| !is_glob(component))
+        .count()
+        .min(components.len() - 1);
+
+    let base = components[..literal].join("/");
+    let base = match base.as_str() {
+        "" if pattern.starts_with('/') => PathBuf::from("/"),
+        "" => PathBuf::from("."),
+        _ => PathBuf::from(base),
+    };
+    (base, components[literal..].join("/"))
+}
+
+/// Expand `glob` against the files under `root`
+///
+/// Returns paths relative to `root`, sorted. `*` and `?` do not cross
+/// directory separators; `**` does.
+///
+/// # Errors
+/// Returns `McpError::InvalidArguments` for malformed patterns and patterns
+/// matching more than [`MAX_GLOB_MATCHES`] files.
+pub fn expand(root: &Path, glob: &str) -> Result<Vec<PathBuf>, McpError> {
+    let matcher = GlobBuilder::new(glob)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| McpError::InvalidArguments(format!("Invalid glob '{glob}': {e}")))?
+        .compile_matcher();
+
+    let mut matches = Vec::new();
+    for entry in WalkBuilder::new(root).build().flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        if matcher.is_match(relative) {
+            if matches.len() == MAX_GLOB_MATCHES {
+                return Err(McpError::InvalidArguments(format!(
+                    "Glob '{glob}' matches more than {MAX_GLOB_MATCHES} files; use a narrower pattern"
+                )));
+            }
+            matches.push(relative.to_path_buf());
+        }
+    }
+
+    matches.sort();
+    Ok(matches)
+}
@@ -0,0 +1,508 @@
+pub mod expand;
+
+use crate::schema::{
+    FileReadResult, FsReadMultipleFilesArgs, FsReadMultipleFilesOutput, ReadEntry,
+};
+use crate::{ReadFileTool, validate_path};
+use futures::{Stream, StreamExt, stream};
+use kodegen_config::shorten_path_for_display;
+use kodegen_mcp_schema::filesystem::ReadMultipleFilesPrompts;
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
+use std::path::Path;
+
+// ============================================================================
+// LIMITS
+// ============================================================================
+
+/// Reads in flight at once by default
+pub const DEFAULT_READ_CONCURRENCY: usize = 16;
+
+/// Most reads a single call may keep in flight
+pub const MAX_READ_CONCURRENCY: usize = 256;
+
+/// Default cap on the content one call returns (32 MiB)
+pub const DEFAULT_MAX_TOTAL_BYTES: usize = 32 * 1024 * 1024;
+
+/// How many files are read at once and how much content a call may return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadLimits {
+    pub concurrency: usize,
+    pub max_total_bytes: usize,
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_READ_CONCURRENCY,
+            max_total_bytes: DEFAULT_MAX_TOTAL_BYTES,
+        }
+    }
+}
+
+impl ReadLimits {
+    /// Server-wide limits from `KODEGEN_READ_CONCURRENCY` and
+    /// `KODEGEN_READ_MAX_TOTAL_BYTES`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let number = |var: &str| std::env::var(var).ok()?.trim().parse().ok();
+        Self {
+            concurrency: number("KODEGEN_READ_CONCURRENCY")
+                .unwrap_or(defaults.concurrency)
+                .clamp(1, MAX_READ_CONCURRENCY),
+            max_total_bytes: number("KODEGEN_READ_MAX_TOTAL_BYTES")
+                .unwrap_or(defaults.max_total_bytes),
+        }
+    }
+
+    /// Apply a call's `concurrency` and `max_total_bytes`
+    ///
+    /// Concurrency is clamped to `1..=MAX_READ_CONCURRENCY`; a call may lower
+    /// the server's byte budget but not raise it.
+    #[must_use]
+    pub fn for_call(self, concurrency: Option<usize>, max_total_bytes: Option<usize>) -> Self {
+        Self {
+            concurrency: concurrency
+                .unwrap_or(self.concurrency)
+                .clamp(1, MAX_READ_CONCURRENCY),
+            max_total_bytes: max_total_bytes
+                .map_or(self.max_total_bytes, |bytes| bytes.min(self.max_total_bytes)),
+        }
+    }
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+/// One file to read, with its effective range
+#[derive(Debug, Clone)]
+struct ReadJob {
+    path: String,
+    offset: i64,
+    length: Option<usize>,
+    symbol: Option<String>,
+}
+
+/// A `paths` entry after glob expansion
+enum Planned {
+    Read(ReadJob),
+    /// The entry could not be expanded; reported at its position
+    Failed(FileReadResult),
+}
+
+/// A result for an entry that could not be turned into files to read
+fn failed(path: String, error: String) -> FileReadResult {
+    FileReadResult {
+        path,
+        success: false,
+        content: None,
+        error: Some(error),
+        mime_type: None,
+        start_line: None,
+        end_line: None,
+        truncated: false,
+        skipped: false,
+        streamed: false,
+        content_hash: None,
+    }
+}
+
+/// Cut `content` to at most `budget` bytes, at the last line boundary that
+/// fits (or a character boundary when no whole line does)
+///
+/// Returns whether anything was cut; a notice is appended when it was.
+pub fn truncate_to_budget(content: &mut String, budget: usize) -> bool {
+    if content.len() <= budget {
+        return false;
+    }
+
+    let mut cut = budget;
+    while !content.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    if let Some(newline) = content[..cut].rfind('\n') {
+        cut = newline + 1;
+    }
+
+    content.truncate(cut);
+    content.push_str(&format!(
+        "\n[Truncated: total output budget reached after {cut} bytes of this file]"
+    ));
+    true
+}
+
+/// Run `read` over `jobs` with at most `concurrency` in flight
+///
+/// With `in_request_order`, results come out in job order whichever finishes
+/// first, so charging the output budget as they come out truncates the same
+/// file on every call. Without it they come out as they complete, which lets
+/// a streaming caller report fast files without waiting on a slow one.
+pub fn read_concurrently<J, F, Fut>(
+    jobs: Vec<J>,
+    concurrency: usize,
+    in_request_order: bool,
+    read: F,
+) -> impl Stream<Item = Fut::Output>
+where
+    F: FnMut(J) -> Fut,
+    Fut: Future,
+{
+    let reads = stream::iter(jobs).map(read);
+    if in_request_order {
+        reads.buffered(concurrency.max(1)).left_stream()
+    } else {
+        reads.buffer_unordered(concurrency.max(1)).right_stream()
+    }
+}
+
+/// Send one finished result as a progress notification
+///
+/// The message is the result serialized as JSON. Once it has been sent the
+/// content is dropped from the final response; if sending fails the content
+/// is kept so nothing is lost.
+async fn stream_result(
+    ctx: &ToolExecutionContext,
+    result: &mut FileReadResult,
+    reported: usize,
+    total: usize,
+) {
+    let Ok(message) = serde_json::to_string(&*result) else {
+        return;
+    };
+    match ctx.update(reported as f64, total as f64, message).await {
+        Ok(()) => {
+            if result.content.take().is_some() {
+                result.streamed = true;
+            }
+        }
+        Err(e) => log::debug!("Streaming {} failed: {e}", result.path),
+    }
+}
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone)]
+pub struct ReadMultipleFilesTool {
+    read_file_tool: ReadFileTool,
+    config_manager: kodegen_config_manager::ConfigManager,
+}
+
+impl ReadMultipleFilesTool {
+    #[must_use]
+    pub fn new(
+        default_line_limit: usize,
+        config_manager: kodegen_config_manager::ConfigManager,
+    ) -> Self {
+        Self {
+            read_file_tool: ReadFileTool::new(default_line_limit, config_manager.clone()),
+            config_manager,
+        }
+    }
+
+    /// Turn `paths` entries into read jobs, expanding globs in place
+    ///
+    /// Entries that fail to expand become failed results at their position.
+    async fn plan(
+        &self,
+        args: &FsReadMultipleFilesArgs,
+        ctx: &ToolExecutionContext,
+    ) -> Vec<Planned> {
+        let mut plan = Vec::with_capacity(args.paths.len());
+
+        for entry in &args.paths {
+            let (path, offset, length, symbol) = match entry {
+                ReadEntry::Path(path) => (path, None, None, None),
+                ReadEntry::Spec(spec) => {
+                    (&spec.path, spec.offset, spec.length, spec.symbol.as_ref())
+                }
+            };
+            let job = ReadJob {
+                path: path.clone(),
+                offset: offset.unwrap_or(args.offset),
+                length: length.or(args.length),
+                symbol: symbol.or(args.symbol.as_ref()).cloned(),
+            };
+
+            // Names like `[id].tsx` are globs syntactically; prefer the file if it exists
+            let literal = ctx
+                .pwd()
+                .map_or_else(|| Path::new(path).to_path_buf(), |pwd| pwd.join(path));
+            if !expand::is_glob(path) || literal.exists() {
+                plan.push(Planned::Read(job));
+                continue;
+            }
+
+            match self.expand_glob(path, ctx).await {
+                Ok(files) if files.is_empty() => {
+                    plan.push(Planned::Failed(failed(
+                        path.clone(),
+                        format!("No files match glob '{path}'"),
+                    )));
+                }
+                Ok(files) => plan.extend(files.into_iter().map(|file| {
+                    Planned::Read(ReadJob {
+                        path: file,
+                        ..job.clone()
+                    })
+                })),
+                Err(e) => plan.push(Planned::Failed(failed(path.clone(), e.to_string()))),
+            }
+        }
+
+        plan
+    }
+
+    /// Expand a glob entry to file paths written relative to the same base
+    async fn expand_glob(
+        &self,
+        pattern: &str,
+        ctx: &ToolExecutionContext,
+    ) -> Result<Vec<String>, McpError> {
+        let (base, glob) = expand::split_pattern(pattern);
+        let base_str = base.to_string_lossy().to_string();
+        let root = validate_path(&base_str, &self.config_manager, ctx.pwd()).await?;
+
+        let relative = tokio::task::spawn_blocking(move || expand::expand(&root, &glob))
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Glob expansion failed: {e}")))??;
+
+        Ok(relative
+            .into_iter()
+            .map(|file| {
+                if base_str == "." {
+                    file.to_string_lossy().to_string()
+                } else {
+                    base.join(file).to_string_lossy().to_string()
+                }
+            })
+            .collect())
+    }
+
+    /// Read a single file and convert to `FileReadResult`
+    async fn read_one_file(
+        &self,
+        path: String,
+        offset: i64,
+        length: Option<usize>,
+        line_numbers: bool,
+        symbol: Option<String>,
+        ctx: &ToolExecutionContext,
+    ) -> FileReadResult {
+        use crate::schema::FsReadFileArgs;
+
+        let args = FsReadFileArgs {
+            path: path.clone(),
+            offset,
+            length,
+            is_url: false,
+            line_numbers,
+            symbol,
+            ..Default::default()
+        };
+
+        match self.read_file_tool.execute(args, ctx.clone()).await {
+            Ok(response) => {
+                // Extract from the typed output
+                FileReadResult {
+                    path,
+                    success: true,
+                    content: Some(response.metadata.content),
+                    error: None,
+                    mime_type: Some(response.metadata.mime_type),
+                    start_line: response.metadata.start_line,
+                    end_line: response.metadata.end_line,
+                    truncated: false,
+                    skipped: false,
+                    streamed: false,
+                    content_hash: response.metadata.content_hash,
+                }
+            }
+            Err(e) => failed(path, e.to_string()),
+        }
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for ReadMultipleFilesTool {
+    type Args = FsReadMultipleFilesArgs;
+    type Prompts = ReadMultipleFilesPrompts;
+
+    fn name() -> &'static str {
+        kodegen_mcp_schema::filesystem::FS_READ_MULTIPLE_FILES
+    }
+
+    fn description() -> &'static str {
+        "Read multiple files in parallel. Returns results for all files, including errors for \
+         individual files that fail. Supports offset and length parameters applied to all \
+         files; entries may instead be objects with their own range ({\"path\": \"a.rs\", \
+         \"offset\": 9, \"length\": 31} or {\"path\": \"b.log\", \"offset\": -20}). Glob \
+         entries (\"src/**/mod.rs\") expand to the matching files, respecting .gitignore. At \
+         most concurrency files are read at once (default 16). Reading stops once \
+         max_total_bytes of content has been returned (capped by the server budget, 32 MiB by \
+         default): the file crossing the budget is truncated and the rest are skipped, and both \
+         are flagged in the results. The budget is charged in request order, so the same files \
+         are cut on every call. Set stream to receive each file's result as a progress \
+         notification as soon as it is read; streamed files are charged in the order they \
+         finish, and their contents are not repeated in the final response. Supports negative \
+         offsets for tail behavior (offset: -N reads last N lines). When offset is negative, \
+         length is ignored. Set line_numbers to prefix each line with its absolute 1-based line \
+         number (cat -n style). Set symbol to fetch the same function, type, heading or key path \
+         from every file. Automatically validates paths and handles different file types \
+         (text/images)."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false // Only reads local files, not URLs
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        if args.paths.is_empty() {
+            return Err(McpError::InvalidArguments(
+                "No paths provided. Please provide at least one file path.".to_string(),
+            ));
+        }
+
+        let plan = self.plan(&args, &ctx).await;
+        let files_requested = plan.len();
+        let limits = ReadLimits::from_env().for_call(args.concurrency, args.max_total_bytes);
+
+        // Results land in their entry's slot, so the output keeps request order
+        let mut paths = Vec::with_capacity(files_requested);
+        let mut slots: Vec<Option<FileReadResult>> = Vec::with_capacity(files_requested);
+        let mut jobs = Vec::new();
+        for (index, item) in plan.into_iter().enumerate() {
+            match item {
+                Planned::Read(job) => {
+                    paths.push(job.path.clone());
+                    slots.push(None);
+                    jobs.push((index, job));
+                }
+                Planned::Failed(result) => {
+                    paths.push(result.path.clone());
+                    slots.push(Some(result));
+                }
+            }
+        }
+
+        let mut reported = 0;
+        if args.stream {
+            for result in slots.iter_mut().flatten() {
+                reported += 1;
+                stream_result(&ctx, result, reported, files_requested).await;
+            }
+        }
+
+        let line_numbers = args.line_numbers;
+        let ctx_ref = &ctx;
+        let mut reads = Box::pin(read_concurrently(
+            jobs,
+            limits.concurrency,
+            !args.stream,
+            |(index, job)| async move {
+                let result = self
+                    .read_one_file(
+                        job.path,
+                        job.offset,
+                        job.length,
+                        line_numbers,
+                        job.symbol,
+                        ctx_ref,
+                    )
+                    .await;
+                (index, result)
+            },
+        ));
+
+        // The budget is charged in request order, or in completion order when
+        // streaming; once it is spent, reads still in flight are dropped and
+        // nothing new is started
+        let mut remaining = limits.max_total_bytes;
+        while remaining > 0
+            && let Some((index, mut result)) = reads.next().await
+        {
+            if let Some(content) = result.content.as_mut() {
+                let size = content.len();
+                result.truncated = truncate_to_budget(content, remaining);
+                remaining = remaining.saturating_sub(size);
+            }
+            if args.stream {
+                reported += 1;
+                stream_result(&ctx, &mut result, reported, files_requested).await;
+            }
+            slots[index] = Some(result);
+        }
+        drop(reads);
+
+        let results: Vec<FileReadResult> = slots
+            .into_iter()
+            .zip(paths)
+            .map(|(slot, path)| {
+                slot.unwrap_or_else(|| FileReadResult {
+                    skipped: true,
+                    ..failed(path, "Skipped: total output budget exhausted".to_string())
+                })
+            })
+            .collect();
+
+        // Count successes and failures
+        let files_read = results.iter().filter(|r| r.success).count();
+        let files_skipped = results.iter().filter(|r| r.skipped).count();
+        let files_truncated = results.iter().filter(|r| r.truncated).count();
+        let files_failed = files_requested - files_read - files_skipped;
+
+        // File list only - no header
+        let mut summary = String::new();
+
+        // Sort results: failures first, then successes (both alphabetically by path)
+        let mut sorted_results: Vec<&FileReadResult> = results.iter().collect();
+        sorted_results.sort_by(|a, b| {
+            match (a.success, b.success) {
+                // Both success or both failure: sort alphabetically by path
+                (true, true) | (false, false) => a.path.cmp(&b.path),
+                // Failures come before successes
+                (false, true) => std::cmp::Ordering::Less,
+                (true, false) => std::cmp::Ordering::Greater,
+            }
+        });
+
+        // Format each file as a single line with icon and shortened path
+        for result in sorted_results {
+            let display_path =
+                shorten_path_for_display(std::path::Path::new(&result.path), ctx.git_root());
+
+            if result.skipped {
+                summary.push_str(&format!("\x1b[2m{} (skipped)\x1b[0m\n", display_path));
+            } else if result.truncated {
+                summary.push_str(&format!("\x1b[33m{} (truncated)\x1b[0m\n", display_path));
+            } else if result.success {
+                summary.push_str(&format!("\x1b[32m{}\x1b[0m\n", display_path));
+            } else {
+                summary.push_str(&format!("\x1b[31m{}\x1b[0m\n", display_path));
+            }
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            FsReadMultipleFilesOutput {
+                success: true,
+                streamed: args.stream,
+                files_requested,
+                files_read,
+                files_failed,
+                files_truncated,
+                files_skipped,
+                results,
+            },
+        ))
+    }
+}
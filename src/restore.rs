@@ -0,0 +1,159 @@
+use crate::journal::DEFAULT_CONNECTION;
+use crate::schema::{FS_RESTORE, FsRestoreArgs, FsRestoreOutput, TrashEntry};
+use crate::trash::Trash;
+use crate::validate_path;
+use kodegen_config::shorten_path_for_display;
+use kodegen_mcp_schema::filesystem::MoveFilePrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone)]
+pub struct RestoreTool {
+    config_manager: kodegen_config_manager::ConfigManager,
+}
+
+impl RestoreTool {
+    #[must_use]
+    pub fn new(config_manager: kodegen_config_manager::ConfigManager) -> Self {
+        Self { config_manager }
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for RestoreTool {
+    type Args = FsRestoreArgs;
+    type Prompts = MoveFilePrompts;
+
+    fn name() -> &'static str {
+        FS_RESTORE
+    }
+
+    fn description() -> &'static str {
+        "Restore a file or directory that fs_delete_file or fs_delete_directory moved to the \
+         trash. Pass id (the trash_id of the delete) or path (the original path; restores the \
+         item most recently deleted from it), and optionally destination to restore elsewhere. \
+         Never replaces an existing file. Call without id or path to list the trash, newest \
+         first (this connection's deletions unless all_connections is true)."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false // Only creates the restored path, never replaces one
+    }
+
+    fn idempotent() -> bool {
+        false // The item leaves the trash on the first call
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let trash = Trash::open_default().ok_or_else(|| {
+            McpError::InvalidArguments("The trash is disabled (KODEGEN_TRASH=0)".to_string())
+        })?;
+
+        let items = trash.list(None).await?;
+        let item = match (&args.id, &args.path) {
+            (Some(id), _) => items
+                .into_iter()
+                .find(|item| item.id == *id)
+                .ok_or_else(|| {
+                    McpError::ResourceNotFound(format!(
+                        "No item {id} in the trash (call fs_restore without arguments to list it)"
+                    ))
+                })?,
+            (None, Some(path)) => {
+                let original = validate_path(path, &self.config_manager, ctx.pwd()).await?;
+                // Newest first, so this is the latest deletion from that path
+                items
+                    .into_iter()
+                    .find(|item| item.original_path == original)
+                    .ok_or_else(|| {
+                        McpError::ResourceNotFound(format!(
+                            "Nothing deleted from {path} is in the trash"
+                        ))
+                    })?
+            }
+            (None, None) => {
+                let connection = ctx.connection_id().unwrap_or(DEFAULT_CONNECTION);
+                return list(&trash, (!args.all_connections).then_some(connection)).await;
+            }
+        };
+
+        // The target is checked against the allowed directories like any write
+        let target = match &args.destination {
+            Some(destination) => destination.clone(),
+            None => item.original_path.to_string_lossy().to_string(),
+        };
+        let target = validate_path(&target, &self.config_manager, ctx.pwd()).await?;
+        trash.restore(&item, &target).await?;
+
+        // Human summary
+        let display_target = shorten_path_for_display(&target, ctx.git_root());
+        let summary = format!(
+            "\x1b[32mRestored from trash\x1b[0m\n\
+             Item: {}\n\
+             To:   {}",
+            item.id, display_target
+        );
+
+        Ok(ToolResponse::new(
+            summary,
+            FsRestoreOutput {
+                success: true,
+                restored: Some(TrashEntry::from(&item)),
+                restored_to: Some(target.to_string_lossy().to_string()),
+                items: Vec::new(),
+                message: "Restored from the trash".to_string(),
+            },
+        ))
+    }
+}
+
+/// The listing returned when neither `id` nor `path` is given
+async fn list(
+    trash: &Trash,
+    connection: Option<&str>,
+) -> Result<ToolResponse<FsRestoreOutput>, McpError> {
+    let items: Vec<TrashEntry> = trash
+        .list(connection)
+        .await?
+        .iter()
+        .map(TrashEntry::from)
+        .collect();
+
+    // Human summary
+    let mut summary = format!("\x1b[36mTrash: {} item(s)\x1b[0m", items.len());
+    for item in &items {
+        summary.push_str(&format!(
+            "\n  {} · {} · {}",
+            item.id, item.deleted_at, item.original_path
+        ));
+    }
+    let message = format!(
+        "{} item(s) in the trash; pass id or path to restore one",
+        items.len()
+    );
+
+    Ok(ToolResponse::new(
+        summary,
+        FsRestoreOutput {
+            success: true,
+            restored: None,
+            restored_to: None,
+            items,
+            message,
+        },
+    ))
+}
@@ -0,0 +1,94 @@
+//! Schema types for fs_apply_patch tool
+
+use kodegen_config::CATEGORY_FILESYSTEM;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::EditBlockPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool name (not yet defined in `kodegen_config`)
+pub const FS_APPLY_PATCH: &str = "fs_apply_patch";
+
+/// Default number of context lines a hunk may ignore at each end, as in
+/// `patch`'s default fuzz factor
+pub const DEFAULT_FUZZ: usize = 2;
+
+// ============================================================================
+// APPLY PATCH ARGS
+// ============================================================================
+
+/// Arguments for `fs_apply_patch` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsApplyPatchArgs {
+    /// Unified diff to apply, as `git diff` or `diff -u` print it; may span
+    /// several files and create, delete or rename them
+    pub patch: String,
+
+    /// Leading path components to strip from the paths in the patch (like
+    /// `patch -p`). Default: 1 for git-style `a/` and `b/` paths, otherwise 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strip: Option<usize>,
+
+    /// Context lines a hunk may ignore at its start and end when it does not
+    /// match as is (default: 2; 0 requires every context line to match)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuzz: Option<usize>,
+
+    /// Check that every hunk applies and report the result without writing
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// APPLY PATCH OUTPUT
+// ============================================================================
+
+/// What the patch did to one file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PatchedFile {
+    /// Path of the file after the patch (before it, for deletions)
+    pub path: String,
+    /// "modify", "create", "delete" or "rename"
+    pub action: String,
+    /// Original path of a renamed file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    pub hunks: u32,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+    /// Hunks that applied at a different line or with fuzz, e.g.
+    /// "hunk 2 applied at line 57 (offset +3, fuzz 1)"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/// Output from `fs_apply_patch` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsApplyPatchOutput {
+    pub success: bool,
+    pub files: Vec<PatchedFile>,
+    pub message: String,
+    /// Undo journal entry covering every file; pass to `fs_undo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo_id: Option<String>,
+
+    /// Nothing was written; this response describes what would be
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+// Prompt providers are sealed upstream; a patch is a batch of block edits, so
+// it shares the edit-block prompts.
+impl ToolArgs for FsApplyPatchArgs {
+    type Output = FsApplyPatchOutput;
+    type Prompts = EditBlockPrompts;
+
+    const NAME: &'static str = FS_APPLY_PATCH;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str =
+        "Apply a multi-file unified diff atomically: every file is patched, or none is";
+}
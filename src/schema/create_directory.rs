@@ -0,0 +1,57 @@
+//! Schema types for fs_create_directory tool
+
+use kodegen_config::{CATEGORY_FILESYSTEM, FS_CREATE_DIRECTORY};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::CreateDirectoryPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// CREATE DIRECTORY ARGS
+// ============================================================================
+
+/// Arguments for `fs_create_directory` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsCreateDirectoryArgs {
+    /// Path to the directory to create
+    pub path: String,
+
+    /// Validate and report which directories would be created without
+    /// creating them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// CREATE DIRECTORY OUTPUT
+// ============================================================================
+
+/// Output from `fs_create_directory` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsCreateDirectoryOutput {
+    pub success: bool,
+    pub path: String,
+    pub created: bool,
+    pub message: String,
+
+    /// Nothing was created; this response describes what would be
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+
+    /// Dry run: directories that would be created, outermost first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub would_create: Vec<String>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+impl ToolArgs for FsCreateDirectoryArgs {
+    type Output = FsCreateDirectoryOutput;
+    type Prompts = CreateDirectoryPrompts;
+
+    const NAME: &'static str = FS_CREATE_DIRECTORY;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Create a new directory or ensure a directory exists. Automatically creates parent directories (like mkdir -p)";
+}
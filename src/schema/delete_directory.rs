@@ -0,0 +1,79 @@
+//! Schema types for fs_delete_directory tool
+
+use kodegen_config::{CATEGORY_FILESYSTEM, FS_DELETE_DIRECTORY};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::DeleteDirectoryPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// DELETE DIRECTORY ARGS
+// ============================================================================
+
+/// Arguments for `fs_delete_directory` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsDeleteDirectoryArgs {
+    /// Path to the directory to delete
+    pub path: String,
+
+    /// Confirm recursive deletion (must be true)
+    #[serde(default)]
+    pub recursive: bool,
+
+    /// Validate and report what would be removed without deleting anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// DELETE DIRECTORY OUTPUT
+// ============================================================================
+
+/// Output from `fs_delete_directory` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsDeleteDirectoryOutput {
+    pub success: bool,
+    pub path: String,
+    pub message: String,
+
+    /// Undo journal entry for this deletion; pass to `fs_undo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo_id: Option<String>,
+
+    /// Trash ID when the directory was moved to the trash; pass to `fs_restore`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_id: Option<String>,
+
+    /// Nothing was deleted; this response describes what would be
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+
+    /// Dry run: what the delete would remove
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub would_remove: Option<TreeSize>,
+}
+
+/// Contents of a directory tree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TreeSize {
+    /// Files and symlinks, at any depth
+    pub files: u64,
+    /// Directories, including the top one
+    pub directories: u64,
+    /// Total size of the files in bytes
+    pub bytes: u64,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+impl ToolArgs for FsDeleteDirectoryArgs {
+    type Output = FsDeleteDirectoryOutput;
+    type Prompts = DeleteDirectoryPrompts;
+
+    const NAME: &'static str = FS_DELETE_DIRECTORY;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str =
+        "Delete a directory and all its contents by moving it to the trash; fs_restore or fs_undo brings it back";
+}
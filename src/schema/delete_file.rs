@@ -0,0 +1,65 @@
+//! Schema types for fs_delete_file tool
+
+use kodegen_config::{CATEGORY_FILESYSTEM, FS_DELETE_FILE};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::DeleteFilePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// DELETE FILE ARGS
+// ============================================================================
+
+/// Arguments for `fs_delete_file` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsDeleteFileArgs {
+    /// Path to the file to delete
+    pub path: String,
+
+    /// Only delete if the file still matches this `content_hash` or
+    /// `modified` value from `fs_read_file`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_match: Option<String>,
+
+    /// Validate and report what would happen without deleting anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// DELETE FILE OUTPUT
+// ============================================================================
+
+/// Output from `fs_delete_file` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsDeleteFileOutput {
+    pub success: bool,
+    pub path: String,
+    pub message: String,
+
+    /// Undo journal entry for this deletion; pass to `fs_undo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo_id: Option<String>,
+
+    /// Trash ID when the file was moved to the trash; pass to `fs_restore`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_id: Option<String>,
+
+    /// Nothing was deleted; this response describes what would be
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+impl ToolArgs for FsDeleteFileArgs {
+    type Output = FsDeleteFileOutput;
+    type Prompts = DeleteFilePrompts;
+
+    const NAME: &'static str = FS_DELETE_FILE;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str =
+        "Delete a file by moving it to the trash; fs_restore or fs_undo brings it back";
+}
@@ -0,0 +1,216 @@
+//! Schema types for fs_edit_block tool
+
+use kodegen_config::{CATEGORY_FILESYSTEM, FS_EDIT_BLOCK};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::EditBlockPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// EDIT BLOCK ARGS
+// ============================================================================
+
+fn default_expected_replacements() -> usize {
+    1
+}
+
+/// Similarity `accept_fuzzy` requires unless `accept_fuzzy_threshold` is given
+pub const DEFAULT_FUZZY_ACCEPT_THRESHOLD: f64 = 0.95;
+
+/// How `old_string` is matched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EditPatternMode {
+    /// Exact text (default)
+    #[default]
+    Literal,
+    /// Regular expression; `new_string` may use `$1` and `${name}` capture
+    /// references (`$$` for a literal `$`)
+    Regex,
+    /// Exact text if it occurs; otherwise whole lines compared without their
+    /// common indentation and trailing whitespace, with `new_string`
+    /// re-indented to the indentation found in the file
+    NormalizeWhitespace,
+}
+
+/// Arguments for `fs_edit_block` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsEditBlockArgs {
+    /// Path to the file to edit
+    pub path: String,
+
+    /// The exact string to search for and replace (leave empty when using `edits`)
+    #[serde(default)]
+    pub old_string: String,
+
+    /// The replacement string (leave empty when using `edits`)
+    #[serde(default)]
+    pub new_string: String,
+
+    /// Expected number of replacements (defaults to 1)
+    /// The file is not modified unless exactly this many matches are found
+    #[serde(default = "default_expected_replacements")]
+    pub expected_replacements: usize,
+
+    /// Replace only this match: 1 for the first, 2 for the second, -1 for
+    /// the last (`expected_replacements` is not checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occurrence: Option<i64>,
+
+    /// Only match within lines from this one (1-based, inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u64>,
+
+    /// Only match within lines up to this one (1-based, inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u64>,
+
+    /// "literal" (default), "regex" or "normalize_whitespace"; applies to
+    /// every entry of `edits` too
+    #[serde(default)]
+    pub pattern_mode: EditPatternMode,
+
+    /// When a literal `old_string` is not found but a span of the file is at
+    /// least `accept_fuzzy_threshold` similar, replace that span instead of
+    /// failing; the response shows what was actually matched
+    #[serde(default)]
+    pub accept_fuzzy: bool,
+
+    /// Minimum similarity (0.0-1.0) for `accept_fuzzy` (default: 0.95, never
+    /// below the configured fuzzy search threshold)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept_fuzzy_threshold: Option<f64>,
+
+    /// Several edits to apply in order, in memory, and write once
+    /// Each edit sees the result of the ones before it; if any fails to
+    /// match, nothing is written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edits: Vec<EditOperation>,
+
+    /// Text encoding of the file (e.g. "utf-16le", "shiftjis", "latin1")
+    /// Default: detected from the BOM, otherwise UTF-8
+    /// The file is written back in the same encoding and BOM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+
+    /// Only edit if the file still matches this `content_hash` or `modified`
+    /// value from `fs_read_file`; otherwise fail with a conflict
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_match: Option<String>,
+
+    /// Validate and return a diff of the change without touching the file
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Lines of context around each change in the returned diff (default: 3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_context: Option<usize>,
+}
+
+/// One edit of a multi-edit `fs_edit_block` call
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EditOperation {
+    /// The exact string to search for and replace
+    pub old_string: String,
+
+    /// The replacement string
+    pub new_string: String,
+
+    /// Expected number of replacements (defaults to 1)
+    #[serde(default = "default_expected_replacements")]
+    pub expected_replacements: usize,
+
+    /// Replace only this match: 1 for the first, -1 for the last
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occurrence: Option<i64>,
+
+    /// Only match within lines from this one (1-based, inclusive), counted
+    /// after the edits before this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u64>,
+
+    /// Only match within lines up to this one (1-based, inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u64>,
+}
+
+// ============================================================================
+// EDIT BLOCK OUTPUT
+// ============================================================================
+
+/// Output from `fs_edit_block` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsEditBlockOutput {
+    pub success: bool,
+    pub path: String,
+    pub replacements_made: u32,
+    pub message: String,
+    /// Encoding the file was read and written in (omitted for plain UTF-8)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// Hash of the file after the edit, for the next `if_match`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Undo journal entry for this edit; pass to `fs_undo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo_id: Option<String>,
+
+    /// Nothing was edited; this response describes what would be
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+
+    /// Unified diff of the change (of the would-be change in a dry run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+
+    /// Each replaced span (regex mode, or normalized whitespace matches)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replaced: Vec<ReplacedSpan>,
+
+    /// `old_string` only matched once indentation and trailing whitespace
+    /// were normalized, and `new_string` was re-indented to fit
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub whitespace_normalized: bool,
+
+    /// Spans replaced through `accept_fuzzy` instead of an exact match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fuzzy_matches: Vec<FuzzyMatchApplied>,
+}
+
+/// One span a regex or whitespace-normalized edit replaced
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplacedSpan {
+    /// Line the match started on before the edit (1-based)
+    pub line_before: usize,
+    /// Line the replacement starts on after the edit (1-based)
+    pub line_after: usize,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+/// A near match `accept_fuzzy` replaced
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FuzzyMatchApplied {
+    /// Line the matched text started on before the edit (1-based)
+    pub line: usize,
+    /// Similarity of the matched text to `old_string` (0.0-1.0)
+    pub similarity: f64,
+    /// The text that was replaced
+    pub matched_text: String,
+    /// Character diff from `old_string` to `matched_text`:
+    /// `{-expected-}{+found+}` around the common prefix and suffix
+    pub char_diff: String,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+impl ToolArgs for FsEditBlockArgs {
+    type Output = FsEditBlockOutput;
+    type Prompts = EditBlockPrompts;
+
+    const NAME: &'static str = FS_EDIT_BLOCK;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Apply surgical text replacements to files. Takes old_string and new_string, performs exact string replacement in the file's own encoding";
+}
@@ -0,0 +1,60 @@
+//! Schema types for fs_empty_trash tool
+
+use super::TrashEntry;
+use kodegen_config::CATEGORY_FILESYSTEM;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::DeleteDirectoryPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool name (not yet defined in `kodegen_config`)
+pub const FS_EMPTY_TRASH: &str = "fs_empty_trash";
+
+// ============================================================================
+// EMPTY TRASH ARGS
+// ============================================================================
+
+/// Arguments for `fs_empty_trash` tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FsEmptyTrashArgs {
+    /// Remove only these trash IDs (default: every item)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<String>,
+
+    /// Remove only items deleted at least this many hours ago
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub older_than_hours: Option<u64>,
+
+    /// Remove items every connection deleted, not just this one's
+    #[serde(default)]
+    pub all_connections: bool,
+}
+
+// ============================================================================
+// EMPTY TRASH OUTPUT
+// ============================================================================
+
+/// Output from `fs_empty_trash` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsEmptyTrashOutput {
+    pub success: bool,
+    /// Items permanently removed
+    pub removed: Vec<TrashEntry>,
+    pub message: String,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+// Prompt providers are sealed upstream; emptying the trash is a permanent
+// recursive delete, so it shares the delete-directory prompts.
+impl ToolArgs for FsEmptyTrashArgs {
+    type Output = FsEmptyTrashOutput;
+    type Prompts = DeleteDirectoryPrompts;
+
+    const NAME: &'static str = FS_EMPTY_TRASH;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str =
+        "Permanently remove deleted files and directories from the trash";
+}
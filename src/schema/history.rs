@@ -0,0 +1,76 @@
+//! Schema types for fs_history tool
+
+use kodegen_config::CATEGORY_FILESYSTEM;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::GetFileInfoPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool name (not yet defined in `kodegen_config`)
+pub const FS_HISTORY: &str = "fs_history";
+
+// ============================================================================
+// HISTORY ARGS
+// ============================================================================
+
+/// Arguments for `fs_history` tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FsHistoryArgs {
+    /// Maximum number of operations to list, newest first (default: 20)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+
+    /// List operations from every connection, not just this one
+    #[serde(default)]
+    pub all_connections: bool,
+}
+
+// ============================================================================
+// HISTORY OUTPUT
+// ============================================================================
+
+/// One operation in the undo journal
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JournalEntry {
+    /// Operation ID, accepted by `fs_undo`
+    pub id: String,
+    pub connection: String,
+    /// Tool that made the change
+    pub tool: String,
+    pub summary: String,
+    /// When the operation ran (RFC 3339)
+    pub timestamp: String,
+    /// Paths the operation changed
+    pub paths: Vec<String>,
+    /// Bytes the journal keeps for this operation
+    pub size_bytes: u64,
+    /// Whether the operation has already been undone
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub undone: bool,
+}
+
+/// Output from `fs_history` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsHistoryOutput {
+    pub success: bool,
+    /// Newest first
+    pub operations: Vec<JournalEntry>,
+    /// Operations in the journal before `limit` was applied
+    pub total: usize,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+// Prompt providers are sealed upstream; the file-info prompts are the
+// closest fit for an inspection tool.
+impl ToolArgs for FsHistoryArgs {
+    type Output = FsHistoryOutput;
+    type Prompts = GetFileInfoPrompts;
+
+    const NAME: &'static str = FS_HISTORY;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str =
+        "List the journaled write, edit, move and delete operations that fs_undo can revert";
+}
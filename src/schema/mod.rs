@@ -0,0 +1,48 @@
+//! Filesystem tool schemas extended beyond `kodegen_mcp_schema::filesystem`
+//!
+//! Every Args/Output type in this module is a field-for-field superset of its
+//! upstream counterpart: new fields are optional on input and additive on
+//! output, so existing clients that deserialize responses with the upstream
+//! types keep working. Prompt providers are reused from the upstream schema.
+
+pub mod read_file;
+pub use read_file::*;
+
+pub mod read_multiple_files;
+pub use read_multiple_files::*;
+
+pub mod write_file;
+pub use write_file::*;
+
+pub mod edit_block;
+pub use edit_block::*;
+
+pub mod apply_patch;
+pub use apply_patch::*;
+
+pub mod replace;
+pub use replace::*;
+
+pub mod create_directory;
+pub use create_directory::*;
+
+pub mod move_file;
+pub use move_file::*;
+
+pub mod delete_file;
+pub use delete_file::*;
+
+pub mod delete_directory;
+pub use delete_directory::*;
+
+pub mod undo;
+pub use undo::*;
+
+pub mod history;
+pub use history::*;
+
+pub mod restore;
+pub use restore::*;
+
+pub mod empty_trash;
+pub use empty_trash::*;
@@ -0,0 +1,68 @@
+//! Schema types for fs_move_file tool
+
+use kodegen_config::{CATEGORY_FILESYSTEM, FS_MOVE_FILE};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::MoveFilePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// MOVE FILE ARGS
+// ============================================================================
+
+/// Arguments for `fs_move_file` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsMoveFileArgs {
+    /// Source path (file or directory to move)
+    pub source: String,
+
+    /// Destination path (where to move it)
+    pub destination: String,
+
+    /// Replace the destination if it exists (default false: fail instead)
+    #[serde(default)]
+    pub overwrite: bool,
+
+    /// Only move if the source still matches this `content_hash` or
+    /// `modified` value from `fs_read_file`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_match: Option<String>,
+
+    /// Validate and report what would happen without moving anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// MOVE FILE OUTPUT
+// ============================================================================
+
+/// Output from `fs_move_file` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsMoveFileOutput {
+    pub success: bool,
+    pub source: String,
+    pub destination: String,
+    pub message: String,
+
+    /// Undo journal entry for this move; pass to `fs_undo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo_id: Option<String>,
+
+    /// Nothing was moved; this response describes what would be
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+impl ToolArgs for FsMoveFileArgs {
+    type Output = FsMoveFileOutput;
+    type Prompts = MoveFilePrompts;
+
+    const NAME: &'static str = FS_MOVE_FILE;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Move or rename files and directories. Can move files between directories and rename them in a single operation";
+}
@@ -0,0 +1,144 @@
+//! Schema types for fs_read_file tool
+
+use kodegen_config::{CATEGORY_FILESYSTEM, FS_READ_FILE};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::ReadFilePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// READ FILE ARGS
+// ============================================================================
+
+/// Arguments for `fs_read_file` tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FsReadFileArgs {
+    /// Path to the file to read (or URL if `is_url` is true)
+    pub path: String,
+
+    /// Line offset to start reading from (0-based)
+    /// Positive: Start from line N (0-based indexing)
+    /// Negative: Read last N lines from end (tail behavior)
+    #[serde(default)]
+    pub offset: i64,
+
+    /// Maximum number of lines to read (None = use tool's default)
+    /// Ignored when offset is negative
+    #[serde(default)]
+    pub length: Option<usize>,
+
+    /// Whether the path is a URL (auto-detected if not specified)
+    /// HTML is returned as markdown and JSON pretty-printed; `offset` and
+    /// `length` apply to the converted text
+    #[serde(default)]
+    pub is_url: bool,
+
+    /// Byte offset to start reading from (switches to byte-addressed mode)
+    /// Positive: Start from byte N (0-based indexing)
+    /// Negative: Read last N bytes from end (tail behavior)
+    /// When set, `offset` and `length` are ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_offset: Option<i64>,
+
+    /// Maximum number of bytes to read in byte-addressed mode
+    /// (default: 65536 for text, 4096 for binary files)
+    /// Ignored when `byte_offset` is negative
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_length: Option<usize>,
+
+    /// Text encoding of the file (e.g. "utf-16le", "shiftjis", "latin1")
+    /// Default: detected from the BOM, otherwise UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+
+    /// Prefix each returned line with its absolute 1-based line number (cat -n style)
+    #[serde(default)]
+    pub line_numbers: bool,
+
+    /// Read only the span of one symbol instead of a line window
+    /// Code: a function, struct, enum, trait, impl or class name, optionally
+    /// qualified by its container and/or prefixed by its kind
+    /// ("execute", "EditBlockTool::execute", "struct Foo", "impl Tool for Foo")
+    /// Markdown: heading text ("Installation")
+    /// JSON/TOML: dotted key path ("dependencies.serde", "items[0].name")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+
+    /// URL cache mode: "use" (default) serves fresh cached copies and
+    /// revalidates stale ones, "refresh" always downloads, "only" reads
+    /// from the cache without touching the network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<String>,
+}
+
+// ============================================================================
+// READ FILE OUTPUT
+// ============================================================================
+
+/// Output from `fs_read_file` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsReadFileOutput {
+    pub success: bool,
+    pub path: String,
+    pub mime_type: String,
+    pub is_image: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_lines: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines_read: Option<u64>,
+    pub is_partial: bool,
+    pub content: String,
+    /// Byte offset of the returned window (byte-addressed mode only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_offset: Option<u64>,
+    /// Number of bytes covered by the returned window (byte-addressed mode only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_read: Option<u64>,
+    /// True if `content` is an xxd-style hex dump rather than text
+    #[serde(default)]
+    pub is_hex_dump: bool,
+    /// Binary format identified from the file's magic number (e.g. "ELF executable")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_format: Option<String>,
+    /// Encoding the content was decoded from (omitted for plain UTF-8)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// First returned line (1-based, inclusive; line-mode text reads only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u64>,
+    /// Last returned line (1-based, inclusive; line-mode text reads only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u64>,
+    /// Symbol that was read, e.g. "fn EditBlockTool::execute" (symbol reads only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// How a URL response was converted: "html-to-markdown" or "json-pretty"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<String>,
+    /// Where a URL body came from: "miss", "hit", "revalidated" or "stale"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_status: Option<String>,
+    /// SHA-256 of the bytes read ("sha256:<hex>"), for `if_match` on writes,
+    /// edits, moves and deletes; only set when the read covered the whole
+    /// local file (use `modified` after partial reads)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// File modification time (RFC 3339), also accepted by `if_match`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+impl ToolArgs for FsReadFileArgs {
+    type Output = FsReadFileOutput;
+    type Prompts = ReadFilePrompts;
+
+    const NAME: &'static str = FS_READ_FILE;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Read the contents of a file from the filesystem or a URL. Supports text files (returned as text), image files (returned as base64) and byte-range reads with hex dumps for binary content";
+}
@@ -0,0 +1,181 @@
+//! Schema types for fs_read_multiple_files tool
+
+use kodegen_config::{CATEGORY_FILESYSTEM, FS_READ_MULTIPLE_FILES};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::ReadMultipleFilesPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// READ MULTIPLE FILES ARGS
+// ============================================================================
+
+/// Arguments for `fs_read_multiple_files` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsReadMultipleFilesArgs {
+    /// List of file paths or glob patterns to read
+    ///
+    /// Accepts both single string and array: `paths: "file.txt"` or `paths: ["file1.txt", "file2.txt"]`
+    /// Entries may be objects with their own range:
+    /// `{"path": "src/a.rs", "offset": 9, "length": 31}` or `{"path": "b.log", "offset": -20}`
+    /// Globs (`src/**/mod.rs`) expand to matching files, respecting .gitignore
+    #[serde(deserialize_with = "kodegen_mcp_schema::serde_helpers::string_or_vec")]
+    pub paths: Vec<ReadEntry>,
+
+    /// Line offset for all files (optional; entries may override it)
+    /// Positive: Start from line N (0-based indexing)
+    /// Negative: Read last N lines from end (tail behavior)
+    #[serde(default)]
+    pub offset: i64,
+
+    /// Max lines to read per file (optional; entries may override it)
+    /// Ignored when offset is negative
+    #[serde(default)]
+    pub length: Option<usize>,
+
+    /// Prefix each returned line with its absolute 1-based line number (cat -n style)
+    #[serde(default)]
+    pub line_numbers: bool,
+
+    /// Read only this symbol from every file (see `fs_read_file`'s `symbol`)
+    /// Files that do not define it are reported as failures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+
+    /// Stop once this many bytes of content have been returned in total
+    /// The file that crosses the budget is truncated at a line boundary and
+    /// the remaining files are skipped; both are flagged in the results.
+    /// Capped by the server budget (`KODEGEN_READ_MAX_TOTAL_BYTES`, 32 MiB by default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_bytes: Option<usize>,
+
+    /// Most files read at once (default `KODEGEN_READ_CONCURRENCY`, or 16; at most 256)
+    /// The budget is charged in request order whatever the concurrency,
+    /// except when streaming
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+
+    /// Send each file's result as a progress notification (JSON message) as
+    /// soon as it is read; streamed contents are left out of the final
+    /// response. Files are then charged to the budget in the order they
+    /// finish, so which one is truncated can vary between calls
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// One entry of `paths`: a path or glob, optionally with its own range
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ReadEntry {
+    /// File path or glob pattern, read with the top-level range
+    Path(String),
+    /// File path or glob pattern with its own range
+    Spec(ReadSpec),
+}
+
+/// Per-entry read range; unset fields fall back to the top-level arguments
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ReadSpec {
+    /// File path or glob pattern
+    pub path: String,
+
+    /// Line offset (negative reads the last N lines)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+
+    /// Max lines to read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+
+    /// Read only this symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+}
+
+impl ReadEntry {
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Path(path) => path,
+            Self::Spec(spec) => &spec.path,
+        }
+    }
+}
+
+impl std::str::FromStr for ReadEntry {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::Path(s.to_string()))
+    }
+}
+
+// ============================================================================
+// READ MULTIPLE FILES OUTPUT
+// ============================================================================
+
+/// Output from `fs_read_multiple_files` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsReadMultipleFilesOutput {
+    pub success: bool,
+    /// Results were sent as progress notifications while reading
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub streamed: bool,
+    pub files_requested: usize,
+    pub files_read: usize,
+    pub files_failed: usize,
+    /// Files cut short by `max_total_bytes`
+    #[serde(default)]
+    pub files_truncated: usize,
+    /// Files not read because `max_total_bytes` was exhausted
+    #[serde(default)]
+    pub files_skipped: usize,
+    pub results: Vec<FileReadResult>,
+}
+
+// ============================================================================
+// SUPPORTING TYPES
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileReadResult {
+    pub path: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// First returned line (1-based, inclusive; text reads only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u64>,
+    /// Last returned line (1-based, inclusive; text reads only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u64>,
+    /// Content was cut short by `max_total_bytes`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// File was not read because `max_total_bytes` was exhausted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// Content was delivered in a progress notification and is omitted here
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub streamed: bool,
+    /// SHA-256 of the file when it was read whole, for `if_match` (see `fs_read_file`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+impl ToolArgs for FsReadMultipleFilesArgs {
+    type Output = FsReadMultipleFilesOutput;
+    type Prompts = ReadMultipleFilesPrompts;
+
+    const NAME: &'static str = FS_READ_MULTIPLE_FILES;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Read multiple files in parallel with bounded concurrency. Returns results for all files, including errors for individual files that fail";
+}
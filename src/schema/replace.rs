@@ -0,0 +1,156 @@
+//! Schema types for fs_replace tool
+
+use kodegen_config::CATEGORY_FILESYSTEM;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::{CaseMode, EngineChoice, SearchPrompts};
+use kodegen_mcp_schema::serde_helpers::string_or_vec;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool name (not yet defined in `kodegen_config`)
+pub const FS_REPLACE: &str = "fs_replace";
+
+// ============================================================================
+// REPLACE ARGS
+// ============================================================================
+
+/// Arguments for `fs_replace` tool
+///
+/// The search fields mean exactly what they mean for `fs_search` content
+/// searches.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsReplaceArgs {
+    /// Root directory (or single file) to search
+    pub path: String,
+
+    /// Pattern to replace (regex unless `literal_search` is set)
+    /// A pattern that matches the empty string is refused
+    pub pattern: String,
+
+    /// Replacement for each match. `$1`, `${1}` and `${name}` insert capture
+    /// groups; `$$` is a literal `$`
+    pub replacement: String,
+
+    /// Glob pattern to filter files (e.g. "*.js", "*.{ts,tsx}") - maps to rg --glob
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_pattern: Option<String>,
+
+    /// File types to include using ripgrep's built-in definitions (rg --type)
+    #[serde(
+        default,
+        deserialize_with = "string_or_vec",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub r#type: Vec<String>,
+
+    /// File types to exclude using ripgrep's built-in definitions (rg --type-not)
+    #[serde(
+        default,
+        deserialize_with = "string_or_vec",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub type_not: Vec<String>,
+
+    /// Case matching mode: "sensitive", "insensitive", or "smart" (default: "sensitive")
+    #[serde(default)]
+    pub case_mode: CaseMode,
+
+    /// Match the pattern as an exact string instead of a regex
+    #[serde(default)]
+    pub literal_search: bool,
+
+    /// Boundary mode for pattern matching: "word", "line", or null (default: null)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boundary_mode: Option<String>,
+
+    /// Include hidden files
+    #[serde(default)]
+    pub include_hidden: bool,
+
+    /// Disable all ignore files (.gitignore, .ignore, etc.)
+    #[serde(default)]
+    pub no_ignore: bool,
+
+    /// Regex engine choice: "auto", "rust", or "pcre2" (default: "auto")
+    #[serde(default)]
+    pub engine: EngineChoice,
+
+    /// Let matches span lines (rg --multiline); otherwise each line is
+    /// matched on its own
+    #[serde(default)]
+    pub multiline: bool,
+
+    /// Skip files larger than this size in bytes (None = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_filesize: Option<u64>,
+
+    /// Maximum directory depth to traverse (None = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+
+    /// Encoding of files without a BOM (default: UTF-8)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+
+    /// Write the changes. Without it, the response only previews them
+    #[serde(default)]
+    pub apply: bool,
+
+    /// Unchanged lines of context around each change in the diffs (default: 3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_context: Option<usize>,
+}
+
+// ============================================================================
+// REPLACE OUTPUT
+// ============================================================================
+
+/// The replacements made (or previewed) in one file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplacedFile {
+    pub path: String,
+    pub replacements: u32,
+    /// Unified diff of the change
+    pub diff: String,
+}
+
+/// A matching file that was left alone
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SkippedFile {
+    pub path: String,
+    /// "binary", "unreadable" or "unwritable"
+    pub reason: String,
+    pub message: String,
+}
+
+/// Output from `fs_replace` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsReplaceOutput {
+    pub success: bool,
+    /// The files were written; otherwise this is a preview
+    pub applied: bool,
+    pub files_changed: u32,
+    pub replacements: u32,
+    pub files: Vec<ReplacedFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedFile>,
+    pub message: String,
+    /// Undo journal entry covering every file; pass to `fs_undo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo_id: Option<String>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+// Prompt providers are sealed upstream; the tool takes fs_search's filters,
+// so it shares the search prompts.
+impl ToolArgs for FsReplaceArgs {
+    type Output = FsReplaceOutput;
+    type Prompts = SearchPrompts;
+
+    const NAME: &'static str = FS_REPLACE;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Search and replace across a directory tree with fs_search's filters, previewing by default";
+}
@@ -0,0 +1,85 @@
+//! Schema types for fs_restore tool
+
+use kodegen_config::CATEGORY_FILESYSTEM;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::MoveFilePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool name (not yet defined in `kodegen_config`)
+pub const FS_RESTORE: &str = "fs_restore";
+
+// ============================================================================
+// RESTORE ARGS
+// ============================================================================
+
+/// Arguments for `fs_restore` tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FsRestoreArgs {
+    /// Trash ID to restore (the `trash_id` of a delete, or an ID from a
+    /// listing)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Original path: restores the item most recently deleted from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Restore to this path instead of the original one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+
+    /// When listing, include items every connection deleted, not just this
+    /// one's
+    #[serde(default)]
+    pub all_connections: bool,
+}
+
+// ============================================================================
+// RESTORE OUTPUT
+// ============================================================================
+
+/// One item in the trash
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TrashEntry {
+    /// Trash ID, accepted by `fs_restore` and `fs_empty_trash`
+    pub id: String,
+    /// Where the item was deleted from
+    pub original_path: String,
+    /// When it was deleted (local time, `YYYY-MM-DDThh:mm:ss`)
+    pub deleted_at: String,
+    /// Connection that deleted it
+    pub connection: String,
+    pub is_directory: bool,
+}
+
+/// Output from `fs_restore` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsRestoreOutput {
+    pub success: bool,
+    /// The item that was restored; absent when only listing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restored: Option<TrashEntry>,
+    /// Where it was restored to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restored_to: Option<String>,
+    /// Trash contents, newest first, when called without `id` or `path`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<TrashEntry>,
+    pub message: String,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+// Prompt providers are sealed upstream; restoring moves the item back, so it
+// shares the move-file prompts.
+impl ToolArgs for FsRestoreArgs {
+    type Output = FsRestoreOutput;
+    type Prompts = MoveFilePrompts;
+
+    const NAME: &'static str = FS_RESTORE;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Restore a file or directory from the trash to where it was deleted from, or list the trash";
+}
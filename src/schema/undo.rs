@@ -0,0 +1,60 @@
+//! Schema types for fs_undo tool
+
+use super::JournalEntry;
+use kodegen_config::CATEGORY_FILESYSTEM;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::WriteFilePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool name (not yet defined in `kodegen_config`)
+pub const FS_UNDO: &str = "fs_undo";
+
+// ============================================================================
+// UNDO ARGS
+// ============================================================================
+
+/// Arguments for `fs_undo` tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FsUndoArgs {
+    /// Number of most recent operations to undo (default: 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+
+    /// Undo only this operation (an `undo_id` or an ID from `fs_history`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+
+    /// Restore even if a path changed after the operation, discarding
+    /// those later changes
+    #[serde(default)]
+    pub force: bool,
+}
+
+// ============================================================================
+// UNDO OUTPUT
+// ============================================================================
+
+/// Output from `fs_undo` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsUndoOutput {
+    pub success: bool,
+    /// Operations that were undone, newest first
+    pub undone: Vec<JournalEntry>,
+    pub message: String,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+// Prompt providers are sealed upstream; undo rewrites files, so it shares the
+// write-file prompts.
+impl ToolArgs for FsUndoArgs {
+    type Output = FsUndoOutput;
+    type Prompts = WriteFilePrompts;
+
+    const NAME: &'static str = FS_UNDO;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Undo the most recent journaled write, edit, move or delete operations by restoring the files they changed";
+}
@@ -0,0 +1,99 @@
+//! Schema types for fs_write_file tool
+
+use kodegen_config::{CATEGORY_FILESYSTEM, FS_WRITE_FILE};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::filesystem::WriteFilePrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// WRITE FILE ARGS
+// ============================================================================
+
+fn default_mode() -> String {
+    "rewrite".to_string()
+}
+
+/// Arguments for `fs_write_file` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsWriteFileArgs {
+    /// Path to the file to write
+    pub path: String,
+
+    /// Content to write to the file
+    pub content: String,
+
+    /// Write mode: "rewrite" (default), "append", "create_new" (fail if the
+    /// file exists), "prepend" or "insert_at_line"
+    #[serde(default = "default_mode")]
+    pub mode: String,
+
+    /// For "insert_at_line": insert before this 1-based line
+    /// (one past the last line inserts at the end)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+
+    /// Text encoding to write (e.g. "utf-16le", "shiftjis", "latin1")
+    /// Default: the existing file's BOM encoding, otherwise UTF-8
+    /// A BOM in an existing file is preserved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+
+    /// Only write if the file still matches this `content_hash` or `modified`
+    /// value from `fs_read_file`; otherwise fail with a conflict
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_match: Option<String>,
+
+    /// Validate and return a diff of the change without touching the file
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Lines of context around each change in the diff returned for
+    /// rewrites and dry runs (default: 3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_context: Option<usize>,
+}
+
+// ============================================================================
+// WRITE FILE OUTPUT
+// ============================================================================
+
+/// Output from `fs_write_file` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsWriteFileOutput {
+    pub success: bool,
+    pub path: String,
+    pub bytes_written: u64,
+    pub lines_written: u64,
+    pub mode: String,
+    /// Encoding the content was written in (omitted for plain UTF-8)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// Hash of the file after the write, for the next `if_match`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Undo journal entry for this write; pass to `fs_undo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo_id: Option<String>,
+
+    /// Nothing was written; this response describes what would be
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+
+    /// Unified diff of a rewrite, or of the would-be change in a dry run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION
+// ============================================================================
+
+impl ToolArgs for FsWriteFileArgs {
+    type Output = FsWriteFileOutput;
+    type Prompts = WriteFilePrompts;
+
+    const NAME: &'static str = FS_WRITE_FILE;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_FILESYSTEM;
+    const DESCRIPTION: &'static str = "Write, append, prepend or insert into file contents. Modes: 'rewrite' (overwrite entire file), 'append', 'create_new' (fail if the file exists), 'prepend' and 'insert_at_line', preserving the file's encoding and BOM";
+}
@@ -0,0 +1,392 @@
+//! `fs_replace`: search and replace across a directory tree
+//!
+//! Files are found by a paths-only content search with `fs_search`'s
+//! filters, and each one is rewritten in memory with the same matcher.
+//! Without `apply` the response previews every change as a diff; with it,
+//! all files are written, and a failed write rolls back the ones already
+//! written.
+
+use super::manager::content_search::scan_for_replace;
+use super::rg::PatternMatcher;
+use super::types::{BinaryMode, BoundaryMode, ReturnMode, SearchIn, SearchSessionOptions};
+use crate::atomic_write::write_atomic;
+use crate::diff::{DEFAULT_CONTEXT_LINES, unified_diff};
+use crate::edit_block::pattern::{RegexReplacement, apply_replacements, expand_matches};
+use crate::encoding::{self, TextEncoding};
+use crate::journal::Recorder;
+use crate::schema::{FsReplaceArgs, FsReplaceOutput, ReplacedFile, SkippedFile};
+use crate::validate_path;
+use encoding_rs::{UTF_16BE, UTF_16LE};
+use kodegen_config::shorten_path_for_display;
+use kodegen_mcp_schema::filesystem::SearchPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone)]
+pub struct FsReplaceTool {
+    config_manager: kodegen_config_manager::ConfigManager,
+}
+
+impl FsReplaceTool {
+    #[must_use]
+    pub fn new(config_manager: kodegen_config_manager::ConfigManager) -> Self {
+        Self { config_manager }
+    }
+}
+
+/// One file's new content, checked and ready to write
+struct Planned {
+    path: PathBuf,
+    original: Vec<u8>,
+    bytes: Vec<u8>,
+    replacements: usize,
+    diff: String,
+}
+
+/// Every replacement `matcher` makes in `content`
+///
+/// Without `multiline`, each line is matched on its own (without its line
+/// terminator), as ripgrep searches, so a match never spans lines.
+///
+/// # Errors
+/// Returns the matcher's error message if the search fails
+pub fn file_replacements(
+    matcher: &PatternMatcher,
+    content: &str,
+    replacement: &str,
+    multiline: bool,
+) -> Result<Vec<RegexReplacement>, String> {
+    if multiline {
+        return expand_matches(matcher, content, replacement);
+    }
+    let mut found = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let text = line.strip_suffix('\n').unwrap_or(line);
+        found.extend(
+            expand_matches(matcher, text, replacement)?
+                .into_iter()
+                .map(|found| RegexReplacement {
+                    span: found.span.start + offset..found.span.end + offset,
+                    text: found.text,
+                }),
+        );
+        offset += line.len();
+    }
+    Ok(found)
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for FsReplaceTool {
+    type Args = FsReplaceArgs;
+    type Prompts = SearchPrompts;
+
+    fn name() -> &'static str {
+        crate::schema::FS_REPLACE
+    }
+
+    fn description() -> &'static str {
+        "Search and replace across a directory tree. Takes fs_search's content filters \
+         (file_pattern, type, type_not, case_mode, literal_search, boundary_mode, include_hidden, \
+         no_ignore, engine, multiline, max_depth, max_filesize) and respects .gitignore the same \
+         way. replacement may use $1, ${1} and ${name} for capture groups ($$ is a literal $). \
+         By default nothing is written: the response previews each file's replacements as a \
+         unified diff. Pass apply: true to write every file; if one write fails, the files \
+         already written are restored. Binary files and files that cannot be written are \
+         skipped and listed in skipped. The response carries an undo_id for fs_undo covering \
+         every file. Automatically validates paths."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true // Rewrites files
+    }
+
+    fn idempotent() -> bool {
+        false // A replacement may match its own output
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let root = validate_path(&args.path, &self.config_manager, ctx.pwd()).await?;
+        if fs::metadata(&root).await.is_err() {
+            return Err(McpError::ResourceNotFound(format!(
+                "{} does not exist",
+                args.path
+            )));
+        }
+        let boundary_mode = match args.boundary_mode.as_deref() {
+            None => None,
+            Some("word") => Some(BoundaryMode::Word),
+            Some("line") => Some(BoundaryMode::Line),
+            Some(other) => {
+                return Err(McpError::InvalidArguments(format!(
+                    "Unknown boundary_mode '{other}'; use \"word\", \"line\" or omit it"
+                )));
+            }
+        };
+        let explicit = args
+            .encoding
+            .as_deref()
+            .map(encoding::resolve_label)
+            .transpose()?;
+
+        let options = SearchSessionOptions {
+            root_path: root.to_string_lossy().to_string(),
+            pattern: args.pattern.clone(),
+            search_in: SearchIn::Content,
+            file_pattern: args.file_pattern.clone(),
+            r#type: args.r#type.clone(),
+            type_not: args.type_not.clone(),
+            case_mode: args.case_mode,
+            max_results: None,
+            include_hidden: args.include_hidden,
+            no_ignore: args.no_ignore,
+            context: 0,
+            before_context: None,
+            after_context: None,
+            timeout_ms: None,
+            early_termination: None,
+            literal_search: args.literal_search,
+            pattern_mode: None,
+            boundary_mode,
+            return_only: ReturnMode::Paths,
+            invert_match: false,
+            engine: args.engine,
+            preprocessor: None,
+            preprocessor_globs: Vec::new(),
+            search_zip: false,
+            binary_mode: BinaryMode::Auto,
+            multiline: args.multiline,
+            max_filesize: args.max_filesize,
+            max_depth: args.max_depth,
+            only_matching: false,
+            sort_by: None,
+            sort_direction: None,
+            encoding: args.encoding.clone(),
+        };
+        let client_pwd = ctx.pwd().map(Path::to_path_buf);
+        let scan_root = root.clone();
+        let scan =
+            tokio::task::spawn_blocking(move || scan_for_replace(&options, &scan_root, client_pwd))
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Search task failed: {e}")))?
+                .map_err(|e| McpError::InvalidArguments(format!("Invalid search: {e}")))?;
+
+        let mut skipped: Vec<SkippedFile> = scan
+            .errors
+            .iter()
+            .map(|error| SkippedFile {
+                path: error.path.clone(),
+                reason: "unreadable".to_string(),
+                message: error.message.clone(),
+            })
+            .collect();
+        let mut planned = Vec::new();
+        for path in scan.files {
+            match self.plan(&path, &args, &scan.matcher, explicit, &ctx).await {
+                Ok(Some(plan)) => planned.push(plan),
+                Ok(None) => {}
+                Err(skip) => skipped.push(skip),
+            }
+        }
+
+        let replacements: usize = planned.iter().map(|plan| plan.replacements).sum();
+        let files: Vec<ReplacedFile> = planned
+            .iter()
+            .map(|plan| ReplacedFile {
+                path: plan.path.to_string_lossy().to_string(),
+                replacements: plan.replacements as u32,
+                diff: plan.diff.clone(),
+            })
+            .collect();
+        let details = file_lines(&planned, &skipped, &ctx);
+
+        if !args.apply || planned.is_empty() {
+            let summary = format!(
+                "\x1b[36mPreview: {replacements} replacement(s) in {} file(s)\x1b[0m\n{details}{}",
+                files.len(),
+                if planned.is_empty() {
+                    ""
+                } else {
+                    "\nPass apply: true to write these changes."
+                }
+            );
+            return Ok(ToolResponse::new(
+                summary,
+                FsReplaceOutput {
+                    success: true,
+                    applied: false,
+                    files_changed: 0,
+                    replacements: replacements as u32,
+                    message: format!(
+                        "Preview: {replacements} replacement(s) in {} file(s); nothing was written",
+                        files.len()
+                    ),
+                    files,
+                    skipped,
+                    undo_id: None,
+                },
+            ));
+        }
+
+        // Write everything, rolling back if any write fails
+        let mut undo = Recorder::for_call(
+            &ctx,
+            Self::name(),
+            format!("replace {:?} in {} file(s)", args.pattern, planned.len()),
+        );
+        for plan in &planned {
+            undo.snapshot(&plan.path).await;
+        }
+        let mut written: Vec<&Planned> = Vec::new();
+        for plan in &planned {
+            if let Err(e) = write_atomic(&plan.path, plan.bytes.clone()).await {
+                for done in written.into_iter().rev() {
+                    if let Err(e) = write_atomic(&done.path, done.original.clone()).await {
+                        log::warn!("Rolling back a failed replace: {e}");
+                    }
+                }
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Writing {} failed ({e}); the files already written were restored",
+                    plan.path.display()
+                )));
+            }
+            written.push(plan);
+        }
+        let undo_id = undo.commit().await;
+
+        let summary = format!(
+            "\x1b[33mReplaced {replacements} occurrence(s) in {} file(s)\x1b[0m\n{details}\nUndo: {}",
+            files.len(),
+            undo_id.as_deref().unwrap_or("not journaled")
+        );
+        Ok(ToolResponse::new(
+            summary,
+            FsReplaceOutput {
+                success: true,
+                applied: true,
+                files_changed: files.len() as u32,
+                replacements: replacements as u32,
+                message: format!(
+                    "Replaced {replacements} occurrence(s) in {} file(s)",
+                    files.len()
+                ),
+                files,
+                skipped,
+                undo_id,
+            },
+        ))
+    }
+}
+
+impl FsReplaceTool {
+    /// Rewrite one file in memory; `None` if the replacement changes nothing
+    async fn plan(
+        &self,
+        path: &Path,
+        args: &FsReplaceArgs,
+        matcher: &PatternMatcher,
+        explicit: Option<&'static encoding_rs::Encoding>,
+        ctx: &ToolExecutionContext,
+    ) -> Result<Option<Planned>, SkippedFile> {
+        let skip = |reason: &str, message: String| SkippedFile {
+            path: path.to_string_lossy().to_string(),
+            reason: reason.to_string(),
+            message,
+        };
+
+        let valid = validate_path(&path.to_string_lossy(), &self.config_manager, ctx.pwd())
+            .await
+            .map_err(|e| skip("unwritable", e.to_string()))?;
+        let original = fs::read(&valid)
+            .await
+            .map_err(|e| skip("unreadable", e.to_string()))?;
+        let text_encoding = encoding::detect(&original, explicit);
+        if !is_utf16(text_encoding) && original.contains(&0) {
+            return Err(skip("binary", "contains NUL bytes".to_string()));
+        }
+        let content = encoding::decode(&original, text_encoding).map_err(|_| {
+            skip(
+                "binary",
+                format!("not valid {}", text_encoding.encoding.name()),
+            )
+        })?;
+
+        let found = file_replacements(matcher, &content, &args.replacement, args.multiline)
+            .map_err(|e| skip("unreadable", e))?;
+        let (new_content, _) = apply_replacements(&content, &found);
+        if new_content == content {
+            return Ok(None);
+        }
+        let bytes = encoding::encode(&new_content, text_encoding)
+            .map_err(|e| skip("unwritable", e.to_string()))?;
+
+        // Opening for writing truncates nothing, but fails on read-only files
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&valid)
+            .await
+            .map_err(|e| skip("unwritable", e.to_string()))?;
+
+        let display = shorten_path_for_display(&valid, ctx.git_root());
+        let diff = unified_diff(
+            &display,
+            &content,
+            &new_content,
+            args.diff_context.unwrap_or(DEFAULT_CONTEXT_LINES),
+        );
+        Ok(Some(Planned {
+            path: valid,
+            original,
+            bytes,
+            replacements: found.len(),
+            diff,
+        }))
+    }
+}
+
+/// UTF-16 text is full of NUL bytes, so it is only binary if it fails to decode
+fn is_utf16(text_encoding: TextEncoding) -> bool {
+    text_encoding.encoding == UTF_16LE || text_encoding.encoding == UTF_16BE
+}
+
+/// One line per changed and skipped file, followed by the diffs
+fn file_lines(planned: &[Planned], skipped: &[SkippedFile], ctx: &ToolExecutionContext) -> String {
+    let mut lines: Vec<String> = planned
+        .iter()
+        .map(|plan| {
+            format!(
+                "  M {} ({} replacement(s))",
+                shorten_path_for_display(&plan.path, ctx.git_root()),
+                plan.replacements
+            )
+        })
+        .collect();
+    lines.extend(skipped.iter().map(|skip| {
+        format!(
+            "  ! {} skipped ({}: {})",
+            shorten_path_for_display(Path::new(&skip.path), ctx.git_root()),
+            skip.reason,
+            skip.message
+        )
+    }));
+    for plan in planned {
+        lines.push(plan.diff.clone());
+    }
+    lines.join("\n")
+}
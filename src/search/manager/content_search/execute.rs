@@ -78,94 +78,14 @@ pub fn execute(
 
     // Build LowArgs from SearchSessionOptions
     use super::super::super::rg::flags::hiargs::HiArgs;
-    use super::super::super::rg::flags::lowargs::{
-        BinaryMode as RgBinaryMode, BoundaryMode as RgBoundaryMode, ContextMode, EncodingMode,
-        LowArgs, Mode, PatternSource, SearchMode,
-    };
-    use super::super::super::types::{BinaryMode, BoundaryMode};
-    use super::super::utils::{build_type_changes, convert_case_mode};
-
-    let mut context = ContextMode::default();
-
-    // Set -C (context) first
-    if options.context > 0 {
-        context.set_both(options.context as usize);
-    }
-
-    // Override with -B (before_context) if specified
-    if let Some(before) = options.before_context {
-        context.set_before(before as usize);
-    }
-
-    // Override with -A (after_context) if specified
-    if let Some(after) = options.after_context {
-        context.set_after(after as usize);
-    }
-
-    // Convert MCP type params to ripgrep TypeChange format
-    let type_changes = build_type_changes(options);
-
-    // Map MCP BinaryMode to ripgrep's internal BinaryMode
-    // Matches ripgrep's --binary and -a/--text flags
-    let binary_mode = match options.binary_mode {
-        BinaryMode::Auto => RgBinaryMode::Auto, // Default: skip binaries
-        BinaryMode::Binary => RgBinaryMode::SearchAndSuppress, // --binary: search but suppress
-        BinaryMode::Text => RgBinaryMode::AsText, // -a/--text: treat as text
-    };
-
-    // Convert encoding string to EncodingMode for rg
-    let encoding_mode = match options.encoding.as_deref() {
-        None | Some("auto") => EncodingMode::Auto,
-        Some("none") => EncodingMode::Disabled,
-        Some(enc_str) => match grep::searcher::Encoding::new(enc_str) {
-            Ok(enc) => EncodingMode::Some(enc),
-            Err(e) => {
-                log::warn!("Invalid encoding '{enc_str}': {e}, using auto");
-                EncodingMode::Auto
-            }
-        },
-    };
+    use super::super::super::rg::flags::lowargs::{Mode, SearchMode};
 
-    log::debug!(
-        "content_search: case_mode from options = {:?}",
-        options.case_mode
+    let mut low_args = low_args(
+        options,
+        root,
+        ctx.return_only,
+        options.literal_search || use_literal_fallback,
     );
-    let mut low_args = LowArgs {
-        patterns: vec![PatternSource::Regexp(options.pattern.clone())],
-        positional: vec![root.as_os_str().to_owned()],
-        case: convert_case_mode(options.case_mode),
-        boundary: options.boundary_mode.map(|mode| match mode {
-            BoundaryMode::Word => RgBoundaryMode::Word,
-            BoundaryMode::Line => RgBoundaryMode::Line,
-        }),
-        fixed_strings: options.literal_search || use_literal_fallback,
-        context,
-        max_count: if ctx.return_only == ReturnMode::Paths {
-            Some(1) // Limit to 1 match per file for Paths mode (optimization)
-        } else {
-            options.max_results.map(u64::from)
-        },
-        max_filesize: options.max_filesize,
-        max_depth: options.max_depth,
-        hidden: options.include_hidden,
-        line_number: Some(true),
-        invert_match: options.invert_match,
-        mode: Mode::Search(SearchMode::Json),
-        only_matching: options.only_matching,
-        type_changes,
-        multiline: options.multiline,
-        multiline_dotall: options.multiline,
-        binary: binary_mode,
-        encoding: encoding_mode,
-        engine: convert_engine_choice(options.engine),
-        // Match ripgrep's --no-ignore flag behavior exactly
-        no_ignore_vcs: options.no_ignore,
-        no_ignore_exclude: options.no_ignore,
-        no_ignore_global: options.no_ignore,
-        no_ignore_parent: options.no_ignore,
-        no_ignore_dot: options.no_ignore,
-        ..Default::default()
-    };
 
     // Bridge SearchMode to ReturnMode for ripgrep CLI compatibility
     // Maps ripgrep's SearchMode enum variants to MCP's ReturnMode
@@ -298,6 +218,126 @@ pub fn execute(
     ctx.is_complete = true;
 }
 
+/// Build ripgrep's low-level arguments for a content search of `root`
+///
+/// `fixed_strings` is passed separately from `options.literal_search` so
+/// `execute` can fall back to a literal search for a pattern that does not
+/// compile as a regex.
+pub(super) fn low_args(
+    options: &super::super::super::types::SearchSessionOptions,
+    root: &std::path::Path,
+    return_only: ReturnMode,
+    fixed_strings: bool,
+) -> super::super::super::rg::flags::lowargs::LowArgs {
+    use super::super::super::rg::flags::lowargs::{
+        BinaryMode as RgBinaryMode, BoundaryMode as RgBoundaryMode, ContextMode, EncodingMode,
+        LowArgs, Mode, PatternSource, SearchMode,
+    };
+    use super::super::super::types::{BinaryMode, BoundaryMode};
+    use super::super::utils::{build_type_changes, convert_case_mode};
+
+    let mut context = ContextMode::default();
+
+    // Set -C (context) first
+    if options.context > 0 {
+        context.set_both(options.context as usize);
+    }
+
+    // Override with -B (before_context) if specified
+    if let Some(before) = options.before_context {
+        context.set_before(before as usize);
+    }
+
+    // Override with -A (after_context) if specified
+    if let Some(after) = options.after_context {
+        context.set_after(after as usize);
+    }
+
+    // Convert MCP type params to ripgrep TypeChange format
+    let type_changes = build_type_changes(options);
+
+    // Map MCP BinaryMode to ripgrep's internal BinaryMode
+    // Matches ripgrep's --binary and -a/--text flags
+    let binary_mode = match options.binary_mode {
+        BinaryMode::Auto => RgBinaryMode::Auto, // Default: skip binaries
+        BinaryMode::Binary => RgBinaryMode::SearchAndSuppress, // --binary: search but suppress
+        BinaryMode::Text => RgBinaryMode::AsText, // -a/--text: treat as text
+    };
+
+    // Convert encoding string to EncodingMode for rg
+    let encoding_mode = match options.encoding.as_deref() {
+        None | Some("auto") => EncodingMode::Auto,
+        Some("none") => EncodingMode::Disabled,
+        Some(enc_str) => match crate::encoding::resolve_label(enc_str)
+            .map_err(|e| e.to_string())
+            .and_then(|enc| {
+                grep::searcher::Encoding::new(enc.name()).map_err(|e| e.to_string())
+            }) {
+            Ok(enc) => EncodingMode::Some(enc),
+            Err(e) => {
+                log::warn!("Invalid encoding '{enc_str}': {e}, using auto");
+                EncodingMode::Auto
+            }
+        },
+    };
+
+    // file_pattern may hold several globs separated by `|` ("*.js|*.ts")
+    let globs = options
+        .file_pattern
+        .as_deref()
+        .map(|pattern| {
+            pattern
+                .split('|')
+                .map(str::trim)
+                .filter(|glob| !glob.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    log::debug!(
+        "content_search: case_mode from options = {:?}",
+        options.case_mode
+    );
+    LowArgs {
+        patterns: vec![PatternSource::Regexp(options.pattern.clone())],
+        positional: vec![root.as_os_str().to_owned()],
+        case: convert_case_mode(options.case_mode),
+        boundary: options.boundary_mode.map(|mode| match mode {
+            BoundaryMode::Word => RgBoundaryMode::Word,
+            BoundaryMode::Line => RgBoundaryMode::Line,
+        }),
+        fixed_strings,
+        globs,
+        context,
+        max_count: if return_only == ReturnMode::Paths {
+            Some(1) // Limit to 1 match per file for Paths mode (optimization)
+        } else {
+            options.max_results.map(u64::from)
+        },
+        max_filesize: options.max_filesize,
+        max_depth: options.max_depth,
+        hidden: options.include_hidden,
+        line_number: Some(true),
+        invert_match: options.invert_match,
+        mode: Mode::Search(SearchMode::Json),
+        only_matching: options.only_matching,
+        type_changes,
+        multiline: options.multiline,
+        multiline_dotall: options.multiline,
+        binary: binary_mode,
+        encoding: encoding_mode,
+        engine: convert_engine_choice(options.engine),
+        // Match ripgrep's --no-ignore flag behavior exactly
+        no_ignore_vcs: options.no_ignore,
+        no_ignore_exclude: options.no_ignore,
+        no_ignore_global: options.no_ignore,
+        no_ignore_parent: options.no_ignore,
+        no_ignore_dot: options.no_ignore,
+        ..Default::default()
+    }
+}
+
 /// Convert MCP API `Engine` to ripgrep's internal `Engine`
 fn convert_engine_choice(
     engine: super::super::super::types::Engine,
@@ -6,10 +6,12 @@
 mod builder;
 mod error_visitor;
 mod execute;
+mod replace;
 mod visitor_core;
 mod visitor_impl;
 
 pub(super) use builder::ContentSearchBuilder;
 pub(super) use error_visitor::ErrorVisitor;
 pub use execute::execute;
+pub use replace::{ReplaceScan, scan_for_replace};
 pub(super) use visitor_core::ContentSearchVisitor;
@@ -0,0 +1,78 @@
+//! File discovery and matcher for `fs_replace`
+//!
+//! A replacement honours exactly the filters and matching rules of
+//! `fs_search`: the files come from a paths-only content search, and the
+//! matcher is built from the same ripgrep arguments that search ran with.
+
+use super::super::super::rg::PatternMatcher;
+use super::super::super::rg::flags::hiargs::HiArgs;
+use super::super::super::types::{PatternMode, ReturnMode, SearchError, SearchSessionOptions};
+use super::super::context::SearchContext;
+use crate::edit_block::pattern::reject_empty_matches;
+use std::path::{Path, PathBuf};
+
+/// The files a replacement touches and the matcher that finds its matches
+pub struct ReplaceScan {
+    /// Files with at least one match, in path order
+    pub files: Vec<PathBuf>,
+    /// Matcher compiled exactly as the search compiled it
+    pub matcher: PatternMatcher,
+    /// Files the search could not read
+    pub errors: Vec<SearchError>,
+}
+
+/// Find the files under `root` that `options` matches
+///
+/// Unlike `fs_search`, a pattern that does not compile as a regex is an
+/// error instead of silently becoming a literal search, and so is one that
+/// matches the empty string.
+///
+/// # Errors
+/// Returns the ripgrep error if the arguments or the pattern are invalid
+pub fn scan_for_replace(
+    options: &SearchSessionOptions,
+    root: &Path,
+    client_pwd: Option<PathBuf>,
+) -> Result<ReplaceScan, String> {
+    let mut options = options.clone();
+    options.return_only = ReturnMode::Paths;
+    options.max_results = None;
+    options.invert_match = false;
+    options.pattern_mode = Some(if options.literal_search {
+        PatternMode::Substring
+    } else {
+        PatternMode::Regex
+    });
+
+    let low_args =
+        super::execute::low_args(&options, root, ReturnMode::Paths, options.literal_search);
+    let matcher = HiArgs::from_low_args(low_args, client_pwd.as_deref())
+        .and_then(|hi_args| hi_args.matcher())
+        .map_err(|e| e.to_string())?;
+    reject_empty_matches(&matcher, &options.pattern)?;
+
+    let mut ctx = SearchContext::new(usize::MAX, ReturnMode::Paths, client_pwd);
+    super::execute(&options, &root.to_path_buf(), &mut ctx);
+    if ctx.is_error {
+        return Err(ctx
+            .error
+            .take()
+            .unwrap_or_else(|| "Search failed".to_string()));
+    }
+
+    let mut files: Vec<PathBuf> = ctx
+        .results()
+        .blocking_read()
+        .iter()
+        .map(|result| PathBuf::from(&result.file))
+        .collect();
+    files.sort();
+    files.dedup();
+    let errors = ctx.errors().blocking_read().clone();
+
+    Ok(ReplaceScan {
+        files,
+        matcher,
+        errors,
+    })
+}
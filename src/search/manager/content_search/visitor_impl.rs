@@ -77,10 +77,11 @@ impl ParallelVisitor for ContentSearchVisitor {
                             result.created = created;
                         }
 
-                        // Track unique files for total_files counter (all modes need this)
-                        // Check if this is the first time seeing this file
+                        // Track unique files for total_files counter in Matches mode
+                        // (Paths mode deduplicates through seen_files itself below, so
+                        // marking the file here would make it skip every file)
                         let mut first_result_from_file = false;
-                        if !results.is_empty() {
+                        if self.return_only == ReturnMode::Matches && !results.is_empty() {
                             let mut seen = self.seen_files.blocking_write();
                             if !seen.contains(&results[0].file) {
                                 seen.insert(results[0].file.clone());
@@ -89,7 +90,7 @@ impl ParallelVisitor for ContentSearchVisitor {
                         }
 
                         // If first result from this file, increment total_files (for Matches mode)
-                        if first_result_from_file && self.return_only == ReturnMode::Matches {
+                        if first_result_from_file {
                             self.total_files.fetch_add(1, Ordering::SeqCst);
                         }
 
@@ -96,6 +96,9 @@ pub(super) fn configure_walker(
         .git_exclude(!hi_args.no_ignore_vcs && !hi_args.no_ignore_exclude)
         .threads(0);
 
+    // file_pattern globs (rg --glob)
+    walker.overrides(hi_args.globs.clone());
+
     if let Some(size) = hi_args.max_filesize {
         walker.max_filesize(Some(size));
     }
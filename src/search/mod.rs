@@ -1,3 +1,4 @@
+pub mod fs_replace;
 pub mod fs_search;
 pub mod manager;
 pub mod registry;
@@ -6,6 +7,7 @@ pub mod session;
 pub mod sorting;
 pub mod types;
 
+pub use fs_replace::*;
 pub use fs_search::*;
 pub use manager::*;
 pub use registry::*;
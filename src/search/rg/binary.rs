@@ -0,0 +1,68 @@
+//! Binary detection shared by `fs_search` and the file readers
+//!
+//! `fs_search` in `BinaryMode::Auto` quits a file at the first NUL byte the
+//! searcher sees, after BOM sniffing has transcoded UTF-16 to UTF-8. Other
+//! tools ask the same searcher, so they never disagree with it about which
+//! files are binary.
+
+use grep::matcher::{Match, Matcher, NoCaptures, NoError};
+use grep::searcher::{BinaryDetection, Searcher, SearcherBuilder, Sink, SinkMatch};
+
+/// The byte whose presence marks data as binary (ripgrep's NUL heuristic)
+pub const BINARY_BYTE: u8 = b'\x00';
+
+/// Binary detection for files found by walking a directory
+#[must_use]
+pub fn implicit_detection() -> BinaryDetection {
+    BinaryDetection::quit(BINARY_BYTE)
+}
+
+/// Whether `fs_search` would treat data starting with `bytes` as binary
+#[must_use]
+pub fn is_binary(bytes: &[u8]) -> bool {
+    let mut searcher = SearcherBuilder::new()
+        .binary_detection(implicit_detection())
+        .build();
+    let mut sink = BinarySink { binary: false };
+    // The matcher never matches and the sink never fails
+    let _ = searcher.search_slice(NeverMatch, bytes, &mut sink);
+    sink.binary
+}
+
+/// Matcher that finds nothing, so the searcher only scans for binary data
+struct NeverMatch;
+
+impl Matcher for NeverMatch {
+    type Captures = NoCaptures;
+    type Error = NoError;
+
+    fn find_at(&self, _haystack: &[u8], _at: usize) -> Result<Option<Match>, NoError> {
+        Ok(None)
+    }
+
+    fn new_captures(&self) -> Result<NoCaptures, NoError> {
+        Ok(NoCaptures::new())
+    }
+}
+
+/// Records whether the searcher reported binary data
+struct BinarySink {
+    binary: bool,
+}
+
+impl Sink for BinarySink {
+    type Error = std::io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, _mat: &SinkMatch<'_>) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    fn binary_data(
+        &mut self,
+        _searcher: &Searcher,
+        _binary_byte_offset: u64,
+    ) -> Result<bool, Self::Error> {
+        self.binary = true;
+        Ok(false)
+    }
+}
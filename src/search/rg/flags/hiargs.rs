@@ -46,6 +46,7 @@ pub(crate) struct HiArgs {
     encoding: EncodingMode,
     engine: Engine,
     fixed_strings: bool,
+    pub(crate) globs: ignore::overrides::Override,
     pub(crate) hidden: bool,
     invert_match: bool,
     line_number: bool,
@@ -106,6 +107,7 @@ impl HiArgs {
 
         let binary = BinaryDetection::from_low_args(&state, &low);
         let types = helpers::types(&low)?;
+        let globs = helpers::globs(&state, &low)?;
         let pre_globs = helpers::preprocessor_globs(&state, &low)?;
 
         let line_number = low.line_number.unwrap_or(false);
@@ -146,6 +148,7 @@ impl HiArgs {
             encoding: low.encoding,
             engine: low.engine,
             fixed_strings: low.fixed_strings,
+            globs,
             hidden: low.hidden,
             invert_match: low.invert_match,
             line_number,
@@ -26,6 +26,28 @@ pub(crate) fn types(low: &LowArgs) -> anyhow::Result<ignore::types::Types> {
     Ok(builder.build()?)
 }
 
+/// Builds the glob overrides that filter searched files (via `-g/--glob`
+/// and `--iglob`).
+pub(crate) fn globs(state: &State, low: &LowArgs) -> anyhow::Result<ignore::overrides::Override> {
+    if low.globs.is_empty() && low.iglobs.is_empty() {
+        return Ok(ignore::overrides::Override::empty());
+    }
+    let mut builder = ignore::overrides::OverrideBuilder::new(&state.cwd);
+    // Make all globs case insensitive with --glob-case-insensitive.
+    if low.glob_case_insensitive {
+        builder.case_insensitive(true)?;
+    }
+    for glob in &low.globs {
+        builder.add(glob)?;
+    }
+    // This only enables case insensitivity for subsequent globs.
+    builder.case_insensitive(true)?;
+    for glob in &low.iglobs {
+        builder.add(glob)?;
+    }
+    Ok(builder.build()?)
+}
+
 /// Builds a glob matcher for all of the preprocessor globs (via `--pre-glob`).
 pub(crate) fn preprocessor_globs(
     state: &State,
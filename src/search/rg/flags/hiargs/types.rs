@@ -10,6 +10,7 @@ use std::{
 };
 
 use crate::search::rg::flags::lowargs::{BinaryMode, LowArgs, Mode, PatternSource};
+use crate::search::rg::binary::{BINARY_BYTE, implicit_detection};
 
 /// State that only needs to be computed once during argument parsing.
 ///
@@ -210,14 +211,14 @@ impl BinaryDetection {
         let explicit = if none {
             grep::searcher::BinaryDetection::none()
         } else {
-            grep::searcher::BinaryDetection::convert(b'\x00')
+            grep::searcher::BinaryDetection::convert(BINARY_BYTE)
         };
         let implicit = if none {
             grep::searcher::BinaryDetection::none()
         } else if convert {
-            grep::searcher::BinaryDetection::convert(b'\x00')
+            grep::searcher::BinaryDetection::convert(BINARY_BYTE)
         } else {
-            grep::searcher::BinaryDetection::quit(b'\x00')
+            implicit_detection()
         };
         BinaryDetection { explicit, implicit }
     }
@@ -1,5 +1,6 @@
 // Ripgrep integration for MCP - configuration types and matcher building
 // Exposes ripgrep's powerful regex/PCRE2 matching through MCP JSON interface
+pub mod binary;
 pub mod flags;
 pub mod haystack;
 pub(crate) mod json_output;
@@ -0,0 +1,119 @@
+//! `.trashinfo` files as the freedesktop.org Trash specification defines them
+
+use chrono::NaiveDateTime;
+use std::path::{Path, PathBuf};
+
+/// Key kodegen adds to the entries it writes; entries without it belong to
+/// someone else
+const CONNECTION_KEY: &str = "X-Kodegen-Connection";
+
+/// `DeletionDate` format: local time, no zone
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// A parsed `.trashinfo` file
+#[derive(Debug)]
+pub(super) struct Info {
+    /// Original path as written: absolute, or relative to the top directory
+    /// of the trash's mount
+    pub path: PathBuf,
+    pub deleted_at: NaiveDateTime,
+    /// `None` for entries kodegen did not write
+    pub connection: Option<String>,
+}
+
+/// Contents of the info file for an item deleted from `path`
+pub(super) fn format(path: &Path, deleted_at: NaiveDateTime, connection: &str) -> String {
+    // A line break in the value would end the key
+    let connection: String = connection
+        .chars()
+        .map(|c| if c.is_control() { '_' } else { c })
+        .collect();
+    format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n{CONNECTION_KEY}={connection}\n",
+        encode(path),
+        deleted_at.format(DATE_FORMAT)
+    )
+}
+
+/// Parse an info file; `None` if it lacks the `[Trash Info]` group, a valid
+/// `Path` or a valid `DeletionDate`
+pub(super) fn parse(text: &str) -> Option<Info> {
+    let mut in_group = false;
+    let (mut path, mut deleted_at, mut connection) = (None, None, None);
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_group = line == "[Trash Info]";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=').filter(|_| in_group) else {
+            continue;
+        };
+        match key.trim() {
+            "Path" => path = decode(value.trim()),
+            "DeletionDate" => {
+                deleted_at = NaiveDateTime::parse_from_str(value.trim(), DATE_FORMAT).ok();
+            }
+            CONNECTION_KEY => connection = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    Some(Info {
+        path: path?,
+        deleted_at: deleted_at?,
+        connection,
+    })
+}
+
+/// Percent-encode a path the way URIs are, keeping `/`
+fn encode(path: &Path) -> String {
+    let mut encoded = String::new();
+    for &byte in path_bytes(path).iter() {
+        if byte.is_ascii_alphanumeric() || b"-_.~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+fn decode(value: &str) -> Option<PathBuf> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    if bytes.is_empty() {
+        return None;
+    }
+    path_from_bytes(bytes)
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> std::borrow::Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().into()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> std::borrow::Cow<'_, [u8]> {
+    path.to_string_lossy().into_owned().into_bytes().into()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+    use std::os::unix::ffi::OsStringExt;
+    Some(std::ffi::OsString::from_vec(bytes).into())
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
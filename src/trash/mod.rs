@@ -0,0 +1,457 @@
+//! Trash for `fs_delete_file` and `fs_delete_directory`
+//!
+//! Deleted files and directories are renamed into a trash directory on the
+//! same filesystem instead of being removed; `fs_restore` puts them back and
+//! `fs_empty_trash` removes them for good. The layout follows the
+//! freedesktop.org Trash specification: the item goes to `files/<id>` and
+//! `info/<id>.trashinfo` records its original path and deletion time, so
+//! desktop file managers can show and restore kodegen's deletions too.
+//!
+//! On Linux the home trash (`$XDG_DATA_HOME/Trash`) takes items on the home
+//! filesystem and items elsewhere go to `$topdir/.Trash-$uid` at the top of
+//! their mount. Other platforms use a kodegen-managed trash under the state
+//! directory. An item that no trash can take without copying is deleted
+//! permanently, as before.
+//!
+//! Only entries kodegen wrote (their info file carries an
+//! `X-Kodegen-Connection` key) are listed, restored or emptied; whatever the
+//! user trashed from a file manager is left alone.
+
+mod info;
+
+use crate::atomic_write::rename_noreplace;
+use crate::schema::TrashEntry;
+use chrono::{Local, NaiveDateTime};
+use kodegen_config::KodegenConfig;
+use kodegen_mcp_schema::McpError;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// ============================================================================
+// ITEMS
+// ============================================================================
+
+/// One item kodegen moved to a trash
+#[derive(Debug, Clone)]
+pub struct TrashedItem {
+    /// Name under `files/` and `info/`; accepted by `fs_restore`
+    pub id: String,
+    pub original_path: PathBuf,
+    /// Local time of the deletion, as the trash specification records it
+    pub deleted_at: NaiveDateTime,
+    /// Connection that deleted it
+    pub connection: String,
+    pub is_directory: bool,
+    /// Trash directory holding the item
+    root: PathBuf,
+}
+
+impl TrashedItem {
+    /// Where the item is now
+    #[must_use]
+    pub fn location(&self) -> PathBuf {
+        self.root.join("files").join(&self.id)
+    }
+
+    fn info_path(&self) -> PathBuf {
+        self.root
+            .join("info")
+            .join(format!("{}.trashinfo", self.id))
+    }
+}
+
+impl From<&TrashedItem> for TrashEntry {
+    fn from(item: &TrashedItem) -> Self {
+        Self {
+            id: item.id.clone(),
+            original_path: item.original_path.to_string_lossy().to_string(),
+            deleted_at: item.deleted_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            connection: item.connection.clone(),
+            is_directory: item.is_directory,
+        }
+    }
+}
+
+// ============================================================================
+// TRASH
+// ============================================================================
+
+/// The home trash plus every top-directory trash kodegen has used
+#[derive(Debug, Clone)]
+pub struct Trash {
+    home: PathBuf,
+    /// File listing the other trash directories, one per line
+    registry: PathBuf,
+}
+
+impl Trash {
+    #[must_use]
+    pub fn new(home: impl Into<PathBuf>, registry: impl Into<PathBuf>) -> Self {
+        Self {
+            home: home.into(),
+            registry: registry.into(),
+        }
+    }
+
+    /// The platform's home trash, or `KODEGEN_TRASH_DIR` if set
+    ///
+    /// Returns `None` when `KODEGEN_TRASH=0` or the trash or state directory
+    /// cannot be determined.
+    #[must_use]
+    pub fn open_default() -> Option<Self> {
+        if std::env::var("KODEGEN_TRASH").is_ok_and(|v| matches!(v.trim(), "0" | "false" | "no")) {
+            return None;
+        }
+        let state = KodegenConfig::state_dir().ok()?;
+        let home = match std::env::var_os("KODEGEN_TRASH_DIR").filter(|v| !v.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => default_home(&state)?,
+        };
+        Some(Self::new(home, state.join("trash-roots")))
+    }
+
+    #[must_use]
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    /// Move `path` into the trash on its filesystem, noting `connection` as
+    /// the deleter
+    ///
+    /// Returns `None` when no trash on that filesystem can take it; the
+    /// caller then deletes it permanently.
+    ///
+    /// # Errors
+    /// Returns the I/O error of a rename that failed for another reason.
+    pub async fn trash(&self, path: &Path, connection: &str) -> io::Result<Option<TrashedItem>> {
+        let trash = self.clone();
+        let path = path.to_path_buf();
+        let connection = connection.to_string();
+        tokio::task::spawn_blocking(move || trash.trash_blocking(&path, &connection))
+            .await
+            .map_err(io::Error::other)?
+    }
+
+    fn trash_blocking(&self, path: &Path, connection: &str) -> io::Result<Option<TrashedItem>> {
+        let metadata = fs::symlink_metadata(path)?;
+        let Some(root) = self.root_for(path, &metadata) else {
+            return Ok(None);
+        };
+        // Deleting a directory that holds the trash cannot go through it
+        if root.starts_with(path) {
+            return Ok(None);
+        }
+        if let Err(e) = create_layout(&root) {
+            log::debug!("Trash {} is unusable: {e}", root.display());
+            return Ok(None);
+        }
+        if root != self.home {
+            self.register(&root);
+        }
+
+        let name = path
+            .file_name()
+            .map_or_else(|| "item".into(), |n| n.to_string_lossy());
+        let item = TrashedItem {
+            id: format!("{name}.{}", &uuid::Uuid::new_v4().simple().to_string()[..8]),
+            original_path: path.to_path_buf(),
+            deleted_at: Local::now().naive_local(),
+            connection: connection.to_string(),
+            is_directory: metadata.is_dir(),
+            root,
+        };
+
+        // The specification has the info file written first, claiming the name
+        let recorded = recorded_path(&item.root, &self.home, path);
+        let info = info::format(recorded, item.deleted_at, connection);
+        fs::File::create_new(item.info_path())?.write_all(info.as_bytes())?;
+
+        match fs::rename(path, item.location()) {
+            Ok(()) => Ok(Some(item)),
+            Err(e) => {
+                let _ = fs::remove_file(item.info_path());
+                if e.kind() == io::ErrorKind::CrossesDevices {
+                    Ok(None)
+                } else {
+                    Err(e)
+                }
+            }
+        }
+    }
+
+    /// kodegen's items in every trash it has used, newest first; only those
+    /// `connection` deleted when given
+    ///
+    /// # Errors
+    /// Returns `McpError::Io` if a trash directory cannot be listed.
+    pub async fn list(&self, connection: Option<&str>) -> Result<Vec<TrashedItem>, McpError> {
+        let trash = self.clone();
+        let connection = connection.map(str::to_string);
+        tokio::task::spawn_blocking(move || {
+            let mut items: Vec<TrashedItem> = trash
+                .entries()?
+                .into_iter()
+                .filter(|item| fs::symlink_metadata(item.location()).is_ok())
+                .filter(|item| connection.as_ref().is_none_or(|c| *c == item.connection))
+                .collect();
+            items.sort_by(|a, b| {
+                b.deleted_at
+                    .cmp(&a.deleted_at)
+                    .then_with(|| b.id.cmp(&a.id))
+            });
+            Ok(items)
+        })
+        .await
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Trash task failed: {e}")))?
+    }
+
+    /// Move `item` back out of the trash to `to`
+    ///
+    /// # Errors
+    /// Returns `McpError::InvalidArguments` when something already exists at
+    /// `to`, or `McpError::Io` when the rename fails.
+    pub async fn restore(&self, item: &TrashedItem, to: &Path) -> Result<(), McpError> {
+        let item = item.clone();
+        let to = to.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            rename_noreplace(&item.location(), &to).map_err(|e| match e.kind() {
+                io::ErrorKind::AlreadyExists => McpError::InvalidArguments(format!(
+                    "{} already exists. Nothing was restored; move or delete it first, or \
+                     pass a destination.",
+                    to.display()
+                )),
+                _ => McpError::Io(e),
+            })?;
+            if let Err(e) = fs::remove_file(item.info_path()) {
+                log::warn!("Failed to remove trash info for {}: {e}", item.id);
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Trash task failed: {e}")))?
+    }
+
+    /// Permanently remove kodegen's trashed items: those in `ids`, or all of
+    /// them (only `connection`'s when given) deleted longer than `older_than`
+    /// ago
+    ///
+    /// Returns the items removed. Info files whose item is gone (moved back
+    /// by `fs_undo`) are cleaned up along the way.
+    ///
+    /// # Errors
+    /// Returns `McpError::ResourceNotFound` when an ID is not in the trash;
+    /// nothing is removed then.
+    pub async fn empty(
+        &self,
+        connection: Option<&str>,
+        ids: &[String],
+        older_than: Option<Duration>,
+    ) -> Result<Vec<TrashedItem>, McpError> {
+        let trash = self.clone();
+        let connection = connection.map(str::to_string);
+        let ids = ids.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let (items, orphans): (Vec<TrashedItem>, Vec<TrashedItem>) = trash
+                .entries()?
+                .into_iter()
+                .partition(|item| fs::symlink_metadata(item.location()).is_ok());
+            for orphan in orphans {
+                let _ = fs::remove_file(orphan.info_path());
+            }
+
+            let now = Local::now().naive_local();
+            let targets: Vec<TrashedItem> = if ids.is_empty() {
+                items
+                    .into_iter()
+                    .filter(|item| connection.as_ref().is_none_or(|c| *c == item.connection))
+                    .filter(|item| {
+                        older_than.is_none_or(|age| {
+                            (now - item.deleted_at).to_std().unwrap_or_default() >= age
+                        })
+                    })
+                    .collect()
+            } else {
+                if let Some(missing) = ids.iter().find(|id| !items.iter().any(|i| i.id == **id)) {
+                    return Err(McpError::ResourceNotFound(format!(
+                        "No item {missing} in the trash. Nothing was removed."
+                    )));
+                }
+                items
+                    .into_iter()
+                    .filter(|item| ids.contains(&item.id))
+                    .collect()
+            };
+
+            for item in &targets {
+                let location = item.location();
+                if fs::symlink_metadata(&location)?.is_dir() {
+                    fs::remove_dir_all(&location)?;
+                } else {
+                    fs::remove_file(&location)?;
+                }
+                fs::remove_file(item.info_path())?;
+            }
+            Ok(targets)
+        })
+        .await
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Trash task failed: {e}")))?
+    }
+
+    /// Every entry kodegen wrote, including those whose item is gone
+    fn entries(&self) -> io::Result<Vec<TrashedItem>> {
+        let mut roots = vec![self.home.clone()];
+        if let Ok(registry) = fs::read_to_string(&self.registry) {
+            roots.extend(
+                registry
+                    .lines()
+                    .filter(|l| !l.is_empty())
+                    .map(PathBuf::from),
+            );
+        }
+
+        let mut items = Vec::new();
+        for root in roots {
+            let infos = match fs::read_dir(root.join("info")) {
+                Ok(infos) => infos,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in infos.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let Some(id) = file_name.strip_suffix(".trashinfo") else {
+                    continue;
+                };
+                let Some(info) = fs::read_to_string(entry.path())
+                    .ok()
+                    .and_then(|text| info::parse(&text))
+                else {
+                    continue;
+                };
+                let Some(connection) = info.connection else {
+                    continue;
+                };
+                let original_path = match root.parent() {
+                    Some(top) if info.path.is_relative() => top.join(&info.path),
+                    _ => info.path,
+                };
+                let is_directory =
+                    fs::symlink_metadata(root.join("files").join(id)).is_ok_and(|m| m.is_dir());
+                items.push(TrashedItem {
+                    id: id.to_string(),
+                    original_path,
+                    deleted_at: info.deleted_at,
+                    connection,
+                    is_directory,
+                    root: root.clone(),
+                });
+            }
+        }
+        Ok(items)
+    }
+
+    /// Remember a top-directory trash so listing and emptying find it
+    fn register(&self, root: &Path) {
+        let line = root.to_string_lossy();
+        if fs::read_to_string(&self.registry).is_ok_and(|r| r.lines().any(|l| l == line)) {
+            return;
+        }
+        let result = (|| {
+            if let Some(parent) = self.registry.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.registry)?;
+            writeln!(file, "{line}")
+        })();
+        if let Err(e) = result {
+            log::warn!("Failed to register trash {}: {e}", root.display());
+        }
+    }
+
+    /// Trash directory for `path`: the home trash when it is on the same
+    /// filesystem, else the top-directory trash of the mount holding `path`
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn root_for(&self, path: &Path, metadata: &fs::Metadata) -> Option<PathBuf> {
+        use std::os::unix::fs::MetadataExt;
+
+        let device = metadata.dev();
+        let home_device = self
+            .home
+            .ancestors()
+            .find_map(|dir| fs::metadata(dir).ok())
+            .map(|m| m.dev());
+        if home_device == Some(device) {
+            return Some(self.home.clone());
+        }
+
+        // Top of the mount: the highest ancestor still on the same device
+        let top = path
+            .ancestors()
+            .skip(1)
+            .map_while(|dir| {
+                fs::metadata(dir)
+                    .ok()
+                    .filter(|m| m.dev() == device)
+                    .map(|_| dir)
+            })
+            .last()?;
+        let uid = rustix::process::getuid().as_raw();
+        let root = top.join(format!(".Trash-{uid}"));
+
+        // Never follow a planted symlink or use another user's directory
+        match fs::symlink_metadata(&root) {
+            Ok(m) if !m.is_dir() || m.uid() != uid => None,
+            _ => Some(root),
+        }
+    }
+
+    /// Trash directory for `path`: always the home trash; a rename across
+    /// filesystems fails and the item is deleted permanently
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn root_for(&self, _path: &Path, _metadata: &fs::Metadata) -> Option<PathBuf> {
+        Some(self.home.clone())
+    }
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn default_home(_state: &Path) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("Trash"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn default_home(state: &Path) -> Option<PathBuf> {
+    Some(state.join("trash"))
+}
+
+/// Path written to the info file: absolute in the home trash, relative to
+/// the mount's top directory in a top-directory trash
+fn recorded_path<'a>(root: &Path, home: &Path, path: &'a Path) -> &'a Path {
+    match root.parent() {
+        Some(top) if root != home => path.strip_prefix(top).unwrap_or(path),
+        _ => path,
+    }
+}
+
+/// Create `files/` and `info/` under `root`, private to this user
+fn create_layout(root: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(root.join("files"))?;
+    builder.create(root.join("info"))
+}
@@ -0,0 +1,90 @@
+use crate::journal::{DEFAULT_CONNECTION, Journal};
+use crate::schema::{FS_UNDO, FsUndoArgs, FsUndoOutput, JournalEntry};
+use kodegen_mcp_schema::filesystem::WriteFilePrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct UndoTool;
+
+impl UndoTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for UndoTool {
+    type Args = FsUndoArgs;
+    type Prompts = WriteFilePrompts;
+
+    fn name() -> &'static str {
+        FS_UNDO
+    }
+
+    fn description() -> &'static str {
+        "Undo the most recent file operations of this connection. fs_write_file, fs_edit_block, \
+         fs_apply_patch, fs_replace, fs_move_file, fs_delete_file and fs_delete_directory \
+         snapshot what they change in a local journal and return an undo_id; fs_undo restores \
+         those snapshots newest first. Use count to undo several operations or operation_id to \
+         undo one specific operation (see fs_history). Undo refuses to touch a path that changed \
+         after the operation unless force is true."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true // Overwrites files with their earlier contents
+    }
+
+    fn idempotent() -> bool {
+        false // Each call undoes the next operation
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+        let journal = Journal::open_default().ok_or_else(|| {
+            McpError::InvalidArguments(
+                "The undo journal is disabled (KODEGEN_UNDO_JOURNAL=0)".to_string(),
+            )
+        })?;
+        let count = args.count.unwrap_or(1).max(1);
+        let connection = ctx.connection_id().unwrap_or(DEFAULT_CONNECTION);
+
+        let undone = journal
+            .undo(connection, count, args.operation_id.as_deref(), args.force)
+            .await?;
+        let undone: Vec<JournalEntry> = undone.iter().map(JournalEntry::from).collect();
+
+        // Human summary
+        let mut summary = format!("\x1b[33mUndid {} operation(s)\x1b[0m", undone.len());
+        for entry in &undone {
+            summary.push_str(&format!(
+                "\n  {} · {} · {}",
+                entry.id, entry.tool, entry.summary
+            ));
+        }
+        let message = format!("Undid {} operation(s)", undone.len());
+
+        Ok(ToolResponse::new(
+            summary,
+            FsUndoOutput {
+                success: true,
+                undone,
+                message,
+            },
+        ))
+    }
+}
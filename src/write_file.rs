@@ -1,11 +1,109 @@
+use crate::atomic_write::{create_new, write_atomic};
+use crate::diff::{DEFAULT_CONTEXT_LINES, unified_diff};
+use crate::encoding::{self, TextEncoding, resolve_label};
+use crate::journal::Recorder;
+use crate::precondition::{ContentHasher, content_hash, read_if_match};
+use crate::schema::{FsWriteFileArgs, FsWriteFileOutput};
 use crate::validate_path;
 use kodegen_config::shorten_path_for_display;
-use kodegen_mcp_schema::filesystem::{FsWriteFileArgs, FsWriteFileOutput, WriteFilePrompts};
+use kodegen_mcp_schema::filesystem::WriteFilePrompts;
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse, McpError};
 use tokio::fs;
 use tokio::fs::OpenOptions;
 use tokio::io::AsyncWriteExt;
 
+// ============================================================================
+// WRITE MODES
+// ============================================================================
+
+/// How `content` is combined with the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriteMode {
+    Rewrite,
+    Append,
+    /// Fail if the file exists
+    CreateNew,
+    /// Insert before this 1-based line (`prepend` is line 1)
+    InsertAtLine(usize),
+}
+
+impl WriteMode {
+    fn parse(mode: &str, line: Option<usize>) -> Result<Self, McpError> {
+        match (mode, line) {
+            ("rewrite", _) => Ok(Self::Rewrite),
+            ("append", _) => Ok(Self::Append),
+            ("create_new", _) => Ok(Self::CreateNew),
+            ("prepend", _) => Ok(Self::InsertAtLine(1)),
+            ("insert_at_line", Some(line)) if line >= 1 => Ok(Self::InsertAtLine(line)),
+            ("insert_at_line", _) => Err(McpError::InvalidArguments(
+                "insert_at_line needs `line`: the 1-based line to insert before".to_string(),
+            )),
+            (other, _) => Err(McpError::InvalidArguments(format!(
+                "Unknown write mode '{other}'. Use \"rewrite\", \"append\", \"create_new\", \
+                 \"prepend\" or \"insert_at_line\"."
+            ))),
+        }
+    }
+
+    /// Whether the file's existing content is kept around the new text
+    fn keeps_content(self) -> bool {
+        matches!(self, Self::Append | Self::InsertAtLine(_))
+    }
+}
+
+/// The file's current text in its detected encoding (empty if it does not exist)
+fn existing_text(
+    old_bytes: Option<&[u8]>,
+    existing: Option<TextEncoding>,
+) -> Result<String, McpError> {
+    match (old_bytes, existing) {
+        (Some(bytes), Some(existing)) => encoding::decode(bytes, existing),
+        _ => Ok(String::new()),
+    }
+}
+
+fn already_exists(path: &str) -> McpError {
+    McpError::InvalidArguments(format!(
+        "{path} already exists; mode \"create_new\" never overwrites. Use mode \"rewrite\" \
+         to replace it."
+    ))
+}
+
+/// Insert `content` into `text` before 1-based `line`
+///
+/// `line` may be one past the last line to add at the end. Inserted text is
+/// given a line break (in the file's style) when it lacks one, so it never
+/// runs into the following line.
+///
+/// # Errors
+/// Returns `McpError::InvalidArguments` when `line` is past the end.
+pub fn insert_lines(text: &str, content: &str, line: usize) -> Result<String, McpError> {
+    let line_ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    if line == 0 || line > lines.len() + 1 {
+        return Err(McpError::InvalidArguments(format!(
+            "line {line} is out of range: the file has {} lines (use 1 to {})",
+            lines.len(),
+            lines.len() + 1
+        )));
+    }
+
+    let split = lines[..line - 1].iter().map(|l| l.len()).sum::<usize>();
+    let (before, after) = text.split_at(split);
+
+    let mut out = String::with_capacity(text.len() + content.len() + 2 * line_ending.len());
+    out.push_str(before);
+    if !before.is_empty() && !before.ends_with('\n') {
+        out.push_str(line_ending);
+    }
+    out.push_str(content);
+    if !after.is_empty() && !content.is_empty() && !content.ends_with('\n') {
+        out.push_str(line_ending);
+    }
+    out.push_str(after);
+    Ok(out)
+}
+
 // ============================================================================
 // TOOL STRUCT
 // ============================================================================
@@ -35,8 +133,18 @@ impl Tool for WriteFileTool {
     }
 
     fn description() -> &'static str {
-        "Write or append to file contents. Supports two modes: 'rewrite' (overwrite entire file) \
-         and 'append' (add to end of file). Automatically validates paths and creates parent \
+        "Write or append to file contents. Modes: 'rewrite' (overwrite entire file, default), \
+         'append' (add to end of file), 'create_new' (create the file, failing if it already \
+         exists), 'prepend' (insert at the top) and 'insert_at_line' (insert before the 1-based \
+         `line`). Rewrites, creates and inserts are atomic: the new contents are fsynced in a \
+         temp file that keeps the original's permissions, then moved into place. Pass if_match \
+         (content_hash or modified from fs_read_file) to refuse the write if the file changed \
+         since it was read; the response carries the new content_hash and an undo_id for \
+         fs_undo; rewrites also return a unified diff against the previous contents (diff_context \
+         sets its context lines, default 3). Pass dry_run: true to validate and get a unified \
+         diff of the write without touching the file. Existing files keep their encoding and BOM; \
+         use the encoding parameter (e.g. \"utf-16le\", \"shiftjis\") for files without a BOM or \
+         to pick the encoding of a new file. Automatically validates paths and creates parent \
          directories if needed."
     }
 
@@ -54,41 +162,190 @@ impl Tool for WriteFileTool {
 
     async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
         let valid_path = validate_path(&args.path, &self.config_manager, ctx.pwd()).await?;
+        let explicit_encoding = args.encoding.as_deref().map(resolve_label).transpose()?;
 
         // Create parent directories if needed
-        if let Some(parent) = valid_path.parent() {
+        if let Some(parent) = valid_path.parent()
+            && !args.dry_run
+        {
             fs::create_dir_all(parent).await?;
         }
 
+        let write_mode = WriteMode::parse(&args.mode, args.line)?;
+        if write_mode == WriteMode::CreateNew && fs::symlink_metadata(&valid_path).await.is_ok() {
+            return Err(already_exists(&args.path));
+        }
+
+        // Read the old contents once: if_match, the encoding to keep, the diff
+        // and the new hash all come from these bytes
+        let old_bytes = read_if_match(&valid_path, args.if_match.as_deref()).await?;
+
+        // Existing files keep their encoding and BOM; new files use the
+        // requested encoding (UTF-8 by default)
+        let existing_encoding = old_bytes
+            .as_deref()
+            .filter(|bytes| !bytes.is_empty())
+            .map(|bytes| encoding::detect(bytes, explicit_encoding));
+        let is_append = write_mode == WriteMode::Append;
+
+        let text_encoding = match existing_encoding {
+            // Added text must match the bytes already in the file
+            Some(existing) if write_mode.keeps_content() => existing,
+            Some(existing) => match explicit_encoding {
+                Some(requested) if requested != existing.encoding => {
+                    TextEncoding::for_new_file(requested)
+                }
+                _ => existing,
+            },
+            None => explicit_encoding.map_or(TextEncoding::UTF8, TextEncoding::for_new_file),
+        };
+
+        // Appended text goes after the file's existing BOM, so it never carries one
+        let write_encoding = if is_append && existing_encoding.is_some() {
+            TextEncoding {
+                bom: false,
+                ..text_encoding
+            }
+        } else {
+            text_encoding
+        };
+        let bytes = encoding::encode(&args.content, write_encoding)?;
+
         // Get file metadata for response
-        let content_bytes = args.content.len();
+        let content_bytes = bytes.len();
         let line_count = args.content.lines().count();
         let mode = args.mode.clone();
+        let diff_context = args.diff_context.unwrap_or(DEFAULT_CONTEXT_LINES);
 
-        // Perform write operation
-        if args.mode == "append" {
-            let mut file = OpenOptions::new()
-                .append(true)
-                .create(true)
-                .open(&valid_path)
-                .await?;
-            file.write_all(args.content.as_bytes()).await?;
-        } else {
-            fs::write(&valid_path, args.content).await?;
+        if args.dry_run {
+            let old_text = match existing_text(old_bytes.as_deref(), existing_encoding) {
+                Ok(text) => Some(text),
+                // Inserting needs the text; the other modes write bytes regardless
+                Err(e) if matches!(write_mode, WriteMode::InsertAtLine(_)) => return Err(e),
+                Err(_) => None,
+            };
+            let diff = match &old_text {
+                Some(old) => {
+                    let new_text = match write_mode {
+                        WriteMode::Rewrite | WriteMode::CreateNew => args.content.clone(),
+                        WriteMode::Append => format!("{old}{}", args.content),
+                        WriteMode::InsertAtLine(line) => insert_lines(old, &args.content, line)?,
+                    };
+                    Some(unified_diff(&args.path, old, &new_text, diff_context))
+                }
+                None => None,
+            };
+            let verb = match (write_mode, existing_encoding.is_some()) {
+                (_, false) => "create",
+                (WriteMode::Append, true) => "append to",
+                (WriteMode::InsertAtLine(_), true) => "insert into",
+                (WriteMode::Rewrite | WriteMode::CreateNew, true) => "rewrite",
+            };
+            let display_path = shorten_path_for_display(&valid_path, ctx.git_root());
+            let mut summary = format!(
+                "\x1b[36mDry run: would {verb} file: {display_path}\x1b[0m\n\
+                 Content: {content_bytes} bytes ({line_count} lines) · Mode: {mode}"
+            );
+            if let Some(diff) = &diff {
+                summary.push('\n');
+                summary.push_str(diff);
+            }
+            return Ok(ToolResponse::new(summary, FsWriteFileOutput {
+                success: true,
+                path: valid_path.to_string_lossy().to_string(),
+                bytes_written: content_bytes as u64,
+                lines_written: line_count as u64,
+                mode,
+                encoding: text_encoding.label(),
+                content_hash: None,
+                undo_id: None,
+                dry_run: true,
+                diff,
+            }));
         }
 
+        // Snapshot the old contents so fs_undo can restore them
+        let mut undo =
+            Recorder::for_call(&ctx, Self::name(), format!("{} {}", args.mode, args.path));
+        undo.snapshot(&valid_path).await;
+
+        // Perform write operation
+        let mut diff = None;
+        let new_hash = match write_mode {
+            WriteMode::Append => {
+                // The file is now the bytes read above followed by the new ones
+                let mut hasher = ContentHasher::default();
+                hasher.update(old_bytes.as_deref().unwrap_or_default());
+                hasher.update(&bytes);
+                let mut file = OpenOptions::new()
+                    .append(true)
+                    .create(true)
+                    .open(&valid_path)
+                    .await?;
+                file.write_all(&bytes).await?;
+                file.flush().await?;
+                Some(hasher.finish())
+            }
+            WriteMode::Rewrite => {
+                // No diff for a file that does not decode as text; it is replaced all the same
+                diff = existing_text(old_bytes.as_deref(), existing_encoding)
+                    .ok()
+                    .map(|old| unified_diff(&args.path, &old, &args.content, diff_context));
+                let hash = content_hash(&bytes);
+                // Temp file + fsync + rename: readers never see a half-written file
+                write_atomic(&valid_path, bytes).await?;
+                Some(hash)
+            }
+            WriteMode::CreateNew => {
+                let hash = content_hash(&bytes);
+                let path = valid_path.clone();
+                tokio::task::spawn_blocking(move || create_new(&path, &bytes))
+                    .await
+                    .map_err(|e| McpError::Other(anyhow::anyhow!("Write task failed: {e}")))?
+                    .map_err(|e| match e.kind() {
+                        std::io::ErrorKind::AlreadyExists => already_exists(&args.path),
+                        _ => McpError::Io(e),
+                    })?;
+                Some(hash)
+            }
+            WriteMode::InsertAtLine(line) => {
+                let existing = existing_text(old_bytes.as_deref(), existing_encoding)?;
+                let new_text = insert_lines(&existing, &args.content, line)?;
+                let new_bytes = encoding::encode(&new_text, text_encoding)?;
+                let hash = content_hash(&new_bytes);
+                write_atomic(&valid_path, new_bytes).await?;
+                Some(hash)
+            }
+        };
+
+        let undo_id = undo.commit().await;
+
+        let encoding_label = text_encoding.label();
+        let encoding_note = encoding_label
+            .as_deref()
+            .map(|e| format!(" · Encoding: {e}"))
+            .unwrap_or_default();
+
         // Human summary
-        let verb = if mode == "append" { "Appended" } else { "Wrote" };
+        let verb = match write_mode {
+            WriteMode::Append => "Appended",
+            WriteMode::InsertAtLine(_) => "Inserted into",
+            WriteMode::Rewrite | WriteMode::CreateNew => "Wrote",
+        };
         let display_path = shorten_path_for_display(&valid_path, ctx.git_root());
-        let summary = format!(
+        let mut summary = format!(
             "\x1b[32m{} file: {}\x1b[0m\n\
-             Written: {} bytes ({} lines) · Mode: {}",
+             Written: {} bytes ({} lines) · Mode: {}{encoding_note}",
             verb,
             display_path,
             content_bytes,
             line_count,
             mode
         );
+        if let Some(diff) = &diff {
+            summary.push('\n');
+            summary.push_str(diff);
+        }
 
         Ok(ToolResponse::new(summary, FsWriteFileOutput {
             success: true,
@@ -96,6 +353,11 @@ impl Tool for WriteFileTool {
             bytes_written: content_bytes as u64,
             lines_written: line_count as u64,
             mode,
+            encoding: encoding_label,
+            content_hash: new_hash,
+            undo_id,
+            dry_run: false,
+            diff,
         }))
     }
 }
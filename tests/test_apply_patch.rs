@@ -0,0 +1,220 @@
+/*!
+Tests for fs_apply_patch's unified diff parser and hunk application
+*/
+
+use kodegen_tools_filesystem::apply_patch::apply::{apply_hunks, check_deletion};
+use kodegen_tools_filesystem::apply_patch::parse::{HunkLine, PatchAction, parse_patch};
+
+const GIT_PATCH: &str = "\
+From 1234 Mon Sep 17 00:00:00 2001
+Subject: [PATCH] Example
+
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn a() {}
+-fn b() {}
++fn b() -> u8 { 1 }
+ fn c() {}
+diff --git a/NEW.md b/NEW.md
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/NEW.md
+@@ -0,0 +1,2 @@
++# New
++text
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index 4444444..0000000
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/from.rs b/to.rs
+similarity index 100%
+rename from from.rs
+rename to to.rs
+diff --git a/script.sh b/script.sh
+old mode 100644
+new mode 100755
+diff --git a/last.txt b/last.txt
+--- a/last.txt
++++ b/last.txt
+@@ -1 +1 @@
+-old
++new
+-- 
+2.43.0
+";
+
+#[test]
+fn test_parse_git_patch_with_create_delete_rename() {
+    let patches = parse_patch(GIT_PATCH, None).unwrap();
+    let summary: Vec<_> = patches
+        .iter()
+        .map(|p| (p.action(), p.old_path.as_deref(), p.new_path.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (PatchAction::Modify, Some("src/lib.rs"), Some("src/lib.rs")),
+            (PatchAction::Create, None, Some("NEW.md")),
+            (PatchAction::Delete, Some("old.txt"), None),
+            (PatchAction::Rename, Some("from.rs"), Some("to.rs")),
+            (PatchAction::Modify, Some("last.txt"), Some("last.txt")),
+        ],
+        "the mode-only change is skipped"
+    );
+
+    let hunk = &patches[0].hunks[0];
+    assert_eq!(hunk.patch_line, 8);
+    assert_eq!(
+        hunk.lines,
+        vec![
+            HunkLine::Context("fn a() {}".into()),
+            HunkLine::Remove("fn b() {}".into()),
+            HunkLine::Add("fn b() -> u8 { 1 }".into()),
+            HunkLine::Context("fn c() {}".into()),
+        ]
+    );
+    assert_eq!(
+        patches[2].hunks[0].lines,
+        vec![HunkLine::Remove("gone".into())]
+    );
+    // The format-patch signature after the last hunk is not part of it
+    assert_eq!(
+        patches[4].hunks[0].lines,
+        vec![HunkLine::Remove("old".into()), HunkLine::Add("new".into())]
+    );
+}
+
+#[test]
+fn test_deletion_must_remove_the_whole_file() {
+    let patches = parse_patch(GIT_PATCH, None).unwrap();
+    let delete = &patches[2];
+    let applied = apply_hunks("gone\n", &delete.hunks, 0).unwrap();
+    assert_eq!(check_deletion(delete, &applied), Ok(()));
+
+    // A hunk that removes only part of the file
+    let applied = apply_hunks("gone\nkept\n", &delete.hunks, 0).unwrap();
+    assert!(check_deletion(delete, &applied).is_err());
+
+    // A deletion without hunks only removes an empty file, as with git apply
+    let patches = parse_patch("diff --git a/x b/x\ndeleted file mode 100644\n", None).unwrap();
+    assert_eq!(
+        (patches[0].action(), patches[0].hunks.len()),
+        (PatchAction::Delete, 0)
+    );
+    let applied = apply_hunks("content\n", &patches[0].hunks, 0).unwrap();
+    let error = check_deletion(&patches[0], &applied).unwrap_err();
+    assert!(
+        error.starts_with("x: the patch deletes the file"),
+        "{error}"
+    );
+    let applied = apply_hunks("", &patches[0].hunks, 0).unwrap();
+    assert_eq!(check_deletion(&patches[0], &applied), Ok(()));
+
+    // Other actions may leave content
+    let modify = &parse_patch(GIT_PATCH, None).unwrap()[0];
+    let applied = apply_hunks("fn a() {}\nfn b() {}\nfn c() {}\n", &modify.hunks, 0).unwrap();
+    assert_eq!(check_deletion(modify, &applied), Ok(()));
+}
+
+#[test]
+fn test_parse_plain_diff_strip_and_recount() {
+    // diff -u output: timestamps, no a/ b/ prefixes, and a wrong line count
+    let patch = "\
+--- src/main.rs\t2024-01-01 10:00:00
++++ src/main.rs\t2024-01-02 10:00:00
+@@ -10,2 +10,2 @@ fn main() {
+     let x = 1;
+-    let y = 2;
++    let y = 3;
+
+     println!(\"{x}\");
+";
+    let patches = parse_patch(patch, None).unwrap();
+    assert_eq!(patches[0].old_path.as_deref(), Some("src/main.rs"));
+    let hunk = &patches[0].hunks[0];
+    assert_eq!(hunk.old_lines().len(), 4, "recounted past the header's 2");
+    assert_eq!(hunk.lines[3], HunkLine::Context(String::new()));
+
+    let patches = parse_patch(patch, Some(1)).unwrap();
+    assert_eq!(patches[0].new_path.as_deref(), Some("main.rs"));
+    assert!(parse_patch(patch, Some(2)).is_err());
+}
+
+#[test]
+fn test_parse_rejects_binary_and_headerless_hunks() {
+    assert!(
+        parse_patch(
+            "diff --git a/x.png b/x.png\nBinary files a/x.png and b/x.png differ\n",
+            None
+        )
+        .is_err()
+    );
+    assert!(parse_patch("@@ -1 +1 @@\n-a\n+b\n", None).is_err());
+    assert!(parse_patch("just some text\n", None).unwrap().is_empty());
+}
+
+#[test]
+fn test_parse_quoted_git_paths() {
+    let patch = "diff --git \"a/my file.txt\" \"b/my file.txt\"\n\
+                 --- \"a/my file.txt\"\n+++ \"b/my file.txt\"\n@@ -1 +1 @@\n-a\n+b\n";
+    let patches = parse_patch(patch, None).unwrap();
+    assert_eq!(patches[0].new_path.as_deref(), Some("my file.txt"));
+}
+
+fn hunks(patch: &str) -> Vec<kodegen_tools_filesystem::apply_patch::parse::Hunk> {
+    parse_patch(patch, None).unwrap().remove(0).hunks
+}
+
+#[test]
+fn test_apply_with_offset_and_fuzz() {
+    let content = "header\nheader\none\ntwo\nthree\nfour\nfive\n";
+    // The header says line 1, but the lines moved down by two
+    let patch = "--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n one\n-two\n+TWO\n three\n";
+    let applied = apply_hunks(content, &hunks(patch), 0).unwrap();
+    assert_eq!(
+        applied.content,
+        "header\nheader\none\nTWO\nthree\nfour\nfive\n"
+    );
+    assert_eq!(applied.notes, vec!["hunk 1 applied at line 3 (offset +2)"]);
+
+    // Stale context at the ends only applies with fuzz
+    let patch = "--- a/f\n+++ b/f\n@@ -3,5 +3,5 @@\n ONE\n two\n-three\n+THREE\n four\n FIVE\n";
+    assert_eq!(apply_hunks(content, &hunks(patch), 0).unwrap_err(), vec![0]);
+    let applied = apply_hunks(content, &hunks(patch), 1).unwrap();
+    assert_eq!(
+        applied.content,
+        "header\nheader\none\ntwo\nTHREE\nfour\nfive\n"
+    );
+    assert_eq!(applied.notes, vec!["hunk 1 applied at line 3 (fuzz 1)"]);
+}
+
+#[test]
+fn test_apply_reports_every_failing_hunk() {
+    let content = "a\nb\nc\nd\n";
+    let patch = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-x\n+y\n@@ -2 +2 @@\n-b\n+B\n@@ -4 +4 @@\n-z\n+Z\n";
+    assert_eq!(
+        apply_hunks(content, &hunks(patch), 2).unwrap_err(),
+        vec![0, 2]
+    );
+}
+
+#[test]
+fn test_apply_keeps_crlf_and_missing_newline() {
+    let patch = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n";
+    let applied = apply_hunks("a\r\nb\r\n", &hunks(patch), 0).unwrap();
+    assert_eq!(applied.content, "a\r\nc\r\n");
+
+    let patch = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n";
+    let applied = apply_hunks("a\nb", &hunks(patch), 0).unwrap();
+    assert_eq!(applied.content, "a\nc\n");
+
+    let patch = "--- /dev/null\n+++ b/f\n@@ -0,0 +1 @@\n+only\n\\ No newline at end of file\n";
+    assert_eq!(apply_hunks("", &hunks(patch), 0).unwrap().content, "only");
+}
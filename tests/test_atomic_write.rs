@@ -0,0 +1,128 @@
+/*!
+Tests for atomic file replacement used by fs_write_file and fs_edit_block
+*/
+
+use kodegen_tools_filesystem::atomic_write::{create_new, rename_noreplace, replace, write_atomic};
+use std::fs;
+use tempfile::TempDir;
+
+/// Names in `dir` other than `keep`: leftover temp files
+fn strays(dir: &std::path::Path, keep: &[&str]) -> Vec<String> {
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| !keep.contains(&name.as_str()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_replace_creates_and_overwrites() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("a.txt");
+
+    write_atomic(&path, b"first\n".to_vec()).await.unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "first\n");
+
+    write_atomic(&path, b"second\n".to_vec()).await.unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "second\n");
+    assert!(strays(temp.path(), &["a.txt"]).is_empty());
+}
+
+#[test]
+fn test_failed_replace_leaves_target_untouched() {
+    let temp = TempDir::new().unwrap();
+    // A directory cannot be replaced by a file
+    let path = temp.path().join("dir");
+    fs::create_dir(&path).unwrap();
+    fs::write(path.join("inner"), "x").unwrap();
+
+    assert!(replace(&path, b"data").is_err());
+    assert!(path.is_dir());
+    assert!(strays(temp.path(), &["dir"]).is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_replace_keeps_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("script.sh");
+    fs::write(&path, "#!/bin/sh\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+
+    replace(&path, b"#!/bin/sh\necho hi\n").unwrap();
+    let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o7777;
+    assert_eq!(mode, 0o750);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_replace_writes_through_symlinks() {
+    let temp = TempDir::new().unwrap();
+    let target = temp.path().join("real.txt");
+    let link = temp.path().join("link.txt");
+    fs::write(&target, "old").unwrap();
+    std::os::unix::fs::symlink("real.txt", &link).unwrap();
+
+    replace(&link, b"new").unwrap();
+    assert!(
+        fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink()
+    );
+    assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_replace_keeps_xattrs() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("tagged.txt");
+    fs::write(&path, "old").unwrap();
+    if xattr::set(&path, "user.kodegen.test", b"kept").is_err() {
+        // Filesystem without user xattrs
+        return;
+    }
+
+    replace(&path, b"new").unwrap();
+    assert_eq!(
+        xattr::get(&path, "user.kodegen.test").unwrap().as_deref(),
+        Some(&b"kept"[..])
+    );
+}
+
+#[test]
+fn test_create_new_refuses_existing_files() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("new.txt");
+
+    create_new(&path, b"fresh").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "fresh");
+
+    let err = create_new(&path, b"clobber").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "fresh");
+    assert!(strays(temp.path(), &["new.txt"]).is_empty());
+}
+
+#[test]
+fn test_rename_noreplace() {
+    let temp = TempDir::new().unwrap();
+    let (a, b, c) = (
+        temp.path().join("a"),
+        temp.path().join("b"),
+        temp.path().join("c"),
+    );
+    fs::write(&a, "a").unwrap();
+    fs::write(&b, "b").unwrap();
+
+    let err = rename_noreplace(&a, &b).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read_to_string(&b).unwrap(), "b");
+
+    rename_noreplace(&a, &c).unwrap();
+    assert!(!a.exists());
+    assert_eq!(fs::read_to_string(&c).unwrap(), "a");
+}
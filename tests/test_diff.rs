@@ -0,0 +1,35 @@
+/*!
+Tests for the unified diffs returned by fs_edit_block and fs_write_file
+*/
+
+use kodegen_tools_filesystem::diff::{DEFAULT_CONTEXT_LINES, unified_diff};
+
+const OLD: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\n";
+const NEW: &str = "one\ntwo\nthree\nfour\nFIVE\nsix\nseven\neight\nnine\n";
+
+#[test]
+fn test_unified_diff_shows_changed_lines_with_context() {
+    let diff = unified_diff("src/lib.rs", OLD, NEW, DEFAULT_CONTEXT_LINES);
+
+    assert!(diff.starts_with("--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+    assert!(diff.contains("@@ -2,7 +2,7 @@"));
+    assert!(diff.contains("\n-five\n+FIVE\n"));
+    assert!(!diff.contains(" one\n"), "only three lines of context");
+}
+
+#[test]
+fn test_unified_diff_context_lines_are_configurable() {
+    let diff = unified_diff("a.txt", OLD, NEW, 0);
+    assert!(diff.contains("@@ -5 +5 @@\n-five\n+FIVE\n"));
+    assert!(!diff.contains(" four\n"));
+
+    let diff = unified_diff("a.txt", OLD, NEW, 10);
+    assert!(diff.contains("@@ -1,9 +1,9 @@"));
+}
+
+#[test]
+fn test_unified_diff_of_new_file_and_no_change() {
+    let diff = unified_diff("new.txt", "", "hello\n", DEFAULT_CONTEXT_LINES);
+    assert!(diff.contains("@@ -0,0 +1 @@\n+hello\n"));
+    assert!(unified_diff("same.txt", "x\n", "x\n", DEFAULT_CONTEXT_LINES).is_empty());
+}
@@ -0,0 +1,47 @@
+/*!
+Tests for the dry-run previews of the mutating tools
+*/
+
+use kodegen_tools_filesystem::dry_run::tree_size;
+use kodegen_tools_filesystem::schema::TreeSize;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_tree_size_counts_files_directories_and_bytes() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().join("tree");
+    fs::create_dir_all(root.join("a/b")).unwrap();
+    fs::create_dir_all(root.join("empty")).unwrap();
+    fs::write(root.join("top.txt"), "12345").unwrap();
+    fs::write(root.join("a/mid.txt"), "123").unwrap();
+    fs::write(root.join("a/b/deep.txt"), "1").unwrap();
+
+    assert_eq!(
+        tree_size(&root).unwrap(),
+        TreeSize {
+            files: 3,
+            directories: 4,
+            bytes: 9,
+        }
+    );
+    assert!(root.join("a/b/deep.txt").exists(), "nothing is touched");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_tree_size_does_not_follow_symlinks() {
+    let temp = TempDir::new().unwrap();
+    let outside = temp.path().join("outside");
+    fs::create_dir_all(&outside).unwrap();
+    fs::write(outside.join("big.bin"), vec![0u8; 4096]).unwrap();
+
+    let root = temp.path().join("tree");
+    fs::create_dir_all(&root).unwrap();
+    std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+    let size = tree_size(&root).unwrap();
+    assert_eq!(size.files, 1, "the link itself is removed, not its target");
+    assert_eq!(size.directories, 1);
+    assert_eq!(size.bytes, 0);
+}
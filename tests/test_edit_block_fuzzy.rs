@@ -0,0 +1,72 @@
+/*!
+Tests for fs_edit_block's accept_fuzzy option
+*/
+
+use kodegen_tools_filesystem::edit_block::fuzzy_accept_threshold;
+use kodegen_tools_filesystem::schema::{
+    DEFAULT_FUZZY_ACCEPT_THRESHOLD, FsEditBlockArgs, FsEditBlockOutput, FuzzyMatchApplied,
+};
+
+#[test]
+fn test_accept_threshold_defaults_and_never_undercuts_suggestions() {
+    assert_eq!(
+        fuzzy_accept_threshold(None, 0.7).unwrap(),
+        DEFAULT_FUZZY_ACCEPT_THRESHOLD
+    );
+    assert_eq!(fuzzy_accept_threshold(Some(0.9), 0.7).unwrap(), 0.9);
+    // A threshold below the suggestion threshold is raised to it
+    assert_eq!(fuzzy_accept_threshold(Some(0.5), 0.7).unwrap(), 0.7);
+    assert!(fuzzy_accept_threshold(Some(1.5), 0.7).is_err());
+    assert!(fuzzy_accept_threshold(Some(-0.1), 0.7).is_err());
+}
+
+#[test]
+fn test_accept_fuzzy_is_opt_in() {
+    let args: FsEditBlockArgs = serde_json::from_value(serde_json::json!({
+        "path": "a.rs", "old_string": "a", "new_string": "b"
+    }))
+    .unwrap();
+    assert!(!args.accept_fuzzy);
+    assert_eq!(args.accept_fuzzy_threshold, None);
+
+    let args: FsEditBlockArgs = serde_json::from_value(serde_json::json!({
+        "path": "a.rs", "old_string": "a", "new_string": "b",
+        "accept_fuzzy": true, "accept_fuzzy_threshold": 0.98
+    }))
+    .unwrap();
+    assert!(args.accept_fuzzy);
+    assert_eq!(args.accept_fuzzy_threshold, Some(0.98));
+}
+
+#[test]
+fn test_output_lists_fuzzy_matches_only_when_present() {
+    let mut output = FsEditBlockOutput {
+        success: true,
+        path: "a.rs".to_string(),
+        replacements_made: 1,
+        message: String::new(),
+        encoding: None,
+        content_hash: None,
+        undo_id: None,
+        dry_run: false,
+        diff: None,
+        replaced: vec![],
+        whitespace_normalized: false,
+        fuzzy_matches: vec![],
+    };
+    let json = serde_json::to_value(&output).unwrap();
+    assert!(json.get("fuzzy_matches").is_none());
+
+    output.fuzzy_matches.push(FuzzyMatchApplied {
+        line: 3,
+        similarity: 0.97,
+        matched_text: "let colour = 1;".to_string(),
+        char_diff: "let colo{--}{+u+}r = 1;".to_string(),
+    });
+    let json = serde_json::to_value(&output).unwrap();
+    assert_eq!(json["fuzzy_matches"][0]["line"], 3);
+    assert_eq!(
+        json["fuzzy_matches"][0]["char_diff"],
+        "let colo{--}{+u+}r = 1;"
+    );
+}
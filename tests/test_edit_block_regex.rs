@@ -0,0 +1,98 @@
+/*!
+Tests for fs_edit_block's regex pattern mode
+*/
+
+use kodegen_mcp_schema::McpError;
+use kodegen_tools_filesystem::edit_block::pattern::{
+    apply_replacements, expand_matches, regex_replacements,
+};
+use kodegen_tools_filesystem::edit_block::scope::line_span;
+use kodegen_tools_filesystem::schema::{EditPatternMode, FsEditBlockArgs};
+use kodegen_tools_filesystem::search::rg::build_pattern_matcher;
+use kodegen_tools_filesystem::search::types::{CaseMode, Engine};
+
+const TEXT: &str = "let user_id = 1;\nprint(user_id);\nlet other = user_id + 2;\n";
+
+#[test]
+fn test_regex_replacements_expand_captures() {
+    let found = regex_replacements(
+        TEXT,
+        0..TEXT.len(),
+        r"\buser_(?P<field>\w+)\b",
+        "account_${field}_$$",
+    )
+    .unwrap();
+    assert_eq!(found.len(), 3);
+    assert_eq!(&TEXT[found[0].span.clone()], "user_id");
+    assert_eq!(found[0].text, "account_id_$");
+
+    let found = regex_replacements(TEXT, 0..TEXT.len(), r"let (\w+) =", "const $1 =").unwrap();
+    let (new, starts) = apply_replacements(TEXT, &found);
+    assert_eq!(
+        new,
+        "const user_id = 1;\nprint(user_id);\nconst other = user_id + 2;\n"
+    );
+    assert_eq!(starts, vec![0, 35]);
+}
+
+#[test]
+fn test_regex_replacements_stay_in_line_range() {
+    let span = line_span(TEXT, Some(2), Some(3)).unwrap();
+    let found = regex_replacements(TEXT, span, r"user_id", "uid").unwrap();
+    assert_eq!(found.len(), 2);
+    assert!(
+        found
+            .iter()
+            .all(|r| r.span.start > TEXT.find('\n').unwrap())
+    );
+}
+
+#[test]
+fn test_regex_falls_back_to_pcre2() {
+    // Look-behind is PCRE2-only
+    let found = regex_replacements(TEXT, 0..TEXT.len(), r"(?<=let )\w+", "x").unwrap();
+    let (new, _) = apply_replacements(TEXT, &found);
+    assert_eq!(new, "let x = 1;\nprint(user_id);\nlet x = user_id + 2;\n");
+
+    assert!(matches!(
+        regex_replacements(TEXT, 0..TEXT.len(), r"(unclosed", "x"),
+        Err(McpError::InvalidArguments(_))
+    ));
+}
+
+#[test]
+fn test_regex_refuses_patterns_matching_the_empty_string() {
+    for pattern in ["x*", "a?", "", "(?m)^"] {
+        let error = regex_replacements("é\n", 0..3, pattern, "-").unwrap_err();
+        assert!(
+            matches!(&error, McpError::InvalidArguments(message) if message.contains("empty string")),
+            "{pattern:?}: {error}"
+        );
+    }
+
+    // Matched directly, empty matches inside a character are dropped
+    let matcher =
+        build_pattern_matcher("x*", Engine::Auto, CaseMode::Sensitive, false, false).unwrap();
+    let found = expand_matches(&matcher, "é\n", "-").unwrap();
+    assert!(
+        found.iter().all(|r| "é\n".is_char_boundary(r.span.start)),
+        "{found:?}"
+    );
+    let (new, _) = apply_replacements("é\n", &found);
+    assert_eq!(new.replace('-', ""), "é\n");
+}
+
+#[test]
+fn test_pattern_mode_defaults_to_literal() {
+    let args: FsEditBlockArgs = serde_json::from_value(serde_json::json!({
+        "path": "a.rs", "old_string": "a", "new_string": "b"
+    }))
+    .unwrap();
+    assert_eq!(args.pattern_mode, EditPatternMode::Literal);
+
+    let args: FsEditBlockArgs = serde_json::from_value(serde_json::json!({
+        "path": "a.rs", "old_string": "a", "new_string": "b", "pattern_mode": "regex"
+    }))
+    .unwrap();
+    assert_eq!(args.pattern_mode, EditPatternMode::Regex);
+}
@@ -0,0 +1,88 @@
+/*!
+Tests for fs_edit_block's line-range scope, occurrence selection and edit lists
+*/
+
+use kodegen_mcp_schema::McpError;
+use kodegen_tools_filesystem::edit_block::scope::{
+    find_in_span, line_span, nth_occurrence, replace_at,
+};
+use kodegen_tools_filesystem::schema::FsEditBlockArgs;
+
+const TEXT: &str = "foo\nbar\nfoo\nbaz\nfoo\n";
+
+#[test]
+fn test_line_span_bounds() {
+    assert_eq!(line_span(TEXT, None, None).unwrap(), 0..TEXT.len());
+    assert_eq!(
+        &TEXT[line_span(TEXT, Some(2), Some(3)).unwrap()],
+        "bar\nfoo\n"
+    );
+    assert_eq!(&TEXT[line_span(TEXT, Some(4), None).unwrap()], "baz\nfoo\n");
+    assert_eq!(&TEXT[line_span(TEXT, None, Some(1)).unwrap()], "foo\n");
+    // An end past the last line runs to the end of the file
+    assert_eq!(&TEXT[line_span(TEXT, Some(5), Some(99)).unwrap()], "foo\n");
+    assert_eq!(&"a\nb"[line_span("a\nb", Some(2), None).unwrap()], "b");
+
+    for (start, end) in [(Some(0), None), (Some(3), Some(2)), (Some(6), None)] {
+        assert!(matches!(
+            line_span(TEXT, start, end),
+            Err(McpError::InvalidArguments(_))
+        ));
+    }
+}
+
+#[test]
+fn test_find_in_span_only_counts_matches_inside() {
+    assert_eq!(find_in_span(TEXT, 0..TEXT.len(), "foo"), vec![0, 8, 16]);
+    let span = line_span(TEXT, Some(2), Some(4)).unwrap();
+    assert_eq!(find_in_span(TEXT, span, "foo"), vec![8]);
+    // A match straddling the end of the range does not count
+    let span = line_span(TEXT, Some(1), Some(1)).unwrap();
+    assert!(find_in_span(TEXT, span, "foo\nbar").is_empty());
+}
+
+#[test]
+fn test_nth_occurrence_from_either_end() {
+    let matches = [0, 8, 16];
+    assert_eq!(nth_occurrence(&matches, 1).unwrap(), 0);
+    assert_eq!(nth_occurrence(&matches, 3).unwrap(), 16);
+    assert_eq!(nth_occurrence(&matches, -1).unwrap(), 16);
+    assert_eq!(nth_occurrence(&matches, -3).unwrap(), 0);
+    for occurrence in [0, 4, -4, i64::MIN] {
+        assert!(matches!(
+            nth_occurrence(&matches, occurrence),
+            Err(McpError::InvalidArguments(_))
+        ));
+    }
+}
+
+#[test]
+fn test_replace_at_only_touches_selected_matches() {
+    assert_eq!(
+        replace_at(TEXT, &[8], 3, "qux"),
+        "foo\nbar\nqux\nbaz\nfoo\n"
+    );
+    assert_eq!(replace_at(TEXT, &[0, 16], 3, "x"), "x\nbar\nfoo\nbaz\nx\n");
+    assert_eq!(replace_at(TEXT, &[], 3, "x"), TEXT);
+}
+
+#[test]
+fn test_edit_list_args_default_per_edit() {
+    let args: FsEditBlockArgs = serde_json::from_value(serde_json::json!({
+        "path": "src/lib.rs",
+        "edits": [
+            { "old_string": "foo", "new_string": "bar" },
+            { "old_string": "baz", "new_string": "qux", "expected_replacements": 2 },
+            { "old_string": "x", "new_string": "y", "occurrence": -1, "start_line": 10 }
+        ]
+    }))
+    .unwrap();
+
+    assert!(args.old_string.is_empty() && args.new_string.is_empty());
+    assert_eq!(args.edits.len(), 3);
+    assert_eq!(args.edits[0].expected_replacements, 1);
+    assert_eq!(args.edits[1].expected_replacements, 2);
+    assert_eq!(args.edits[2].occurrence, Some(-1));
+    assert_eq!(args.edits[2].start_line, Some(10));
+    assert_eq!(args.edits[2].end_line, None);
+}
@@ -0,0 +1,63 @@
+/*!
+Tests for fs_edit_block's normalize_whitespace pattern mode
+*/
+
+use kodegen_tools_filesystem::edit_block::pattern::apply_replacements;
+use kodegen_tools_filesystem::edit_block::whitespace::normalized_replacements;
+use kodegen_tools_filesystem::schema::{EditPatternMode, FsEditBlockArgs};
+
+fn normalize_edit(content: &str, old: &str, new: &str, line_ending: &str) -> Option<String> {
+    let found = normalized_replacements(content, 0..content.len(), old, new, line_ending);
+    if found.is_empty() {
+        return None;
+    }
+    Some(apply_replacements(content, &found).0)
+}
+
+#[test]
+fn test_reindents_new_string_to_the_file() {
+    let content = "fn main() {\n        if ready {\n            go();\n        }\n}\n";
+    let old = "if ready {\n    go();\n}";
+    let new = "if ready {\n    go();\n    log();\n}";
+    assert_eq!(
+        normalize_edit(content, old, new, "\n").unwrap(),
+        "fn main() {\n        if ready {\n            go();\n            log();\n        }\n}\n"
+    );
+}
+
+#[test]
+fn test_ignores_trailing_whitespace_and_blank_lines() {
+    let content = "\ta = 1;   \n\t  \n\tb = 2;\n";
+    let old = "a = 1;\n\nb = 2;\n";
+    assert_eq!(
+        normalize_edit(content, old, "a = 3;\n", "\n").unwrap(),
+        "\ta = 3;\n"
+    );
+}
+
+#[test]
+fn test_other_differences_still_fail() {
+    let content = "    if ready {\n        go();\n    }\n";
+    // Relative indentation and inner spacing still count
+    assert!(normalize_edit(content, "if ready {\ngo();\n}", "x", "\n").is_none());
+    assert!(normalize_edit(content, "if  ready {\n    go();\n}", "x", "\n").is_none());
+}
+
+#[test]
+fn test_keeps_crlf_line_endings() {
+    let content = "x\r\n    one\r\n    two\r\ny\r\n";
+    assert_eq!(
+        normalize_edit(content, "one\ntwo", "uno\ndos", "\r\n").unwrap(),
+        "x\r\n    uno\r\n    dos\r\ny\r\n"
+    );
+}
+
+#[test]
+fn test_pattern_mode_accepts_normalize_whitespace() {
+    let args: FsEditBlockArgs = serde_json::from_value(serde_json::json!({
+        "path": "a.rs", "old_string": "a", "new_string": "b",
+        "pattern_mode": "normalize_whitespace"
+    }))
+    .unwrap();
+    assert_eq!(args.pattern_mode, EditPatternMode::NormalizeWhitespace);
+}
@@ -0,0 +1,78 @@
+/*!
+Tests for encoding detection and round-tripping used by read, write and edit
+*/
+
+use encoding_rs::{SHIFT_JIS, UTF_8, UTF_16LE, WINDOWS_1252};
+use kodegen_tools_filesystem::encoding::{
+    TextEncoding, decode, detect, detect_file, encode, read_text, resolve_label,
+};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_resolve_labels_like_search() {
+    assert_eq!(resolve_label("shiftjis").expect("shiftjis"), SHIFT_JIS);
+    assert_eq!(resolve_label("utf-16le").expect("utf-16le"), UTF_16LE);
+    assert_eq!(resolve_label("latin1").expect("latin1"), WINDOWS_1252);
+    assert_eq!(resolve_label("UTF8").expect("utf8"), UTF_8);
+    // Unhyphenated spellings documented by fs_search
+    assert_eq!(resolve_label("utf16le").expect("utf16le"), UTF_16LE);
+    assert_eq!(resolve_label("Shift-JIS").expect("Shift-JIS"), SHIFT_JIS);
+    assert!(resolve_label("klingon").is_err());
+}
+
+#[test]
+fn test_bom_wins_over_explicit_encoding() {
+    let detected = detect(b"\xff\xfea\0", Some(SHIFT_JIS));
+    assert_eq!(detected.encoding, UTF_16LE);
+    assert!(detected.bom);
+
+    let detected = detect(b"plain", Some(SHIFT_JIS));
+    assert_eq!(detected.encoding, SHIFT_JIS);
+    assert!(!detected.bom);
+
+    assert_eq!(detect(b"plain", None), TextEncoding::UTF8);
+    assert_eq!(TextEncoding::UTF8.label(), None);
+}
+
+#[test]
+fn test_utf16le_bom_round_trip() {
+    let original = b"\xff\xfeh\0i\0\r\0\n\0".to_vec();
+    let text_encoding = detect(&original, None);
+    let text = decode(&original, text_encoding).expect("decode");
+    assert_eq!(text, "hi\r\n");
+    assert_eq!(encode(&text, text_encoding).expect("encode"), original);
+    assert_eq!(text_encoding.label().as_deref(), Some("UTF-16LE (BOM)"));
+}
+
+#[test]
+fn test_shift_jis_round_trip_and_unmappable() {
+    let text_encoding = TextEncoding::for_new_file(SHIFT_JIS);
+    assert!(!text_encoding.bom);
+
+    let bytes = encode("日本語", text_encoding).expect("encode");
+    assert_eq!(bytes, b"\x93\xfa\x96\x7b\x8c\xea");
+    assert_eq!(decode(&bytes, text_encoding).expect("decode"), "日本語");
+
+    // Characters outside the encoding are an error, not a silent substitution
+    assert!(encode("emoji 🦀", text_encoding).is_err());
+    // Malformed input is an error, not mojibake
+    assert!(decode(b"\xff\xfe\xfd", TextEncoding::UTF8).is_err());
+}
+
+#[tokio::test]
+async fn test_read_text_and_detect_file() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("resource.rc");
+    fs::write(&path, b"\xff\xfeo\0k\0").expect("Failed to write file");
+
+    let (text, text_encoding) = read_text(&path, None).await.expect("read");
+    assert_eq!(text, "ok");
+    assert_eq!(text_encoding.encoding, UTF_16LE);
+
+    let detected = detect_file(&path, None).await.expect("detect");
+    assert_eq!(detected, Some(text_encoding));
+
+    let missing = temp_dir.path().join("missing.txt");
+    assert_eq!(detect_file(&missing, None).await.expect("detect"), None);
+}
@@ -0,0 +1,121 @@
+/*!
+Tests for if_match preconditions shared by the mutating file tools
+*/
+
+use kodegen_mcp_schema::McpError;
+use kodegen_tools_filesystem::precondition::{
+    ContentHasher, check_if_match, content_hash, fingerprint, read_if_match,
+};
+use std::fs;
+use tempfile::TempDir;
+
+fn assert_conflict(result: Result<(), McpError>) {
+    match result {
+        Err(McpError::InvalidArguments(msg)) => assert!(msg.starts_with("Conflict:"), "{msg}"),
+        other => panic!("expected a conflict, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_fingerprint_matches_content_hash() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("a.txt");
+    fs::write(&path, "hello\n").unwrap();
+
+    let state = fingerprint(&path).await.unwrap();
+    assert_eq!(
+        state.content_hash.as_deref(),
+        Some(content_hash(b"hello\n").as_str())
+    );
+    assert!(state.content_hash.unwrap().starts_with("sha256:"));
+    assert!(state.modified.is_some());
+
+    let mut hasher = ContentHasher::default();
+    hasher.update(b"hel");
+    hasher.update(b"lo\n");
+    assert_eq!(hasher.finish(), content_hash(b"hello\n"));
+
+    // Directories have a modification time but no hash
+    let dir = fingerprint(temp.path()).await.unwrap();
+    assert!(dir.content_hash.is_none() && dir.modified.is_some());
+}
+
+#[tokio::test]
+async fn test_if_match_by_hash() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("a.txt");
+    fs::write(&path, "v1").unwrap();
+    let hash = content_hash(b"v1");
+
+    check_if_match(&path, None).await.unwrap();
+    check_if_match(&path, Some(&hash)).await.unwrap();
+    check_if_match(
+        &path,
+        Some(&hash.to_uppercase().replace("SHA256:", "sha256:")),
+    )
+    .await
+    .unwrap();
+
+    fs::write(&path, "v2").unwrap();
+    assert_conflict(check_if_match(&path, Some(&hash)).await);
+
+    fs::remove_file(&path).unwrap();
+    assert_conflict(check_if_match(&path, Some(&hash)).await);
+}
+
+#[tokio::test]
+async fn test_if_match_by_modified_time() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("a.txt");
+    fs::write(&path, "v1").unwrap();
+    let modified = fingerprint(&path).await.unwrap().modified.unwrap();
+
+    check_if_match(&path, Some(&modified)).await.unwrap();
+    check_if_match(
+        temp.path(),
+        Some(&fingerprint(temp.path()).await.unwrap().modified.unwrap()),
+    )
+    .await
+    .unwrap();
+
+    assert_conflict(check_if_match(&path, Some("2001-01-01T00:00:00Z")).await);
+    assert!(matches!(
+        check_if_match(&path, Some("yesterday")).await,
+        Err(McpError::InvalidArguments(msg)) if msg.starts_with("if_match must be")
+    ));
+    // Directories cannot be matched by hash
+    assert!(matches!(
+        check_if_match(temp.path(), Some(&content_hash(b""))).await,
+        Err(McpError::InvalidArguments(msg)) if msg.contains("no content hash")
+    ));
+}
+
+#[tokio::test]
+async fn test_read_if_match_returns_the_checked_bytes() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("a.txt");
+    fs::write(&path, "v1").unwrap();
+    let hash = content_hash(b"v1");
+    let modified = fingerprint(&path).await.unwrap().modified.unwrap();
+
+    assert_eq!(read_if_match(&path, None).await.unwrap().unwrap(), b"v1");
+    assert_eq!(
+        read_if_match(&path, Some(&hash)).await.unwrap().unwrap(),
+        b"v1"
+    );
+    assert_eq!(
+        read_if_match(&path, Some(&modified))
+            .await
+            .unwrap()
+            .unwrap(),
+        b"v1"
+    );
+
+    fs::write(&path, "v2").unwrap();
+    assert_conflict(read_if_match(&path, Some(&hash)).await.map(drop));
+
+    // A missing file reads as None, and fails any precondition
+    fs::remove_file(&path).unwrap();
+    assert!(read_if_match(&path, None).await.unwrap().is_none());
+    assert_conflict(read_if_match(&path, Some(&hash)).await.map(drop));
+}
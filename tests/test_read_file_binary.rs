@@ -0,0 +1,63 @@
+/*!
+Tests for binary detection and magic-number identification in fs_read_file
+*/
+
+use kodegen_tools_filesystem::read_file::binary::{
+    SNIFF_LEN, identify_format, looks_binary, read_header,
+};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_looks_binary_uses_nul_heuristic() {
+    assert!(looks_binary(b"\x7fELF\x02\x01\x01\x00"));
+    assert!(!looks_binary(b"fn main() {}\n"));
+    // Non-UTF-8 without NUL bytes is not considered binary (e.g. Latin-1 text)
+    assert!(!looks_binary(b"caf\xe9\n"));
+    // Like fs_search, UTF-16 with a BOM is transcoded before the NUL check
+    assert!(!looks_binary(b"\xff\xfeh\0i\0\n\0"));
+    assert!(looks_binary(b"h\0i\0\n\0"));
+}
+
+#[test]
+fn test_identify_common_formats() {
+    let cases: &[(&[u8], &str)] = &[
+        (b"\x7fELF\x02\x01\x01\x00", "ELF executable"),
+        (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", "PNG image"),
+        (b"SQLite format 3\0\x10\0", "SQLite database"),
+        (b"\0asm\x01\0\0\0", "WebAssembly module"),
+        (b"\x1f\x8b\x08\0", "gzip archive"),
+        (b"PK\x03\x04\x14\0", "ZIP archive"),
+        (b"MZ\x90\0\x03\0", "PE/COFF executable"),
+    ];
+
+    for (header, expected) in cases {
+        let format = identify_format(header).expect("format should be identified");
+        assert_eq!(format.name, *expected);
+    }
+
+    assert!(identify_format(b"\0\0\0\0plain data").is_none());
+}
+
+#[test]
+fn test_identify_tar_by_ustar_offset() {
+    let mut header = vec![0u8; 512];
+    header[..8].copy_from_slice(b"file.txt");
+    header[257..262].copy_from_slice(b"ustar");
+
+    let format = identify_format(&header).expect("tar should be identified");
+    assert_eq!(format.name, "tar archive");
+    assert_eq!(format.mime_type, "application/x-tar");
+}
+
+#[tokio::test]
+async fn test_read_header_is_bounded() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let small = temp_dir.path().join("small.bin");
+    let large = temp_dir.path().join("large.bin");
+    fs::write(&small, b"\x7fELF").expect("Failed to write file");
+    fs::write(&large, vec![0u8; SNIFF_LEN * 2]).expect("Failed to write file");
+
+    assert_eq!(read_header(&small).await.expect("small header"), b"\x7fELF");
+    assert_eq!(read_header(&large).await.expect("large header").len(), SNIFF_LEN);
+}
@@ -0,0 +1,67 @@
+/*!
+Tests for byte-addressed reads and hex dump rendering in fs_read_file
+*/
+
+use kodegen_tools_filesystem::read_file::byte_range::{decode_utf8_window, read_byte_window};
+use kodegen_tools_filesystem::read_file::hex_dump::hex_dump;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_hex_dump_full_and_short_rows() {
+    let bytes: Vec<u8> = b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00abc".to_vec();
+    let dump = hex_dump(&bytes, 0x10);
+    let rows: Vec<&str> = dump.lines().collect();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        rows[0],
+        "00000010: 7f45 4c46 0201 0100 0000 0000 0000 0000  .ELF............"
+    );
+    // Short final row is padded so the ASCII column stays aligned
+    assert_eq!(
+        rows[1],
+        "00000020: 6162 63                                  abc"
+    );
+}
+
+#[tokio::test]
+async fn test_read_byte_window_forward_and_tail() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("data.bin");
+    fs::write(&path, b"0123456789").expect("Failed to write file");
+
+    let window = read_byte_window(&path, 3, 4).await.expect("forward read");
+    assert_eq!(window.offset, 3);
+    assert_eq!(window.bytes, b"3456");
+    assert_eq!(window.total_bytes, 10);
+    assert!(window.is_partial());
+
+    let tail = read_byte_window(&path, -2, 100).await.expect("tail read");
+    assert_eq!(tail.offset, 8);
+    assert_eq!(tail.bytes, b"89");
+
+    let past_end = read_byte_window(&path, 50, 4).await.expect("read past EOF");
+    assert_eq!(past_end.offset, 10);
+    assert!(past_end.bytes.is_empty());
+
+    let whole = read_byte_window(&path, 0, 100).await.expect("whole read");
+    assert!(!whole.is_partial());
+}
+
+#[test]
+fn test_decode_utf8_window_trims_cut_characters() {
+    // "é" is 0xC3 0xA9; a window starting mid-character and ending mid-character
+    let bytes = b"\xa9abc\xc3";
+    let (skip, text) = decode_utf8_window(bytes, false).expect("should decode");
+    assert_eq!(skip, 1);
+    assert_eq!(text, "abc");
+}
+
+#[test]
+fn test_decode_utf8_window_rejects_binary() {
+    assert!(decode_utf8_window(b"abc\0def", true).is_none());
+    assert!(decode_utf8_window(b"\xff\xfe\x00\x01garbage", true).is_none());
+    // Leading continuation bytes at file start are not trimmed
+    assert!(decode_utf8_window(b"\xa9abc", true).is_none());
+}
@@ -0,0 +1,35 @@
+/*!
+Tests for cat -n style line numbering in fs_read_file
+*/
+
+use kodegen_tools_filesystem::read_file::line_numbers::number_lines;
+use kodegen_tools_filesystem::read_file::render_line_window;
+
+#[test]
+fn test_number_lines_uses_absolute_numbers() {
+    let lines = vec!["fn main() {".to_string(), "}".to_string()];
+    assert_eq!(number_lines(&lines, 41), "    41\tfn main() {\n    42\t}");
+}
+
+#[test]
+fn test_number_lines_widens_past_six_digits() {
+    let lines = vec!["x".to_string()];
+    assert_eq!(number_lines(&lines, 1_234_567), "1234567\tx");
+    assert_eq!(number_lines(&[], 1), "");
+}
+
+#[test]
+fn test_window_notice_uses_start_line_coordinates() {
+    let lines = vec!["d".to_string(), "e".to_string()];
+    let (content, partial, start, end) = render_line_window(lines.clone(), Some(10), 3, false);
+    // fs_read_file reports start_line = start + 1 and end_line = end
+    let (start_line, end_line) = (start + 1, end);
+    assert!(partial);
+    assert_eq!((start_line, end_line), (4, 5));
+    assert!(content.starts_with("[Reading 2 lines (lines 4-5) of 10 total lines]\n\n"));
+
+    let (content, _, start, end) = render_line_window(lines, Some(10), -2, true);
+    assert_eq!((start + 1, end), (9, 10));
+    assert!(content.starts_with("[Reading last 2 lines (lines 9-10) of 10 total lines]"));
+    assert!(content.ends_with("     9\td\n    10\te"));
+}
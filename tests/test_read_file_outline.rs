@@ -0,0 +1,132 @@
+/*!
+Tests for symbol/outline-aware reads in fs_read_file
+*/
+
+use kodegen_tools_filesystem::read_file::outline::{Language, SymbolKind, find_symbols, outline};
+use std::path::Path;
+
+const RUST_SOURCE: &str = r##"use std::fmt;
+
+/// A tool
+#[derive(Clone)]
+pub struct Tool {
+    name: String,
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "}} {}", self.name)
+    }
+}
+
+impl Tool {
+    /// Run it
+    pub async fn execute(&self) -> Result<(), String> {
+        let brace = '{';
+        let raw = r#"fn fake() {"#;
+        // fn commented() {
+        if brace == '{' {
+            return Ok(());
+        }
+        Ok(())
+    }
+}
+
+fn execute() {}
+"##;
+
+fn span(text: &str, language: Language, selector: &str) -> (usize, usize) {
+    let found = find_symbols(text, language, selector);
+    let symbol = found.first().unwrap_or_else(|| panic!("{selector} not found"));
+    (symbol.start_line + 1, symbol.end_line + 1)
+}
+
+#[test]
+fn test_rust_outline_ignores_strings_and_comments() {
+    let symbols = outline(RUST_SOURCE, Language::Rust);
+    let names: Vec<String> = symbols.iter().map(|s| s.display_name()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "struct Tool",
+            "impl Tool",
+            "fn Tool::fmt",
+            "impl Tool",
+            "fn Tool::execute",
+            "fn execute",
+        ]
+    );
+}
+
+#[test]
+fn test_rust_symbol_spans_include_docs_and_attributes() {
+    assert_eq!(span(RUST_SOURCE, Language::Rust, "struct Tool"), (3, 7));
+    assert_eq!(span(RUST_SOURCE, Language::Rust, "Tool::execute"), (16, 25));
+    assert_eq!(span(RUST_SOURCE, Language::Rust, "impl fmt::Display for Tool"), (9, 13));
+
+    // Unqualified names match in source order
+    let all = find_symbols(RUST_SOURCE, Language::Rust, "execute");
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[1].parent, None);
+    assert_eq!(all[1].start_line + 1, 28);
+}
+
+#[test]
+fn test_clike_and_python_outlines() {
+    let ts = "export class Service {\n  constructor(x) {\n    this.x = x;\n  }\n\n  async run(a: string): Promise<void> {\n    if (a) {\n      call(a);\n    }\n  }\n}\n\nexport const helper = (n: number) => {\n  return n;\n};\n";
+    assert_eq!(span(ts, Language::CLike, "Service.run"), (6, 10));
+    assert_eq!(span(ts, Language::CLike, "helper"), (13, 15));
+    assert!(find_symbols(ts, Language::CLike, "call").is_empty());
+
+    let go = "package main\n\nfunc (s *Server) Start(port int) error {\n\treturn nil\n}\n";
+    assert_eq!(span(go, Language::CLike, "Server.Start"), (3, 5));
+
+    let py = "class Greeter:\n    \"\"\"Says hi.\n\ndef not_a_def():\n    \"\"\"\n\n    @staticmethod\n    def greet(name,\n              punctuation='!'):\n        return name\n\n\ndef main():\n    pass\n";
+    let symbols = outline(py, Language::Python);
+    let names: Vec<String> = symbols.iter().map(|s| s.display_name()).collect();
+    assert_eq!(names, vec!["class Greeter", "fn Greeter::greet", "fn main"]);
+    assert_eq!(span(py, Language::Python, "Greeter.greet"), (7, 10));
+    assert_eq!(span(py, Language::Python, "class Greeter"), (1, 10));
+}
+
+#[test]
+fn test_markdown_sections_nest() {
+    let md = "# Title\n\nIntro\n\n## Install\n\nSteps\n\n```sh\n# not a heading\n```\n\n### Linux\n\napt\n\n## Usage\n\nRun it\n";
+    assert_eq!(span(md, Language::Markdown, "Install"), (5, 15));
+    assert_eq!(span(md, Language::Markdown, "### linux"), (13, 15));
+
+    let headings = outline(md, Language::Markdown);
+    assert_eq!(headings.len(), 4);
+    assert_eq!(headings[2].parent.as_deref(), Some("Install"));
+    assert!(headings.iter().all(|h| h.kind == SymbolKind::Heading));
+}
+
+#[test]
+fn test_json_key_paths() {
+    let json = "{\n  \"name\": \"demo\",\n  \"scripts\": {\n    \"build\": \"tsc\",\n    \"test\": \"jest\"\n  },\n  \"files\": [\n    { \"path\": \"a\" },\n    {\n      \"path\": \"b\"\n    }\n  ]\n}\n";
+    assert_eq!(span(json, Language::Json, "scripts"), (3, 6));
+    assert_eq!(span(json, Language::Json, "scripts.test"), (5, 5));
+    assert_eq!(span(json, Language::Json, "files[1]"), (9, 11));
+    assert_eq!(span(json, Language::Json, "files.1.path"), (10, 10));
+    assert!(find_symbols(json, Language::Json, "scripts.lint").is_empty());
+}
+
+#[test]
+fn test_toml_key_paths() {
+    let toml = "[package]\nname = \"demo\"\n\n[dependencies]\nserde = { version = \"1\", features = [\n  \"derive\",\n] }\ntokio = \"1\"\n\n[[bin]]\nname = \"a\"\n\n[[bin]]\nname = \"b\"\n";
+    assert_eq!(span(toml, Language::Toml, "package"), (1, 2));
+    assert_eq!(span(toml, Language::Toml, "dependencies.serde"), (5, 7));
+    assert_eq!(span(toml, Language::Toml, "dependencies.tokio"), (8, 8));
+    assert_eq!(span(toml, Language::Toml, "bin.1"), (13, 14));
+    assert_eq!(span(toml, Language::Toml, "bin.1.name"), (14, 14));
+    assert_eq!(span(toml, Language::Toml, "bin"), (10, 11));
+}
+
+#[test]
+fn test_language_from_path() {
+    assert_eq!(Language::from_path(Path::new("src/lib.rs")), Some(Language::Rust));
+    assert_eq!(Language::from_path(Path::new("app.tsx")), Some(Language::CLike));
+    assert_eq!(Language::from_path(Path::new("README.md")), Some(Language::Markdown));
+    assert_eq!(Language::from_path(Path::new("Cargo.toml")), Some(Language::Toml));
+    assert_eq!(Language::from_path(Path::new("notes.txt")), None);
+}
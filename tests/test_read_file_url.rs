@@ -0,0 +1,566 @@
+/*!
+Tests for URL reads in fs_read_file: content-type conversion, the access
+policy and the on-disk HTTP cache, against a local HTTP stand-in
+*/
+
+use kodegen_tools_filesystem::read_file::web::cache::{
+    CacheMode, CacheStatus, DEFAULT_CACHE_MAX_AGE, DEFAULT_CACHE_MAX_BYTES, HttpCache, fetch_cached,
+};
+use kodegen_tools_filesystem::read_file::web::convert::{
+    Conversion, UrlContent, convert, html_to_markdown, pretty_json,
+};
+use kodegen_tools_filesystem::read_file::web::policy::{UrlPolicy, host_matches, is_public};
+use kodegen_tools_filesystem::read_file::web::{FetchedBody, fetch};
+use reqwest::Url;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>Docs</title><style>body { color: red; }</style></head>
+<body>
+<nav><a href="/">Home</a> | <a href="/about">About</a></nav>
+<h1>Install</h1>
+<p>Run <code>cargo add demo</code> and see <a href="https://example.com/guide">the guide</a>.</p>
+<script>window.tracking = true;</script>
+<ul><li>fast</li><li>small</li></ul>
+<footer>Copyright</footer>
+</body>
+</html>"#;
+
+/// A raw HTTP/1.1 response
+fn response(status: &str, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut head = format!("HTTP/1.1 {status}\r\nConnection: close\r\n");
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    [head.into_bytes(), body.to_vec()].concat()
+}
+
+/// A 200 response with a declared length and optional content type
+fn ok(content_type: &str, body: &[u8]) -> Vec<u8> {
+    let length = body.len().to_string();
+    let mut headers = vec![("Content-Length", length.as_str())];
+    if !content_type.is_empty() {
+        headers.push(("Content-Type", content_type));
+    }
+    response("200 OK", &headers, body)
+}
+
+/// Serve responses from `handler` (given the request path and the raw
+/// request) on an ephemeral loopback port
+async fn serve<F>(handler: F) -> String
+where
+    F: Fn(&str, &str) -> Vec<u8> + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            let mut request = vec![0u8; 4096];
+            let n = stream.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..n]).to_string();
+            let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+
+            let _ = stream.write_all(&handler(&path, &request)).await;
+            let _ = stream.shutdown().await;
+        }
+    });
+
+    format!("http://{addr}")
+}
+
+/// The default policy, with the loopback stand-in explicitly allowed
+fn local_policy() -> UrlPolicy {
+    UrlPolicy {
+        allowed_hosts: vec!["127.0.0.1".to_string()],
+        timeout: Duration::from_secs(5),
+        ..UrlPolicy::default()
+    }
+}
+
+fn body(content_type: &str, bytes: &[u8]) -> FetchedBody {
+    FetchedBody {
+        url: "http://stand-in/resource".to_string(),
+        content_type: content_type.to_string(),
+        bytes: bytes.to_vec(),
+        ..FetchedBody::default()
+    }
+}
+
+fn text(content: UrlContent) -> (String, Option<Conversion>) {
+    match content {
+        UrlContent::Text { text, conversion } => (text, conversion),
+        UrlContent::Image => panic!("expected text"),
+    }
+}
+
+#[test]
+fn test_html_to_markdown_strips_page_chrome() {
+    let markdown = html_to_markdown(PAGE);
+
+    assert!(markdown.starts_with("# Install"), "{markdown}");
+    assert!(markdown.contains("`cargo add demo`"));
+    assert!(markdown.contains("[the guide](https://example.com/guide)"));
+    assert!(markdown.contains("fast") && markdown.contains("small"));
+    for chrome in ["color: red", "tracking", "About", "Copyright"] {
+        assert!(
+            !markdown.contains(chrome),
+            "{chrome} leaked into {markdown}"
+        );
+    }
+    assert!(!markdown.contains("\n\n\n"));
+}
+
+#[test]
+fn test_convert_dispatches_on_content_type() {
+    let (markdown, conversion) =
+        text(convert(&body("text/html; charset=utf-8", PAGE.as_bytes())).unwrap());
+    assert_eq!(conversion, Some(Conversion::HtmlToMarkdown));
+    assert!(markdown.starts_with("# Install"));
+
+    let (json, conversion) = text(
+        convert(&body(
+            "application/vnd.api+json",
+            br#"{"a":[1,2],"b":null}"#,
+        ))
+        .unwrap(),
+    );
+    assert_eq!(conversion, Some(Conversion::PrettyJson));
+    assert_eq!(json, "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": null\n}");
+
+    // Malformed JSON is returned unchanged
+    let (raw, conversion) = text(convert(&body("application/json", b"{oops")).unwrap());
+    assert_eq!((raw.as_str(), conversion), ("{oops", None));
+
+    // Declared charsets are honored
+    let (latin1, _) = text(convert(&body("text/plain; charset=ISO-8859-1", b"caf\xe9")).unwrap());
+    assert_eq!(latin1, "café");
+
+    // Untyped bodies are sniffed
+    let (sniffed, conversion) = text(convert(&body("", PAGE.as_bytes())).unwrap());
+    assert_eq!(conversion, Some(Conversion::HtmlToMarkdown));
+    assert!(sniffed.contains("# Install"));
+
+    assert_eq!(
+        convert(&body("image/png", b"\x89PNG\r\n\x1a\n")).unwrap(),
+        UrlContent::Image
+    );
+    assert_eq!(pretty_json("[1]").as_deref(), Some("[\n  1\n]"));
+}
+
+#[test]
+fn test_convert_refuses_pdf_and_binary() {
+    let err = convert(&body("application/pdf", b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n")).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("application/pdf"), "{message}");
+    assert!(message.contains("PDF document 1.7"), "{message}");
+    assert!(message.contains("15 bytes"), "{message}");
+
+    let err = convert(&body(
+        "application/octet-stream",
+        b"\x7fELF\x02\x01\x01\0\0\0",
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("ELF executable"));
+
+    // Generic types carrying text are still readable
+    let (plain, _) = text(convert(&body("application/octet-stream", b"just text\n")).unwrap());
+    assert_eq!(plain, "just text\n");
+}
+
+#[tokio::test]
+async fn test_fetch_from_local_stand_in() {
+    let base = serve(|path, _| match path {
+        "/docs" => ok("text/html; charset=utf-8", PAGE.as_bytes()),
+        "/data" => ok("application/json", br#"{"ok":true}"#),
+        "/report.pdf" => ok("application/pdf", b"%PDF-1.4\n"),
+        _ => response("404 Not Found", &[("Content-Length", "0")], b""),
+    })
+    .await;
+    let policy = local_policy();
+
+    let page = fetch(&format!("{base}/docs"), &policy).await.unwrap();
+    assert_eq!(page.content_type, "text/html; charset=utf-8");
+    assert_eq!(page.bytes.len(), PAGE.len());
+    let (markdown, _) = text(convert(&page).unwrap());
+    assert!(markdown.starts_with("# Install"));
+
+    let data = fetch(&format!("{base}/data"), &policy).await.unwrap();
+    assert_eq!(text(convert(&data).unwrap()).0, "{\n  \"ok\": true\n}");
+
+    let pdf = fetch(&format!("{base}/report.pdf"), &policy).await.unwrap();
+    let message = convert(&pdf).unwrap_err().to_string();
+    assert!(message.contains(&format!("{base}/report.pdf")), "{message}");
+
+    let missing = fetch(&format!("{base}/missing"), &policy)
+        .await
+        .unwrap_err();
+    assert!(missing.to_string().contains("404"));
+}
+
+#[test]
+fn test_policy_host_rules() {
+    assert!(host_matches("example.com", "docs.example.com", Some(443)));
+    assert!(host_matches("example.com", "EXAMPLE.com.", None));
+    assert!(!host_matches("*.example.com", "example.com", None));
+    assert!(!host_matches("example.com", "badexample.com", None));
+    assert!(host_matches("localhost:8080", "localhost", Some(8080)));
+    assert!(!host_matches("localhost:8080", "localhost", Some(9090)));
+    assert!(host_matches("[::1]:8080", "::1", Some(8080)));
+
+    for private in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "::1",
+        "fe80::1",
+        "fd00::1",
+        "::ffff:10.0.0.1",
+        // IPv4 embedded in IPv6: IPv4-compatible, NAT64 and 6to4
+        "::10.0.0.1",
+        "64:ff9b::a9fe:a9fe",
+        "2002:a9fe:a9fe::1",
+        "2002:7f00:1::",
+        // Discard-only and IETF protocol assignments
+        "100::1",
+        "2001::1",
+        "2001:1ff::1",
+        "64:ff9b:1::8.8.8.8",
+    ] {
+        assert!(!is_public(private.parse().unwrap()), "{private}");
+    }
+    for public in [
+        "93.184.216.34",
+        "1.1.1.1",
+        "2606:4700::1111",
+        "64:ff9b::808:808",
+        "2002:808:808::1",
+        "::8.8.8.8",
+        "2001:4860::8888",
+    ] {
+        assert!(is_public(public.parse().unwrap()), "{public}");
+    }
+
+    let check = |policy: &UrlPolicy, url: &str| policy.check_url(&Url::parse(url).unwrap());
+
+    // Private and link-local addresses are blocked by default
+    let default = UrlPolicy::default();
+    assert!(check(&default, "http://169.254.169.254/latest/meta-data").is_err());
+    assert!(check(&default, "http://[::1]:8080/").is_err());
+    assert!(check(&default, "http://[64:ff9b::a9fe:a9fe]/").is_err());
+    assert!(check(&default, "file:///etc/passwd").is_err());
+    assert!(check(&default, "https://example.com/").is_ok());
+
+    // Denied hosts win over allowed ones; a non-empty allow list is exclusive
+    let policy = UrlPolicy {
+        allowed_hosts: vec!["example.com".to_string(), "localhost".to_string()],
+        denied_hosts: vec!["internal.example.com".to_string()],
+        ..UrlPolicy::default()
+    };
+    assert!(check(&policy, "https://docs.example.com/").is_ok());
+    assert!(check(&policy, "https://internal.example.com/").is_err());
+    assert!(check(&policy, "https://rust-lang.org/").is_err());
+
+    let allow_private = UrlPolicy {
+        allow_private: true,
+        ..UrlPolicy::default()
+    };
+    assert!(check(&allow_private, "http://10.0.0.5/").is_ok());
+}
+
+#[tokio::test]
+async fn test_fetch_enforces_policy() {
+    let base = serve(|path, _| match path {
+        "/big" => ok("text/plain", &[b'x'; 4096]),
+        // No Content-Length: the cap must be enforced while streaming
+        "/stream" => response("200 OK", &[("Content-Type", "text/plain")], &[b'y'; 4096]),
+        "/hop/3" => ok("text/plain", b"arrived"),
+        hop if hop.starts_with("/hop/") => {
+            let n: u32 = hop[5..].parse().unwrap();
+            let next = format!("/hop/{}", n + 1);
+            response(
+                "302 Found",
+                &[("Location", next.as_str()), ("Content-Length", "0")],
+                b"",
+            )
+        }
+        "/escape" => response(
+            "302 Found",
+            &[
+                ("Location", "http://169.254.169.254/"),
+                ("Content-Length", "0"),
+            ],
+            b"",
+        ),
+        _ => response("404 Not Found", &[("Content-Length", "0")], b""),
+    })
+    .await;
+
+    // Loopback is refused unless explicitly allowed
+    let err = fetch(&format!("{base}/big"), &UrlPolicy::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("non-public address"), "{err}");
+
+    // Host names are checked after resolution too
+    let port = base.rsplit(':').next().unwrap();
+    let err = fetch(
+        &format!("http://localhost:{port}/big"),
+        &UrlPolicy::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("non-public address"), "{err}");
+
+    let small = UrlPolicy {
+        max_body_bytes: 1024,
+        ..local_policy()
+    };
+    for path in ["/big", "/stream"] {
+        let err = fetch(&format!("{base}{path}"), &small).await.unwrap_err();
+        assert!(err.to_string().contains("1024 byte limit"), "{path}: {err}");
+    }
+    assert_eq!(
+        fetch(&format!("{base}/stream"), &local_policy())
+            .await
+            .unwrap()
+            .bytes
+            .len(),
+        4096
+    );
+
+    let body = fetch(&format!("{base}/hop/0"), &local_policy())
+        .await
+        .unwrap();
+    assert_eq!(body.bytes, b"arrived");
+    assert!(body.url.ends_with("/hop/3"));
+
+    let two_hops = UrlPolicy {
+        max_redirects: 2,
+        ..local_policy()
+    };
+    let err = fetch(&format!("{base}/hop/0"), &two_hops)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("stopped after 2 redirects"),
+        "{err}"
+    );
+
+    let err = fetch(&format!("{base}/escape"), &local_policy())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("redirect refused"), "{err}");
+}
+
+#[tokio::test]
+async fn test_cached_fetch_revalidates_and_serves_offline() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let failing = Arc::new(AtomicBool::new(false));
+    let base = {
+        let requests = Arc::clone(&requests);
+        let failing = Arc::clone(&failing);
+        serve(move |path, request| {
+            requests.fetch_add(1, Ordering::SeqCst);
+            if failing.load(Ordering::SeqCst) {
+                return response("503 Service Unavailable", &[("Content-Length", "0")], b"");
+            }
+            let conditional = request
+                .to_ascii_lowercase()
+                .contains("if-none-match: \"v1\"");
+            match path {
+                "/doc" if conditional => response("304 Not Modified", &[("ETag", "\"v1\"")], b""),
+                "/doc" => response(
+                    "200 OK",
+                    &[
+                        ("Content-Type", "text/plain"),
+                        ("Content-Length", "7"),
+                        ("ETag", "\"v1\""),
+                    ],
+                    b"version",
+                ),
+                "/secret" => response(
+                    "200 OK",
+                    &[("Content-Length", "6"), ("Cache-Control", "no-store")],
+                    b"secret",
+                ),
+                _ => response("404 Not Found", &[("Content-Length", "0")], b""),
+            }
+        })
+        .await
+    };
+    let dir = TempDir::new().unwrap();
+    let policy = local_policy();
+    let doc = format!("{base}/doc");
+
+    // Offline reads fail until the URL has been cached
+    let err = fetch_cached(
+        &doc,
+        &policy,
+        &HttpCache::new(
+            dir.path(),
+            Duration::ZERO,
+            DEFAULT_CACHE_MAX_BYTES,
+            DEFAULT_CACHE_MAX_AGE,
+        ),
+        CacheMode::Only,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("not in the URL cache"), "{err}");
+    assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+    // A zero TTL makes every cached read revalidate with the stored ETag
+    let revalidating = HttpCache::new(
+        dir.path(),
+        Duration::ZERO,
+        DEFAULT_CACHE_MAX_BYTES,
+        DEFAULT_CACHE_MAX_AGE,
+    );
+    let (body, status) = fetch_cached(&doc, &policy, &revalidating, CacheMode::Use)
+        .await
+        .unwrap();
+    assert_eq!(
+        (body.bytes.as_slice(), status),
+        (&b"version"[..], CacheStatus::Miss)
+    );
+    let (body, status) = fetch_cached(&doc, &policy, &revalidating, CacheMode::Use)
+        .await
+        .unwrap();
+    assert_eq!(
+        (body.bytes.as_slice(), status),
+        (&b"version"[..], CacheStatus::Revalidated)
+    );
+    assert_eq!(body.etag.as_deref(), Some("\"v1\""));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    // Fresh entries are served without a request; refresh always downloads
+    let fresh = HttpCache::new(
+        dir.path(),
+        Duration::from_secs(60),
+        DEFAULT_CACHE_MAX_BYTES,
+        DEFAULT_CACHE_MAX_AGE,
+    );
+    let (_, status) = fetch_cached(&doc, &policy, &fresh, CacheMode::Use)
+        .await
+        .unwrap();
+    assert_eq!(status, CacheStatus::Hit);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    let (_, status) = fetch_cached(&doc, &policy, &fresh, CacheMode::Refresh)
+        .await
+        .unwrap();
+    assert_eq!(status, CacheStatus::Miss);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    // Server failures fall back to the cached copy; offline reads need no server
+    failing.store(true, Ordering::SeqCst);
+    let (body, status) = fetch_cached(&doc, &policy, &revalidating, CacheMode::Use)
+        .await
+        .unwrap();
+    assert_eq!(
+        (body.bytes.as_slice(), status),
+        (&b"version"[..], CacheStatus::Stale)
+    );
+    let (_, status) = fetch_cached(&doc, &policy, &revalidating, CacheMode::Only)
+        .await
+        .unwrap();
+    assert_eq!(status, CacheStatus::Hit);
+    failing.store(false, Ordering::SeqCst);
+
+    // no-store responses are never written to disk
+    let secret = format!("{base}/secret");
+    fetch_cached(&secret, &policy, &fresh, CacheMode::Use)
+        .await
+        .unwrap();
+    assert!(
+        fetch_cached(&secret, &policy, &fresh, CacheMode::Only)
+            .await
+            .is_err()
+    );
+
+    // Host rules still apply to cached URLs
+    let denied = UrlPolicy {
+        denied_hosts: vec!["127.0.0.1".to_string()],
+        ..local_policy()
+    };
+    assert!(
+        fetch_cached(&doc, &denied, &fresh, CacheMode::Only)
+            .await
+            .is_err()
+    );
+
+    assert!(CacheMode::parse("REFRESH").is_ok_and(|mode| mode == CacheMode::Refresh));
+    assert!(CacheMode::parse("sometimes").is_err());
+}
+
+#[tokio::test]
+async fn test_cache_prunes_by_size_and_age() {
+    let base = serve(|path, _| match path {
+        "/a" | "/b" => ok("text/plain", &[b'x'; 1000]),
+        _ => response("404 Not Found", &[("Content-Length", "0")], b""),
+    })
+    .await;
+    let dir = TempDir::new().unwrap();
+    let policy = local_policy();
+    let (a, b) = (format!("{base}/a"), format!("{base}/b"));
+
+    // Room for one entry: storing the second evicts the older one
+    let small = HttpCache::new(
+        dir.path(),
+        Duration::from_secs(60),
+        1500,
+        DEFAULT_CACHE_MAX_AGE,
+    );
+    fetch_cached(&a, &policy, &small, CacheMode::Use)
+        .await
+        .unwrap();
+    assert!(
+        fetch_cached(&a, &policy, &small, CacheMode::Only)
+            .await
+            .is_ok()
+    );
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    fetch_cached(&b, &policy, &small, CacheMode::Use)
+        .await
+        .unwrap();
+    assert!(
+        fetch_cached(&a, &policy, &small, CacheMode::Only)
+            .await
+            .is_err()
+    );
+    assert!(
+        fetch_cached(&b, &policy, &small, CacheMode::Only)
+            .await
+            .is_ok()
+    );
+
+    // Entries past the age limit are pruned however much room is left
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let expiring = HttpCache::new(
+        dir.path(),
+        Duration::from_secs(60),
+        DEFAULT_CACHE_MAX_BYTES,
+        Duration::ZERO,
+    );
+    expiring.prune().await;
+    assert!(
+        fetch_cached(&b, &policy, &small, CacheMode::Only)
+            .await
+            .is_err()
+    );
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
@@ -0,0 +1,63 @@
+/*!
+Tests that URL reads ignore proxy settings from the environment
+
+A proxy resolves the target host itself, which would bypass the URL
+policy's address checks. This lives in its own test binary because it
+changes the process environment.
+*/
+
+use kodegen_tools_filesystem::read_file::web::fetch;
+use kodegen_tools_filesystem::read_file::web::policy::UrlPolicy;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn test_fetch_ignores_environment_proxy() {
+    // A "proxy" that answers every request it receives
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let proxy = listener.local_addr().unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&connections);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            seen.fetch_add(1, Ordering::SeqCst);
+            let mut request = vec![0u8; 4096];
+            let _ = stream.read(&mut request).await;
+            let _ = stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nproxied",
+                )
+                .await;
+            let _ = stream.shutdown().await;
+        }
+    });
+
+    let proxy_url = format!("http://{proxy}");
+    for var in [
+        "HTTP_PROXY",
+        "http_proxy",
+        "HTTPS_PROXY",
+        "https_proxy",
+        "ALL_PROXY",
+    ] {
+        // SAFETY: this test binary runs no other threads that read the environment
+        unsafe { std::env::set_var(var, &proxy_url) };
+    }
+    for var in ["NO_PROXY", "no_proxy"] {
+        // SAFETY: as above
+        unsafe { std::env::remove_var(var) };
+    }
+
+    // localhost resolves to loopback, which the default policy blocks; going
+    // through the proxy would skip that check and return its response
+    let err = fetch(
+        &format!("http://localhost:{}/", proxy.port()),
+        &UrlPolicy::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("non-public address"), "{err}");
+    assert_eq!(connections.load(Ordering::SeqCst), 0);
+}
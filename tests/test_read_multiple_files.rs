@@ -0,0 +1,223 @@
+/*!
+Tests for per-entry ranges, glob expansion and the output budget in
+fs_read_multiple_files
+*/
+
+use futures::StreamExt;
+use kodegen_tools_filesystem::read_multiple_files::expand::{expand, is_glob, split_pattern};
+use kodegen_tools_filesystem::read_multiple_files::{
+    DEFAULT_MAX_TOTAL_BYTES, MAX_READ_CONCURRENCY, ReadLimits, read_concurrently,
+    truncate_to_budget,
+};
+use kodegen_tools_filesystem::schema::{FileReadResult, FsReadMultipleFilesArgs, ReadEntry};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[test]
+fn test_entries_accept_paths_and_ranges() {
+    let args: FsReadMultipleFilesArgs = serde_json::from_value(serde_json::json!({
+        "paths": [
+            "README.md",
+            {"path": "src/a.rs", "offset": 9, "length": 31},
+            {"path": "b.log", "offset": -20}
+        ],
+        "length": 100
+    }))
+    .unwrap();
+
+    assert_eq!(args.paths.len(), 3);
+    assert!(matches!(&args.paths[0], ReadEntry::Path(p) if p == "README.md"));
+    match &args.paths[1] {
+        ReadEntry::Spec(spec) => {
+            assert_eq!((spec.offset, spec.length), (Some(9), Some(31)));
+        }
+        ReadEntry::Path(_) => panic!("expected a ranged entry"),
+    }
+    assert_eq!(args.paths[2].path(), "b.log");
+
+    // A single string is still accepted
+    let single: FsReadMultipleFilesArgs =
+        serde_json::from_value(serde_json::json!({"paths": "one.txt"})).unwrap();
+    assert_eq!(single.paths[0].path(), "one.txt");
+}
+
+#[test]
+fn test_split_pattern() {
+    assert_eq!(
+        split_pattern("src/**/mod.rs"),
+        (PathBuf::from("src"), "**/mod.rs".to_string())
+    );
+    assert_eq!(
+        split_pattern("*.rs"),
+        (PathBuf::from("."), "*.rs".to_string())
+    );
+    assert_eq!(
+        split_pattern("/repo/crates/*/Cargo.toml"),
+        (PathBuf::from("/repo/crates"), "*/Cargo.toml".to_string())
+    );
+    assert!(is_glob("src/{a,b}.rs") && is_glob("file?.txt") && !is_glob("src/lib.rs"));
+}
+
+#[test]
+fn test_expand_respects_gitignore() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    fs::create_dir(root.join(".git")).unwrap();
+    fs::write(root.join(".gitignore"), "target/\n").unwrap();
+    for file in [
+        "src/lib.rs",
+        "src/read_file/mod.rs",
+        "src/edit/mod.rs",
+        "src/edit/deep/mod.rs",
+        "target/debug/mod.rs",
+    ] {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "// test\n").unwrap();
+    }
+
+    let found = expand(root, "**/mod.rs").unwrap();
+    assert_eq!(
+        found,
+        vec![
+            PathBuf::from("src/edit/deep/mod.rs"),
+            PathBuf::from("src/edit/mod.rs"),
+            PathBuf::from("src/read_file/mod.rs"),
+        ]
+    );
+
+    // `*` does not cross directories
+    let found = expand(&root.join("src"), "*/mod.rs").unwrap();
+    assert_eq!(
+        found,
+        vec![
+            PathBuf::from("edit/mod.rs"),
+            PathBuf::from("read_file/mod.rs")
+        ]
+    );
+
+    assert!(expand(root, "src/[").is_err());
+}
+
+#[test]
+fn test_truncate_to_budget() {
+    let mut content = "first line\nsecond line\nthird line".to_string();
+    assert!(!truncate_to_budget(&mut content.clone(), 100));
+
+    assert!(truncate_to_budget(&mut content, 15));
+    assert!(content.starts_with("first line\n\n[Truncated"), "{content}");
+
+    // No line fits: cut at a character boundary
+    let mut wide = "ééééé".to_string();
+    assert!(truncate_to_budget(&mut wide, 5));
+    assert!(wide.starts_with("éé\n[Truncated"), "{wide}");
+}
+
+/// Charge `budget` across reads that finish in reverse order, the way
+/// fs_read_multiple_files does, returning each file's size and whether it
+/// was truncated (`None` for files never charged)
+async fn charge_reversed_reads(
+    sizes: &[usize],
+    budget: usize,
+    in_request_order: bool,
+) -> Vec<Option<(usize, bool)>> {
+    let jobs: Vec<(usize, usize)> = sizes.iter().copied().enumerate().collect();
+    let count = jobs.len() as u64;
+    let mut reads = std::pin::pin!(read_concurrently(
+        jobs,
+        8,
+        in_request_order,
+        |(index, size)| async move {
+            // Later files finish first
+            tokio::time::sleep(Duration::from_millis(10 * (count - index as u64))).await;
+            (index, "x\n".repeat(size / 2))
+        }
+    ));
+
+    let mut charged = vec![None; sizes.len()];
+    let mut remaining = budget;
+    while remaining > 0
+        && let Some((index, mut content)) = reads.next().await
+    {
+        let size = content.len();
+        let truncated = truncate_to_budget(&mut content, remaining);
+        remaining = remaining.saturating_sub(size);
+        charged[index] = Some((size, truncated));
+    }
+    charged
+}
+
+#[tokio::test]
+async fn test_budget_is_charged_in_request_order() {
+    let sizes = [40, 100, 60, 200, 20, 80];
+    let first = charge_reversed_reads(&sizes, 170, true).await;
+
+    // 40 + 100 fit, the third file crosses the budget, the rest are skipped
+    assert_eq!(first[..2], [Some((40, false)), Some((100, false))]);
+    assert_eq!(first[2], Some((60, true)));
+    assert!(first[3..].iter().all(Option::is_none), "{first:?}");
+
+    for _ in 0..5 {
+        assert_eq!(charge_reversed_reads(&sizes, 170, true).await, first);
+    }
+}
+
+#[tokio::test]
+async fn test_streamed_reads_are_charged_as_they_finish() {
+    let sizes = [40, 100, 60, 200, 20, 80];
+    let charged = charge_reversed_reads(&sizes, 170, false).await;
+
+    // The last files finish first: 80 + 20 fit, the 200-byte file crosses
+    // the budget, and the slow files at the front are never waited on
+    assert!(charged[..3].iter().all(Option::is_none), "{charged:?}");
+    assert_eq!(charged[3], Some((200, true)));
+    assert_eq!(charged[4..], [Some((20, false)), Some((80, false))]);
+}
+
+#[test]
+fn test_read_limits_for_call() {
+    let server = ReadLimits::default();
+    assert_eq!(server.for_call(None, None), server);
+
+    let limits = server.for_call(Some(4), Some(1024));
+    assert_eq!((limits.concurrency, limits.max_total_bytes), (4, 1024));
+
+    // Concurrency is clamped; the byte budget can be lowered but not raised
+    let limits = server.for_call(Some(0), Some(usize::MAX));
+    assert_eq!(limits.concurrency, 1);
+    assert_eq!(limits.max_total_bytes, DEFAULT_MAX_TOTAL_BYTES);
+    assert_eq!(
+        server.for_call(Some(100_000), None).concurrency,
+        MAX_READ_CONCURRENCY
+    );
+}
+
+#[test]
+fn test_stream_flags_serialize_only_when_set() {
+    let args: FsReadMultipleFilesArgs = serde_json::from_value(serde_json::json!({
+        "paths": ["a.txt"],
+        "concurrency": 2,
+        "stream": true
+    }))
+    .unwrap();
+    assert_eq!(args.concurrency, Some(2));
+    assert!(args.stream);
+
+    let result = FileReadResult {
+        path: "a.txt".to_string(),
+        success: true,
+        content: Some("a".to_string()),
+        error: None,
+        mime_type: None,
+        start_line: None,
+        end_line: None,
+        truncated: false,
+        skipped: false,
+        streamed: false,
+        content_hash: None,
+    };
+    let json = serde_json::to_value(&result).unwrap();
+    assert!(json.get("streamed").is_none() && json.get("skipped").is_none());
+}
@@ -0,0 +1,131 @@
+//! Tests for fs_replace's file discovery and per-file replacement
+
+use kodegen_tools_filesystem::edit_block::pattern::apply_replacements;
+use kodegen_tools_filesystem::search::file_replacements;
+use kodegen_tools_filesystem::search::manager::content_search::scan_for_replace;
+use kodegen_tools_filesystem::search::rg::build_pattern_matcher;
+use kodegen_tools_filesystem::search::types::{
+    BinaryMode, BoundaryMode, CaseMode, Engine, ReturnMode, SearchIn, SearchSessionOptions,
+};
+use std::fs;
+use tempfile::TempDir;
+
+fn make_options(root_path: &str, pattern: &str) -> SearchSessionOptions {
+    SearchSessionOptions {
+        root_path: root_path.to_string(),
+        pattern: pattern.to_string(),
+        search_in: SearchIn::Content,
+        file_pattern: None,
+        r#type: vec![],
+        type_not: vec![],
+        case_mode: CaseMode::Sensitive,
+        max_results: None,
+        include_hidden: false,
+        no_ignore: false,
+        context: 0,
+        before_context: None,
+        after_context: None,
+        timeout_ms: None,
+        early_termination: None,
+        literal_search: false,
+        pattern_mode: None,
+        boundary_mode: None,
+        return_only: ReturnMode::Matches,
+        invert_match: false,
+        engine: Engine::Auto,
+        preprocessor: None,
+        preprocessor_globs: vec![],
+        search_zip: false,
+        binary_mode: BinaryMode::Auto,
+        multiline: false,
+        max_filesize: None,
+        max_depth: None,
+        only_matching: false,
+        sort_by: None,
+        sort_direction: None,
+        encoding: None,
+    }
+}
+
+#[test]
+fn test_scan_uses_search_filters_and_matcher() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join(".git")).unwrap();
+    fs::write(root.join(".gitignore"), "target/\n").unwrap();
+    fs::create_dir(root.join("target")).unwrap();
+    fs::write(root.join("a.rs"), "let user_id = 1;\nlet user_idx = 2;\n").unwrap();
+    fs::write(root.join("b.rs"), "print(user_id);\n").unwrap();
+    fs::write(root.join("notes.md"), "user_id\n").unwrap();
+    fs::write(root.join("target/ignored.rs"), "user_id\n").unwrap();
+    fs::write(root.join("none.rs"), "nothing here\n").unwrap();
+
+    let mut options = make_options(&root.to_string_lossy(), "user_(id)");
+    options.file_pattern = Some("*.rs".to_string());
+    options.boundary_mode = Some(BoundaryMode::Word);
+    let scan = scan_for_replace(&options, root, None).unwrap();
+    let names: Vec<_> = scan
+        .files
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names, vec!["a.rs", "b.rs"]);
+
+    // The matcher keeps the word boundary the search used
+    let content = fs::read_to_string(&scan.files[0]).unwrap();
+    let found = file_replacements(&scan.matcher, &content, "account_$1", false).unwrap();
+    let (new, _) = apply_replacements(&content, &found);
+    assert_eq!(new, "let account_id = 1;\nlet user_idx = 2;\n");
+}
+
+#[test]
+fn test_scan_rejects_invalid_regex() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("a.txt"), "f(x\n").unwrap();
+    let options = make_options(&temp_dir.path().to_string_lossy(), "f(x");
+    assert!(scan_for_replace(&options, temp_dir.path(), None).is_err());
+
+    let mut options = options;
+    options.literal_search = true;
+    let scan = scan_for_replace(&options, temp_dir.path(), None).unwrap();
+    assert_eq!(scan.files.len(), 1);
+}
+
+#[test]
+fn test_scan_rejects_patterns_matching_the_empty_string() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("a.txt"), "aé\n").unwrap();
+    fs::write(temp_dir.path().join("b.txt"), "other\n").unwrap();
+
+    for pattern in ["a*", "x?", ""] {
+        let options = make_options(&temp_dir.path().to_string_lossy(), pattern);
+        let error = scan_for_replace(&options, temp_dir.path(), None)
+            .err()
+            .unwrap_or_else(|| panic!("{pattern:?} was accepted"));
+        assert!(error.contains("empty string"), "{pattern:?}: {error}");
+    }
+
+    let mut options = make_options(&temp_dir.path().to_string_lossy(), "");
+    options.literal_search = true;
+    assert!(scan_for_replace(&options, temp_dir.path(), None).is_err());
+
+    // A pattern that needs at least one character is fine
+    let options = make_options(&temp_dir.path().to_string_lossy(), "a+");
+    let scan = scan_for_replace(&options, temp_dir.path(), None).unwrap();
+    assert_eq!(scan.files.len(), 1);
+}
+
+#[test]
+fn test_file_replacements_stay_on_one_line_unless_multiline() {
+    let content = "a\nb a  b\r\n";
+    let matcher =
+        build_pattern_matcher(r"a\s+b", Engine::PCRE2, CaseMode::Sensitive, false, false).unwrap();
+
+    let found = file_replacements(&matcher, content, "X", false).unwrap();
+    let (new, _) = apply_replacements(content, &found);
+    assert_eq!(new, "a\nb X\r\n");
+
+    let found = file_replacements(&matcher, content, "X", true).unwrap();
+    let (new, _) = apply_replacements(content, &found);
+    assert_eq!(new, "X X\r\n");
+}
@@ -0,0 +1,150 @@
+//! Tests for fs_search's content search file filters and per-mode file counts
+//!
+//! These run the real content search over a temporary tree: file_pattern
+//! globs must filter the walk, and Paths mode must list each matching file
+//! exactly once.
+
+use kodegen_tools_filesystem::search::manager::content_search::execute;
+use kodegen_tools_filesystem::search::manager::context::SearchContext;
+use kodegen_tools_filesystem::search::types::{
+    BinaryMode, CaseMode, Engine, ReturnMode, SearchIn, SearchSessionOptions,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn make_options(root_path: &str, pattern: &str, return_only: ReturnMode) -> SearchSessionOptions {
+    SearchSessionOptions {
+        root_path: root_path.to_string(),
+        pattern: pattern.to_string(),
+        search_in: SearchIn::Content,
+        file_pattern: None,
+        r#type: vec![],
+        type_not: vec![],
+        case_mode: CaseMode::Sensitive,
+        max_results: None,
+        include_hidden: false,
+        no_ignore: false,
+        context: 0,
+        before_context: None,
+        after_context: None,
+        timeout_ms: None,
+        early_termination: None,
+        literal_search: false,
+        pattern_mode: None,
+        boundary_mode: None,
+        return_only,
+        invert_match: false,
+        engine: Engine::Auto,
+        preprocessor: None,
+        preprocessor_globs: vec![],
+        search_zip: false,
+        binary_mode: BinaryMode::Auto,
+        multiline: false,
+        max_filesize: None,
+        max_depth: None,
+        only_matching: false,
+        sort_by: None,
+        sort_direction: None,
+        encoding: None,
+    }
+}
+
+/// A tree where every file mentions `needle`, some of them twice
+fn make_tree() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("src")).unwrap();
+    fs::write(root.join("src/lib.rs"), "needle\nneedle again\n").unwrap();
+    fs::write(root.join("src/main.rs"), "fn main() { needle(); }\n").unwrap();
+    fs::write(root.join("README.md"), "needle\nand needle\n").unwrap();
+    fs::write(root.join("notes.txt"), "needle\n").unwrap();
+    temp_dir
+}
+
+/// Run a content search and return (relative file, line) per result, sorted,
+/// with the context's total_files
+fn search(root: &Path, options: &SearchSessionOptions) -> (Vec<(String, Option<u32>)>, usize) {
+    let mut ctx = SearchContext::new(usize::MAX, options.return_only, None);
+    execute(options, &root.to_path_buf(), &mut ctx);
+    assert!(!ctx.is_error, "search failed: {:?}", ctx.error);
+    let total_files = ctx.total_files();
+
+    let mut found: Vec<(String, Option<u32>)> = ctx
+        .take_results()
+        .into_iter()
+        .map(|result| {
+            let file = PathBuf::from(&result.file);
+            let relative = file.strip_prefix(root).unwrap_or(&file);
+            (relative.to_string_lossy().replace('\\', "/"), result.line)
+        })
+        .collect();
+    found.sort();
+    (found, total_files)
+}
+
+fn files(found: &[(String, Option<u32>)]) -> Vec<&str> {
+    let mut files: Vec<&str> = found.iter().map(|(file, _)| file.as_str()).collect();
+    files.dedup();
+    files
+}
+
+#[test]
+fn test_file_pattern_filters_content_search() {
+    let temp_dir = make_tree();
+    let root = temp_dir.path();
+
+    let mut options = make_options(&root.to_string_lossy(), "needle", ReturnMode::Matches);
+    options.file_pattern = Some("*.rs".to_string());
+    let (found, _) = search(root, &options);
+    assert_eq!(files(&found), vec!["src/lib.rs", "src/main.rs"]);
+
+    // Several globs separated by `|`
+    options.file_pattern = Some("*.md|*.txt".to_string());
+    let (found, _) = search(root, &options);
+    assert_eq!(files(&found), vec!["README.md", "notes.txt"]);
+
+    // A glob no file matches finds nothing, rather than everything
+    options.file_pattern = Some("*.py".to_string());
+    let (found, total_files) = search(root, &options);
+    assert!(found.is_empty(), "{found:?}");
+    assert_eq!(total_files, 0);
+}
+
+#[test]
+fn test_paths_mode_lists_each_matching_file_once() {
+    let temp_dir = make_tree();
+    let root = temp_dir.path();
+
+    let options = make_options(&root.to_string_lossy(), "needle", ReturnMode::Paths);
+    let (found, total_files) = search(root, &options);
+    assert_eq!(
+        found,
+        vec![
+            ("README.md".to_string(), None),
+            ("notes.txt".to_string(), None),
+            ("src/lib.rs".to_string(), None),
+            ("src/main.rs".to_string(), None),
+        ]
+    );
+    assert_eq!(total_files, 4);
+
+    // Combined with a file_pattern
+    let mut options = options;
+    options.file_pattern = Some("*.rs".to_string());
+    let (found, total_files) = search(root, &options);
+    assert_eq!(files(&found), vec!["src/lib.rs", "src/main.rs"]);
+    assert_eq!(total_files, 2);
+}
+
+#[test]
+fn test_matches_mode_counts_distinct_files() {
+    let temp_dir = make_tree();
+    let root = temp_dir.path();
+
+    let options = make_options(&root.to_string_lossy(), "needle", ReturnMode::Matches);
+    let (found, total_files) = search(root, &options);
+    // Every matching line, but each file counted once
+    assert_eq!(found.len(), 6);
+    assert_eq!(total_files, 4);
+}
@@ -0,0 +1,191 @@
+/*!
+Tests for the trash behind fs_delete_file, fs_delete_directory, fs_restore and fs_empty_trash
+*/
+
+use kodegen_mcp_schema::McpError;
+use kodegen_tools_filesystem::journal::{DEFAULT_MAX_AGE, DEFAULT_MAX_BYTES, Journal};
+use kodegen_tools_filesystem::trash::Trash;
+use std::fs;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn trash(temp: &TempDir) -> Trash {
+    Trash::new(temp.path().join("Trash"), temp.path().join("trash-roots"))
+}
+
+#[tokio::test]
+async fn test_trash_file_writes_trashinfo_and_restores() {
+    let temp = TempDir::new().unwrap();
+    let trash = trash(&temp);
+    let path = temp.path().join("notes.txt");
+    fs::write(&path, "keep me").unwrap();
+
+    let item = trash.trash(&path, "c1").await.unwrap().expect("trashed");
+    assert!(!path.exists());
+    assert!(item.id.starts_with("notes.txt."));
+    assert_eq!(item.location(), trash.home().join("files").join(&item.id));
+    assert_eq!(fs::read_to_string(item.location()).unwrap(), "keep me");
+
+    let info = fs::read_to_string(
+        trash
+            .home()
+            .join("info")
+            .join(format!("{}.trashinfo", item.id)),
+    )
+    .unwrap();
+    assert!(info.starts_with("[Trash Info]\n"));
+    assert!(info.contains(&format!("Path={}\n", path.display())));
+    assert!(info.contains("DeletionDate="));
+    assert!(info.contains("X-Kodegen-Connection=c1\n"));
+
+    let listed = trash.list(Some("c1")).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].original_path, path);
+    assert!(trash.list(Some("c2")).await.unwrap().is_empty());
+
+    trash.restore(&listed[0], &path).await.unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "keep me");
+    assert!(trash.list(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_trash_directory_and_path_encoding() {
+    let temp = TempDir::new().unwrap();
+    let trash = trash(&temp);
+    let dir = temp.path().join("my dir%");
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("nested/inner.txt"), "inner").unwrap();
+
+    let item = trash.trash(&dir, "c1").await.unwrap().expect("trashed");
+    assert!(item.is_directory);
+    let info = fs::read_to_string(
+        trash
+            .home()
+            .join("info")
+            .join(format!("{}.trashinfo", item.id)),
+    )
+    .unwrap();
+    assert!(
+        info.contains("my%20dir%25\n"),
+        "path is percent-encoded: {info}"
+    );
+
+    let listed = trash.list(None).await.unwrap();
+    assert_eq!(listed[0].original_path, dir, "encoding round-trips");
+    assert!(listed[0].is_directory);
+
+    trash.restore(&listed[0], &dir).await.unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.join("nested/inner.txt")).unwrap(),
+        "inner"
+    );
+}
+
+#[tokio::test]
+async fn test_restore_refuses_to_replace_existing_path() {
+    let temp = TempDir::new().unwrap();
+    let trash = trash(&temp);
+    let path = temp.path().join("a.txt");
+    fs::write(&path, "old").unwrap();
+    let item = trash.trash(&path, "c1").await.unwrap().expect("trashed");
+    fs::write(&path, "new").unwrap();
+
+    assert!(matches!(
+        trash.restore(&item, &path).await,
+        Err(McpError::InvalidArguments(_))
+    ));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    assert!(item.location().exists(), "item stays in the trash");
+
+    let elsewhere = temp.path().join("restored/a.txt");
+    trash.restore(&item, &elsewhere).await.unwrap();
+    assert_eq!(fs::read_to_string(&elsewhere).unwrap(), "old");
+}
+
+#[tokio::test]
+async fn test_empty_trash_by_id_connection_and_age() {
+    let temp = TempDir::new().unwrap();
+    let trash = trash(&temp);
+    let mut items = Vec::new();
+    for (name, connection) in [("a", "c1"), ("b", "c1"), ("c", "c2")] {
+        let path = temp.path().join(name);
+        fs::write(&path, name).unwrap();
+        items.push(trash.trash(&path, connection).await.unwrap().unwrap());
+    }
+
+    // An unknown ID removes nothing
+    assert!(matches!(
+        trash.empty(None, &["missing".to_string()], None).await,
+        Err(McpError::ResourceNotFound(_))
+    ));
+    assert_eq!(trash.list(None).await.unwrap().len(), 3);
+
+    // Nothing is an hour old yet
+    let removed = trash
+        .empty(Some("c1"), &[], Some(Duration::from_secs(3600)))
+        .await
+        .unwrap();
+    assert!(removed.is_empty());
+
+    let removed = trash
+        .empty(None, &[items[0].id.clone()], None)
+        .await
+        .unwrap();
+    assert_eq!(removed.len(), 1);
+    assert!(!items[0].location().exists());
+
+    let removed = trash.empty(Some("c1"), &[], None).await.unwrap();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].id, items[1].id);
+
+    let left = trash.list(None).await.unwrap();
+    assert_eq!(left.len(), 1);
+    assert_eq!(left[0].connection, "c2");
+}
+
+#[tokio::test]
+async fn test_foreign_trash_entries_are_left_alone() {
+    let temp = TempDir::new().unwrap();
+    let trash = trash(&temp);
+    let files = trash.home().join("files");
+    let info = trash.home().join("info");
+    fs::create_dir_all(&files).unwrap();
+    fs::create_dir_all(&info).unwrap();
+    fs::write(files.join("photo.jpg"), "user's").unwrap();
+    fs::write(
+        info.join("photo.jpg.trashinfo"),
+        "[Trash Info]\nPath=/home/user/photo.jpg\nDeletionDate=2024-01-01T10:00:00\n",
+    )
+    .unwrap();
+
+    assert!(trash.list(None).await.unwrap().is_empty());
+    assert!(trash.empty(None, &[], None).await.unwrap().is_empty());
+    assert!(files.join("photo.jpg").exists());
+    assert!(info.join("photo.jpg.trashinfo").exists());
+}
+
+#[tokio::test]
+async fn test_undo_moves_trashed_item_back() {
+    let temp = TempDir::new().unwrap();
+    let trash = trash(&temp);
+    let journal = Journal::new(
+        temp.path().join("journal"),
+        DEFAULT_MAX_BYTES,
+        DEFAULT_MAX_AGE,
+    );
+    let path = temp.path().join("deleted.txt");
+    fs::write(&path, "keep me").unwrap();
+
+    let mut undo = journal.begin("c1", "fs_delete_file", "delete deleted.txt");
+    let item = trash.trash(&path, "c1").await.unwrap().expect("trashed");
+    undo.moved(&path, &item.location());
+    undo.commit().await.expect("journaled");
+
+    journal.undo("c1", 1, None, false).await.unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "keep me");
+
+    // The leftover info file is not listed, and emptying cleans it up
+    assert!(trash.list(None).await.unwrap().is_empty());
+    trash.empty(None, &[], None).await.unwrap();
+    assert_eq!(fs::read_dir(trash.home().join("info")).unwrap().count(), 0);
+}
@@ -0,0 +1,226 @@
+/*!
+Tests for the undo journal behind fs_undo and fs_history
+*/
+
+use kodegen_mcp_schema::McpError;
+use kodegen_tools_filesystem::journal::{DEFAULT_MAX_AGE, DEFAULT_MAX_BYTES, Journal};
+use std::fs;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn journal(temp: &TempDir) -> Journal {
+    Journal::new(
+        temp.path().join("journal"),
+        DEFAULT_MAX_BYTES,
+        DEFAULT_MAX_AGE,
+    )
+}
+
+#[tokio::test]
+async fn test_undo_restores_rewritten_file() {
+    let temp = TempDir::new().unwrap();
+    let journal = journal(&temp);
+    let path = temp.path().join("a.txt");
+    fs::write(&path, "old\n").unwrap();
+
+    let mut undo = journal.begin("c1", "fs_write_file", "rewrite a.txt");
+    undo.snapshot(&path).await;
+    fs::write(&path, "new\n").unwrap();
+    let id = undo.commit().await.expect("journaled");
+
+    let history = journal.history(Some("c1")).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].id, id);
+    assert!(!history[0].undone);
+
+    let undone = journal.undo("c1", 1, None, false).await.unwrap();
+    assert_eq!(undone.len(), 1);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "old\n");
+    assert!(journal.history(Some("c1")).await.unwrap()[0].undone);
+
+    // Nothing left to undo
+    assert!(matches!(
+        journal.undo("c1", 1, None, false).await,
+        Err(McpError::ResourceNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_undo_removes_created_file_and_restores_deleted_one() {
+    let temp = TempDir::new().unwrap();
+    let journal = journal(&temp);
+    let created = temp.path().join("created.txt");
+    let deleted = temp.path().join("deleted.txt");
+    fs::write(&deleted, "keep me").unwrap();
+
+    let mut undo = journal.begin("c1", "fs_write_file", "create_new created.txt");
+    undo.snapshot(&created).await;
+    fs::write(&created, "fresh").unwrap();
+    undo.commit().await.unwrap();
+
+    let mut undo = journal.begin("c1", "fs_delete_file", "delete deleted.txt");
+    undo.snapshot(&deleted).await;
+    fs::remove_file(&deleted).unwrap();
+    undo.commit().await.unwrap();
+
+    let undone = journal.undo("c1", 2, None, false).await.unwrap();
+    assert_eq!(undone.len(), 2);
+    assert_eq!(undone[0].tool, "fs_delete_file", "newest first");
+    assert!(!created.exists());
+    assert_eq!(fs::read_to_string(&deleted).unwrap(), "keep me");
+}
+
+#[tokio::test]
+async fn test_undo_restores_deleted_directory_tree() {
+    let temp = TempDir::new().unwrap();
+    let journal = journal(&temp);
+    let dir = temp.path().join("tree");
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("top.txt"), "top").unwrap();
+    fs::write(dir.join("nested/inner.txt"), "inner").unwrap();
+
+    let mut undo = journal.begin("c1", "fs_delete_directory", "delete tree");
+    undo.snapshot(&dir).await;
+    fs::remove_dir_all(&dir).unwrap();
+    undo.commit().await.unwrap();
+
+    journal.undo("c1", 1, None, false).await.unwrap();
+    assert_eq!(fs::read_to_string(dir.join("top.txt")).unwrap(), "top");
+    assert_eq!(
+        fs::read_to_string(dir.join("nested/inner.txt")).unwrap(),
+        "inner"
+    );
+}
+
+#[tokio::test]
+async fn test_undo_reverses_overwriting_move() {
+    let temp = TempDir::new().unwrap();
+    let journal = journal(&temp);
+    let source = temp.path().join("src.txt");
+    let dest = temp.path().join("dest.txt");
+    fs::write(&source, "source").unwrap();
+    fs::write(&dest, "destination").unwrap();
+
+    let mut undo = journal.begin("c1", "fs_move_file", "move src.txt -> dest.txt");
+    undo.snapshot(&dest).await;
+    undo.moved(&source, &dest);
+    fs::rename(&source, &dest).unwrap();
+    undo.commit().await.unwrap();
+
+    journal.undo("c1", 1, None, false).await.unwrap();
+    assert_eq!(fs::read_to_string(&source).unwrap(), "source");
+    assert_eq!(fs::read_to_string(&dest).unwrap(), "destination");
+}
+
+#[tokio::test]
+async fn test_undo_refuses_later_changes_unless_forced() {
+    let temp = TempDir::new().unwrap();
+    let journal = journal(&temp);
+    let path = temp.path().join("a.txt");
+    fs::write(&path, "v1").unwrap();
+
+    let mut undo = journal.begin("c1", "fs_edit_block", "edit a.txt");
+    undo.snapshot(&path).await;
+    fs::write(&path, "v2").unwrap();
+    let id = undo.commit().await.unwrap();
+
+    // Someone else changes the file afterwards
+    fs::write(&path, "v3").unwrap();
+    match journal.undo("c1", 1, Some(&id), false).await {
+        Err(McpError::InvalidArguments(msg)) => assert!(msg.starts_with("Conflict:"), "{msg}"),
+        other => panic!("expected a conflict, got {other:?}"),
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), "v3");
+
+    journal.undo("c1", 1, Some(&id), true).await.unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "v1");
+}
+
+#[tokio::test]
+async fn test_connections_are_isolated() {
+    let temp = TempDir::new().unwrap();
+    let journal = journal(&temp);
+    let path = temp.path().join("a.txt");
+
+    let mut undo = journal.begin("c1", "fs_write_file", "rewrite a.txt");
+    undo.snapshot(&path).await;
+    fs::write(&path, "c1").unwrap();
+    undo.commit().await.unwrap();
+
+    assert!(journal.history(Some("c2")).await.unwrap().is_empty());
+    assert_eq!(journal.history(None).await.unwrap().len(), 1);
+    assert!(journal.undo("c2", 1, None, false).await.is_err());
+    assert!(path.exists());
+}
+
+#[tokio::test]
+async fn test_uncommitted_operation_is_discarded() {
+    let temp = TempDir::new().unwrap();
+    let journal = journal(&temp);
+    let path = temp.path().join("a.txt");
+    fs::write(&path, "data").unwrap();
+
+    let mut undo = journal.begin("c1", "fs_delete_file", "delete a.txt");
+    undo.snapshot(&path).await;
+    drop(undo); // the operation failed
+
+    assert!(journal.history(Some("c1")).await.unwrap().is_empty());
+    assert_eq!(fs::read_dir(journal.root().join("c1")).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn test_prune_keeps_journal_within_size_budget() {
+    let temp = TempDir::new().unwrap();
+    let journal = Journal::new(temp.path().join("journal"), 25, DEFAULT_MAX_AGE);
+
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        let path = temp.path().join(format!("{i}.txt"));
+        fs::write(&path, "0123456789").unwrap();
+        let mut undo = journal.begin("c1", "fs_write_file", format!("rewrite {i}.txt"));
+        undo.snapshot(&path).await;
+        fs::write(&path, "changed").unwrap();
+        ids.push(undo.commit().await.unwrap());
+    }
+
+    let kept: Vec<String> = journal
+        .history(Some("c1"))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|op| op.id)
+        .collect();
+    assert_eq!(kept, vec![ids[2].clone(), ids[1].clone()]);
+}
+
+#[tokio::test]
+async fn test_prune_drops_expired_operations() {
+    let temp = TempDir::new().unwrap();
+    let journal = Journal::new(
+        temp.path().join("journal"),
+        DEFAULT_MAX_BYTES,
+        Duration::ZERO,
+    );
+    let path = temp.path().join("a.txt");
+
+    let mut undo = journal.begin("c1", "fs_write_file", "rewrite a.txt");
+    undo.snapshot(&path).await;
+    fs::write(&path, "x").unwrap();
+    undo.commit().await.unwrap();
+
+    assert!(journal.history(Some("c1")).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_oversized_snapshot_is_not_journaled() {
+    let temp = TempDir::new().unwrap();
+    let journal = Journal::new(temp.path().join("journal"), 4, DEFAULT_MAX_AGE);
+    let path = temp.path().join("big.txt");
+    fs::write(&path, "more than four bytes").unwrap();
+
+    let mut undo = journal.begin("c1", "fs_delete_file", "delete big.txt");
+    undo.snapshot(&path).await;
+    fs::remove_file(&path).unwrap();
+    assert!(undo.commit().await.is_none());
+    assert!(journal.history(None).await.unwrap().is_empty());
+}
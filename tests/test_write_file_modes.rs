@@ -0,0 +1,37 @@
+/*!
+Tests for fs_write_file's prepend and insert_at_line modes
+*/
+
+use kodegen_tools_filesystem::insert_lines;
+
+#[test]
+fn test_insert_before_line() {
+    let text = "one\ntwo\nthree\n";
+    assert_eq!(
+        insert_lines(text, "zero\n", 1).unwrap(),
+        "zero\none\ntwo\nthree\n"
+    );
+    assert_eq!(
+        insert_lines(text, "1.5", 2).unwrap(),
+        "one\n1.5\ntwo\nthree\n"
+    );
+    assert_eq!(
+        insert_lines(text, "four\n", 4).unwrap(),
+        "one\ntwo\nthree\nfour\n"
+    );
+    assert!(insert_lines(text, "x", 5).is_err());
+    assert!(insert_lines(text, "x", 0).is_err());
+}
+
+#[test]
+fn test_insert_keeps_line_structure() {
+    // File without a trailing newline: inserted text starts on its own line
+    assert_eq!(insert_lines("a\nb", "c", 3).unwrap(), "a\nb\nc");
+    // CRLF files get CRLF separators
+    assert_eq!(
+        insert_lines("a\r\nb\r\n", "x", 2).unwrap(),
+        "a\r\nx\r\nb\r\n"
+    );
+    // Empty file
+    assert_eq!(insert_lines("", "first", 1).unwrap(), "first");
+}